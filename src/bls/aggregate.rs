@@ -0,0 +1,177 @@
+//! Aggregation and batch verification for BLS on BN254
+//!
+//! Signatures on the same group add: σ = Σ σ_i verifies with one pairing per
+//! distinct message plus one for σ, however many signers there are.
+//!
+//! Adding public keys as well (`fast_aggregate_verify`, everyone signed the same
+//! message) opens the rogue-key attack: announce pk' = x g - pk_honest and the
+//! aggregate key becomes x g, which the attacker alone can sign for. A proof of
+//! possession (`scheme::prove_possession`) needs the secret key of pk', which nobody
+//! has, so keys must only be aggregated after their PoP checked out.
+//!
+//! Batch verification checks n independent (pk, m, σ) at once. Summing them would
+//! let errors cancel, so each triple is weighted by a random 128-bit scalar first.
+//!
+//! Every public key and signature is subgroup-checked on its own: a sum of points
+//! can land back in the subgroup even when its parts do not.
+
+use ark_bn254::Fr;
+use ark_ec::{CurveGroup, PrimeGroup};
+use ark_ff::Zero;
+
+use super::scheme::{InPrimeSubgroup, Variant, random_scalar};
+
+/// Σ points, for signatures or public keys alike
+pub fn aggregate<G: CurveGroup>(points: &[G]) -> G {
+    points.iter().copied().sum()
+}
+
+/// e(g, σ) = ∏ e(pk_i, H(m_i)) for signers on possibly different messages
+pub fn aggregate_verify<V: Variant>(pks: &[V::PublicKey], msgs: &[&[u8]], sig: &V::Signature) -> bool {
+    if pks.is_empty() || pks.len() != msgs.len() || !sig.in_prime_subgroup() {
+        return false;
+    }
+    if pks.iter().any(|pk| pk.is_zero() || !pk.in_prime_subgroup()) {
+        return false;
+    }
+    let mut pairs = vec![(-V::PublicKey::generator(), *sig)];
+    pairs.extend(pks.iter().zip(msgs).map(|(pk, msg)| (*pk, V::hash_to_signature_group(msg, V::SIG_DST))));
+    V::pairing_product(&pairs).is_zero()
+}
+
+/// Everyone signed `msg`: verify σ against Σ pk_i with two pairings
+///
+/// Only sound when every pk_i passed `verify_possession`, see the module docs.
+pub fn fast_aggregate_verify<V: Variant>(pks: &[V::PublicKey], msg: &[u8], sig: &V::Signature) -> bool {
+    if pks.is_empty() || !pks.iter().all(|pk| pk.in_prime_subgroup()) {
+        return false;
+    }
+    super::scheme::verify::<V>(&aggregate(pks), msg, sig)
+}
+
+/// Random weights for batch verification: 128 bits is enough to make a forged
+/// batch pass with probability 2^-128
+fn batch_weight() -> Fr {
+    random_scalar::<16>()
+}
+
+/// All of (pk_i, m_i, σ_i) are valid, checked with n + 1 pairings:
+/// e(g, Σ r_i σ_i) = ∏ e(r_i pk_i, H(m_i))
+pub fn batch_verify<V: Variant>(items: &[(V::PublicKey, &[u8], V::Signature)]) -> bool {
+    if items.is_empty() {
+        return false;
+    }
+    let valid = |pk: &V::PublicKey, sig: &V::Signature| {
+        !pk.is_zero() && !sig.is_zero() && pk.in_prime_subgroup() && sig.in_prime_subgroup()
+    };
+    if !items.iter().all(|(pk, _, sig)| valid(pk, sig)) {
+        return false;
+    }
+    let weights: Vec<Fr> = items.iter().map(|_| batch_weight()).collect();
+    let combined: V::Signature = items.iter().zip(&weights).map(|((_, _, sig), r)| *sig * r).sum();
+    let mut pairs = vec![(-V::PublicKey::generator(), combined)];
+    pairs.extend(
+        items
+            .iter()
+            .zip(&weights)
+            .map(|((pk, msg, _), r)| (*pk * r, V::hash_to_signature_group(msg, V::SIG_DST))),
+    );
+    V::pairing_product(&pairs).is_zero()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bls::scheme::tests::g2_outside_subgroup;
+    use crate::bls::scheme::{
+        MinPk, MinSig, SecretKey, point_to_bytes, prove_possession, public_key, sign, verify_possession,
+    };
+
+    fn aggregation<V: Variant>() {
+        let keys: Vec<SecretKey> = (0..4).map(|_| SecretKey::random()).collect();
+        let pks: Vec<V::PublicKey> = keys.iter().map(public_key::<V>).collect();
+
+        // Distinct messages
+        let msgs: Vec<&[u8]> = vec![b"block 1", b"block 2", b"block 3", b"block 4"];
+        let sigs: Vec<V::Signature> = keys.iter().zip(&msgs).map(|(sk, m)| sign::<V>(sk, m)).collect();
+        let agg = aggregate(&sigs);
+        assert!(aggregate_verify::<V>(&pks, &msgs, &agg));
+        assert!(!aggregate_verify::<V>(&pks[1..], &msgs[1..], &agg));
+        let swapped: Vec<&[u8]> = vec![b"block 2", b"block 1", b"block 3", b"block 4"];
+        assert!(!aggregate_verify::<V>(&pks, &swapped, &agg));
+
+        // Same message
+        let sigs: Vec<V::Signature> = keys.iter().map(|sk| sign::<V>(sk, b"vote yes")).collect();
+        assert!(fast_aggregate_verify::<V>(&pks, b"vote yes", &aggregate(&sigs)));
+        assert!(!fast_aggregate_verify::<V>(&pks, b"vote no", &aggregate(&sigs)));
+        assert!(!fast_aggregate_verify::<V>(&pks[..3], b"vote yes", &aggregate(&sigs)));
+    }
+
+    #[test]
+    fn test_aggregation() {
+        aggregation::<MinPk>();
+        aggregation::<MinSig>();
+    }
+
+    #[test]
+    fn test_rogue_key_attack_and_pop() {
+        let honest = SecretKey::random();
+        let honest_pk = public_key::<MinPk>(&honest);
+
+        // The attacker knows x but not the secret key of rogue_pk = x g - honest_pk
+        let x = SecretKey::random();
+        let rogue_pk = public_key::<MinPk>(&x) - honest_pk;
+        let forged = sign::<MinPk>(&x, b"honest agrees to pay");
+        assert!(fast_aggregate_verify::<MinPk>(&[honest_pk, rogue_pk], b"honest agrees to pay", &forged));
+
+        // The best the attacker can offer as a PoP for rogue_pk is one made with x
+        let pop = MinPk::hash_to_signature_group(&point_to_bytes(&rogue_pk), MinPk::POP_DST) * x.0;
+        assert!(!verify_possession::<MinPk>(&rogue_pk, &pop));
+        assert!(!verify_possession::<MinPk>(&rogue_pk, &prove_possession::<MinPk>(&x)));
+        assert!(verify_possession::<MinPk>(&honest_pk, &prove_possession::<MinPk>(&honest)));
+    }
+
+    #[test]
+    fn test_keys_outside_subgroup() {
+        // P off the subgroup added to one key and subtracted from another leaves the
+        // sum a valid key, so only checking the aggregate would accept them
+        let bad = g2_outside_subgroup();
+        let (sk1, sk2) = (SecretKey::random(), SecretKey::random());
+        let sig = sign::<MinSig>(&sk1, b"m") + sign::<MinSig>(&sk2, b"m");
+        let pks = [public_key::<MinSig>(&sk1) + bad, public_key::<MinSig>(&sk2) - bad];
+        assert!(crate::bls::scheme::verify::<MinSig>(&aggregate(&pks), b"m", &sig));
+        assert!(!fast_aggregate_verify::<MinSig>(&pks, b"m", &sig));
+        assert!(!aggregate_verify::<MinSig>(&pks, &[b"m", b"m"], &sig));
+        let items: Vec<(_, &[u8], _)> = vec![(pks[0], b"m", sig), (pks[1], b"m", sig)];
+        assert!(!batch_verify::<MinSig>(&items));
+    }
+
+    fn batch<V: Variant>() {
+        let keys: Vec<SecretKey> = (0..5).map(|_| SecretKey::random()).collect();
+        let msgs: Vec<Vec<u8>> = (0..5).map(|i| format!("tx {i}").into_bytes()).collect();
+        let mut items: Vec<(V::PublicKey, &[u8], V::Signature)> = keys
+            .iter()
+            .zip(&msgs)
+            .map(|(sk, m)| (public_key::<V>(sk), m.as_slice(), sign::<V>(sk, m)))
+            .collect();
+        assert!(batch_verify::<V>(&items));
+
+        // Shift δ from one signature to another: the plain sum is unchanged, so an
+        // unweighted batch check would accept two invalid signatures
+        let delta = V::Signature::generator();
+        items[0].2 += delta;
+        items[1].2 -= delta;
+        assert!(aggregate_verify::<V>(
+            &items.iter().map(|(pk, _, _)| *pk).collect::<Vec<_>>(),
+            &items.iter().map(|(_, m, _)| *m).collect::<Vec<_>>(),
+            &aggregate(&items.iter().map(|(_, _, s)| *s).collect::<Vec<_>>()),
+        ));
+        assert!(!batch_verify::<V>(&items));
+    }
+
+    #[test]
+    fn test_batch_verify() {
+        batch::<MinPk>();
+        batch::<MinSig>();
+    }
+}
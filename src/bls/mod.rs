@@ -0,0 +1,2 @@
+pub mod scheme;
+pub mod aggregate;
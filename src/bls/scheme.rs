@@ -0,0 +1,231 @@
+//! BLS signatures on BN254 (draft-irtf-cfrg-bls-signature, proof-of-possession scheme)
+//!
+//! sk is a scalar, pk = sk g and the signature is sk H(m), where H hashes into the
+//! other group. Verification is one pairing equation:
+//!   e(g, sk H(m)) = e(sk g, H(m))
+//! Signatures are unique (no nonce at all) and add up: the sum of signatures
+//! verifies against the matching pairing product, see `aggregate`.
+//!
+//! Which group gets what is the variant:
+//! - `MinPk`: pk in G1 (32 bytes), signature in G2 (64 bytes), H = hash to G2
+//! - `MinSig`: signature in G1, pk in G2, H = `hash_to_bn254_g1`
+//!
+//! Points that did not come out of `deserialize_compressed` (which validates) may be
+//! off the curve or, in G2 with its large cofactor, outside the order r subgroup,
+//! where pairing equations say nothing. Every verification function checks them with
+//! `InPrimeSubgroup` before pairing.
+//!
+//! BN254 gives about 100 bits of security since the exTNFS improvements, fine for
+//! teaching and for Ethereum's precompiles, not for new deployments.
+
+use std::fmt;
+
+use ark_bn254::{Bn254, Fr, G1Projective, G2Projective};
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
+use ark_ec::{AffineRepr, CurveGroup, PrimeGroup};
+use ark_ff::{PrimeField, Zero};
+use ark_serialize::CanonicalSerialize;
+use rand::{RngCore, rng};
+
+use crate::elliptic_point::hash_to_curve::{hash_to_bn254_g1, hash_to_bn254_g2};
+
+/// On the curve and in the order r subgroup
+pub trait InPrimeSubgroup {
+    fn in_prime_subgroup(&self) -> bool;
+}
+
+impl<P: SWCurveConfig> InPrimeSubgroup for Projective<P> {
+    fn in_prime_subgroup(&self) -> bool {
+        let affine = self.into_affine();
+        affine.is_on_curve() && affine.is_in_correct_subgroup_assuming_on_curve()
+    }
+}
+
+/// Where public keys and signatures live, and how to hash and pair them
+pub trait Variant {
+    type PublicKey: CurveGroup<ScalarField = Fr> + InPrimeSubgroup;
+    type Signature: CurveGroup<ScalarField = Fr> + InPrimeSubgroup;
+    /// Ciphersuite tag for messages
+    const SIG_DST: &'static [u8];
+    /// Separate tag for proofs of possession, so a PoP is never a valid message signature
+    const POP_DST: &'static [u8];
+
+    fn hash_to_signature_group(msg: &[u8], dst: &[u8]) -> Self::Signature;
+
+    /// ∏ e(pk_i, sig_i), with whichever of the two is in G1 passed first
+    fn pairing_product(pairs: &[(Self::PublicKey, Self::Signature)]) -> PairingOutput<Bn254>;
+}
+
+/// Public keys in G1, signatures in G2
+#[derive(Debug, Clone, Copy)]
+pub struct MinPk;
+
+/// Signatures in G1, public keys in G2
+#[derive(Debug, Clone, Copy)]
+pub struct MinSig;
+
+impl Variant for MinPk {
+    type PublicKey = G1Projective;
+    type Signature = G2Projective;
+    const SIG_DST: &'static [u8] = b"BLS_SIG_BN254G2_XMD:SHA-256_TAI_POP_";
+    const POP_DST: &'static [u8] = b"BLS_POP_BN254G2_XMD:SHA-256_TAI_POP_";
+
+    fn hash_to_signature_group(msg: &[u8], dst: &[u8]) -> G2Projective {
+        hash_to_bn254_g2(msg, dst).into_group()
+    }
+
+    fn pairing_product(pairs: &[(G1Projective, G2Projective)]) -> PairingOutput<Bn254> {
+        let (g1, g2): (Vec<_>, Vec<_>) = pairs.iter().map(|(pk, sig)| (pk.into_affine(), sig.into_affine())).unzip();
+        Bn254::multi_pairing(g1, g2)
+    }
+}
+
+impl Variant for MinSig {
+    type PublicKey = G2Projective;
+    type Signature = G1Projective;
+    const SIG_DST: &'static [u8] = b"BLS_SIG_BN254G1_XMD:SHA-256_SVDW_RO_POP_";
+    const POP_DST: &'static [u8] = b"BLS_POP_BN254G1_XMD:SHA-256_SVDW_RO_POP_";
+
+    fn hash_to_signature_group(msg: &[u8], dst: &[u8]) -> G1Projective {
+        hash_to_bn254_g1(msg, dst).into_group()
+    }
+
+    fn pairing_product(pairs: &[(G2Projective, G1Projective)]) -> PairingOutput<Bn254> {
+        let (g1, g2): (Vec<_>, Vec<_>) = pairs.iter().map(|(pk, sig)| (sig.into_affine(), pk.into_affine())).unzip();
+        Bn254::multi_pairing(g1, g2)
+    }
+}
+
+/// Not `Copy`, so every duplicate is an explicit `clone`
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretKey(pub Fr);
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SecretKey").field(&"<redacted>").finish()
+    }
+}
+
+/// N random bytes reduced mod r
+pub(crate) fn random_scalar<const N: usize>() -> Fr {
+    let mut bytes = [0u8; N];
+    rng().fill_bytes(&mut bytes);
+    Fr::from_le_bytes_mod_order(&bytes)
+}
+
+impl SecretKey {
+    /// 64 random bytes reduced mod r, so the bias is negligible
+    pub fn random() -> Self {
+        loop {
+            let sk = random_scalar::<64>();
+            if !sk.is_zero() {
+                return Self(sk);
+            }
+        }
+    }
+}
+
+/// Compressed encoding, what proofs of possession and aggregation hashes sign over
+pub fn point_to_bytes<G: CurveGroup>(point: &G) -> Vec<u8> {
+    let mut out = Vec::new();
+    point.into_affine().serialize_compressed(&mut out).expect("writing to a Vec cannot fail");
+    out
+}
+
+pub fn public_key<V: Variant>(sk: &SecretKey) -> V::PublicKey {
+    V::PublicKey::generator() * sk.0
+}
+
+pub fn sign<V: Variant>(sk: &SecretKey, msg: &[u8]) -> V::Signature {
+    V::hash_to_signature_group(msg, V::SIG_DST) * sk.0
+}
+
+/// e(g, sig) = e(pk, H(m)), checked as e(-g, sig) e(pk, H(m)) = 1
+fn verify_with_dst<V: Variant>(pk: &V::PublicKey, msg: &[u8], sig: &V::Signature, dst: &[u8]) -> bool {
+    // The identity key verifies the identity signature on every message
+    if pk.is_zero() || sig.is_zero() || !pk.in_prime_subgroup() || !sig.in_prime_subgroup() {
+        return false;
+    }
+    let hashed = V::hash_to_signature_group(msg, dst);
+    V::pairing_product(&[(-V::PublicKey::generator(), *sig), (*pk, hashed)]).is_zero()
+}
+
+pub fn verify<V: Variant>(pk: &V::PublicKey, msg: &[u8], sig: &V::Signature) -> bool {
+    verify_with_dst::<V>(pk, msg, sig, V::SIG_DST)
+}
+
+/// Sign your own public key under the PoP tag, which needs sk
+pub fn prove_possession<V: Variant>(sk: &SecretKey) -> V::Signature {
+    let pk = public_key::<V>(sk);
+    V::hash_to_signature_group(&point_to_bytes(&pk), V::POP_DST) * sk.0
+}
+
+pub fn verify_possession<V: Variant>(pk: &V::PublicKey, proof: &V::Signature) -> bool {
+    verify_with_dst::<V>(pk, &point_to_bytes(pk), proof, V::POP_DST)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    fn round_trip<V: Variant>() {
+        let sk = SecretKey::random();
+        let pk = public_key::<V>(&sk);
+        let sig = sign::<V>(&sk, b"hello");
+        assert!(verify::<V>(&pk, b"hello", &sig));
+        // Unique: signing again gives the same point
+        assert_eq!(sig, sign::<V>(&sk, b"hello"));
+        assert!(!verify::<V>(&pk, b"hellp", &sig));
+        assert!(!verify::<V>(&public_key::<V>(&SecretKey::random()), b"hello", &sig));
+        assert!(!verify::<V>(&V::PublicKey::zero(), b"hello", &V::Signature::zero()));
+
+        let proof = prove_possession::<V>(&sk);
+        assert!(verify_possession::<V>(&pk, &proof));
+        // Different tags: the PoP does not double as a signature on the key bytes
+        assert!(!verify::<V>(&pk, &point_to_bytes(&pk), &proof));
+        assert!(format!("{sk:?}").contains("<redacted>"));
+    }
+
+    #[test]
+    fn test_min_pk() {
+        round_trip::<MinPk>();
+        let sk = SecretKey::random();
+        assert_eq!(point_to_bytes(&public_key::<MinPk>(&sk)).len(), 32);
+        assert_eq!(point_to_bytes(&sign::<MinPk>(&sk, b"m")).len(), 64);
+    }
+
+    #[test]
+    fn test_min_sig() {
+        round_trip::<MinSig>();
+        let sk = SecretKey::random();
+        assert_eq!(point_to_bytes(&public_key::<MinSig>(&sk)).len(), 64);
+        assert_eq!(point_to_bytes(&sign::<MinSig>(&sk, b"m")).len(), 32);
+    }
+
+    /// A point on the G2 curve outside the order r subgroup
+    pub(crate) fn g2_outside_subgroup() -> G2Projective {
+        use ark_bn254::{Fq2, G2Affine};
+        (1u64..)
+            .filter_map(|x| G2Affine::get_point_from_x_unchecked(Fq2::from(x), false))
+            .find(|p| !p.is_in_correct_subgroup_assuming_on_curve())
+            .unwrap()
+            .into_group()
+    }
+
+    #[test]
+    fn test_rejects_points_outside_subgroup() {
+        use ark_bn254::{Fq, G1Affine};
+        let sk = SecretKey::random();
+        let bad_g2 = g2_outside_subgroup();
+        assert!(!bad_g2.in_prime_subgroup());
+        assert!(!verify::<MinPk>(&public_key::<MinPk>(&sk), b"m", &bad_g2));
+        assert!(!verify::<MinSig>(&bad_g2, b"m", &sign::<MinSig>(&sk, b"m")));
+
+        // G1 has cofactor 1, so there the only bad points are off the curve
+        let off_curve = G1Affine::new_unchecked(Fq::from(1u64), Fq::from(1u64)).into_group();
+        assert!(!off_curve.in_prime_subgroup());
+        assert!(!verify::<MinSig>(&public_key::<MinSig>(&sk), b"m", &off_curve));
+        assert!(public_key::<MinPk>(&sk).in_prime_subgroup());
+    }
+}
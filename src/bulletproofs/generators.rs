@@ -0,0 +1,39 @@
+//! Nothing-up-my-sleeve generators for Bulletproofs
+//!
+//! The range proof commits to bit vectors with G_0.., H_0.. and to values with the
+//! Pedersen pair (g, h) of `perdesen_commitment`, so the commitments it proves things
+//! about are ordinary `Commitment`s. Soundness needs every discrete log relation
+//! between all of these to be unknown, so each one is hashed to the curve.
+//!
+//! G_i are the `VectorParams` generators under the Pedersen DST, H_i the same
+//! construction under a DST of their own.
+
+use ark_bn254::G1Affine;
+
+use crate::elliptic_point::hash_to_curve::PEDERSEN_DST;
+use crate::perdesen_commitment::{PedersenParams, VectorParams};
+
+/// Separates H_i from G_i, which are hashed from the same labels
+const H_VEC_DST: &[u8] = b"BULLETPROOFS-H-VEC-V01-with-BN254G1_XMD:SHA-256_SVDW_RO_";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulletproofGens {
+    pub g_vec: Vec<G1Affine>,
+    pub h_vec: Vec<G1Affine>,
+    pub pedersen: PedersenParams,
+}
+
+impl BulletproofGens {
+    /// Enough generators for `capacity` bits in total (bits per value * values)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            g_vec: VectorParams::setup(capacity, PEDERSEN_DST).gens,
+            h_vec: VectorParams::setup(capacity, H_VEC_DST).gens,
+            pedersen: PedersenParams::default(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.g_vec.len()
+    }
+}
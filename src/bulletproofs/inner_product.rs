@@ -0,0 +1,192 @@
+//! The Bulletproofs inner-product argument
+//!
+//! Proves knowledge of vectors a, b of length n (a power of two) with
+//!   P = <a, G> + <b, H> + <a, b> Q
+//! in 2 log n points plus two scalars. Each round splits the vectors in halves, sends
+//! the cross terms
+//!   L = <a_lo, G_hi> + <b_hi, H_lo> + <a_lo, b_hi> Q
+//!   R = <a_hi, G_lo> + <b_lo, H_hi> + <a_hi, b_lo> Q
+//! and folds with a challenge u:
+//!   a' = u a_lo + u^-1 a_hi,  b' = u^-1 b_lo + u b_hi
+//!   G' = u^-1 G_lo + u G_hi,  H' = u H_lo + u^-1 H_hi
+//! so P' = P + u^2 L + u^-2 R is the same statement at half the size.
+//!
+//! The verifier never folds the generators round by round: the final G is Σ s_i G_i,
+//! where s_i multiplies u_j or u_j^-1 depending on bit j of i, so the whole check is
+//! one multi-scalar multiplication.
+//!
+//! Both sides bind n, Q and P to the transcript before the first round, so a proof is
+//! only valid for the statement it was made for even when the caller has not.
+
+use ark_bn254::{Fr, G1Affine, G1Projective};
+use ark_ec::{CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::scalar_utils::inner_product;
+use crate::transcript::Transcript;
+
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct InnerProductProof {
+    pub l_vec: Vec<G1Affine>,
+    pub r_vec: Vec<G1Affine>,
+    pub a: Fr,
+    pub b: Fr,
+}
+
+pub fn msm(bases: &[G1Affine], scalars: &[Fr]) -> G1Projective {
+    G1Projective::msm(bases, scalars).expect("as many bases as scalars")
+}
+
+/// lo * x + hi * y entry-wise, for the folded generators
+fn fold_points(lo: &[G1Affine], hi: &[G1Affine], x: Fr, y: Fr) -> Vec<G1Affine> {
+    let folded: Vec<G1Projective> = lo.iter().zip(hi).map(|(l, h)| *l * x + *h * y).collect();
+    G1Projective::normalize_batch(&folded)
+}
+
+fn fold_scalars(lo: &[Fr], hi: &[Fr], x: Fr, y: Fr) -> Vec<Fr> {
+    lo.iter().zip(hi).map(|(l, h)| *l * x + *h * y).collect()
+}
+
+fn bind_statement(transcript: &mut Transcript, n: usize, q: &G1Affine, p: &G1Projective) {
+    transcript.append_u64(b"ipa n", n as u64);
+    transcript.append(b"ipa Q", q);
+    transcript.append(b"ipa P", &p.into_affine());
+}
+
+impl InnerProductProof {
+    pub fn prove(
+        transcript: &mut Transcript,
+        q: &G1Affine,
+        mut g: Vec<G1Affine>,
+        mut h: Vec<G1Affine>,
+        mut a: Vec<Fr>,
+        mut b: Vec<Fr>,
+    ) -> Result<Self, &'static str> {
+        let n = g.len();
+        if !n.is_power_of_two() || h.len() != n || a.len() != n || b.len() != n {
+            return Err("inner-product vectors must all have the same power of two length");
+        }
+        let p = msm(&g, &a) + msm(&h, &b) + *q * inner_product(&a, &b);
+        bind_statement(transcript, n, q, &p);
+        let (mut l_vec, mut r_vec) = (Vec::new(), Vec::new());
+
+        while a.len() > 1 {
+            let half = a.len() / 2;
+            let (a_lo, a_hi) = a.split_at(half);
+            let (b_lo, b_hi) = b.split_at(half);
+            let (g_lo, g_hi) = g.split_at(half);
+            let (h_lo, h_hi) = h.split_at(half);
+
+            let c_l = inner_product(a_lo, b_hi);
+            let c_r = inner_product(a_hi, b_lo);
+            let l = msm(g_hi, a_lo) + msm(h_lo, b_hi) + *q * c_l;
+            let r = msm(g_lo, a_hi) + msm(h_hi, b_lo) + *q * c_r;
+            let (l, r) = (l.into_affine(), r.into_affine());
+            transcript.append(b"L", &l);
+            transcript.append(b"R", &r);
+            l_vec.push(l);
+            r_vec.push(r);
+
+            let u = transcript.challenge_scalar(b"u");
+            let u_inv = u.inverse().ok_or("zero challenge")?;
+            (a, b, g, h) = (
+                fold_scalars(a_lo, a_hi, u, u_inv),
+                fold_scalars(b_lo, b_hi, u_inv, u),
+                fold_points(g_lo, g_hi, u_inv, u),
+                fold_points(h_lo, h_hi, u, u_inv),
+            );
+        }
+        Ok(Self { l_vec, r_vec, a: a[0], b: b[0] })
+    }
+
+    /// Check P = <a, G> + <b, H> + <a, b> Q for the a, b the prover knows
+    pub fn verify(
+        &self,
+        transcript: &mut Transcript,
+        q: &G1Affine,
+        g: &[G1Affine],
+        h: &[G1Affine],
+        p: &G1Projective,
+    ) -> Result<(), &'static str> {
+        let n = g.len();
+        let rounds = n.trailing_zeros() as usize;
+        if !n.is_power_of_two() || h.len() != n {
+            return Err("inner-product generators must have the same power of two length");
+        }
+        if self.l_vec.len() != rounds || self.r_vec.len() != rounds {
+            return Err("wrong number of inner-product rounds");
+        }
+        bind_statement(transcript, n, q, p);
+        let mut challenges = Vec::with_capacity(rounds);
+        for (l, r) in self.l_vec.iter().zip(&self.r_vec) {
+            transcript.append(b"L", l);
+            transcript.append(b"R", r);
+            let u = transcript.challenge_scalar(b"u");
+            challenges.push((u, u.inverse().ok_or("zero challenge")?));
+        }
+
+        // Round j splits on bit (rounds - 1 - j) of the index: the high half gets u_j
+        let s: Vec<Fr> = (0..n)
+            .map(|i| {
+                challenges.iter().enumerate().fold(Fr::ONE, |acc, (j, (u, u_inv))| {
+                    if (i >> (rounds - 1 - j)) & 1 == 1 { acc * u } else { acc * u_inv }
+                })
+            })
+            .collect();
+        let mut s_inv = s.clone();
+        ark_ff::batch_inversion(&mut s_inv);
+
+        let a_s: Vec<Fr> = s.iter().map(|si| self.a * si).collect();
+        let b_s: Vec<Fr> = s_inv.iter().map(|si| self.b * si).collect();
+        let lhs = msm(g, &a_s) + msm(h, &b_s) + *q * (self.a * self.b);
+
+        let mut bases = vec![];
+        let mut scalars = vec![];
+        for ((l, r), (u, u_inv)) in self.l_vec.iter().zip(&self.r_vec).zip(&challenges) {
+            bases.extend([*l, *r]);
+            scalars.extend([u.square(), u_inv.square()]);
+        }
+        let rhs = *p + msm(&bases, &scalars);
+        if (lhs - rhs).is_zero() { Ok(()) } else { Err("inner-product proof does not verify") }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bulletproofs::generators::BulletproofGens;
+    use crate::perdesen_commitment::random_scalar;
+
+    #[test]
+    fn test_inner_product_argument() {
+        let gens = BulletproofGens::new(8);
+        let q = gens.pedersen.g.into_affine();
+        let a: Vec<Fr> = (0..8).map(|_| random_scalar()).collect();
+        let b: Vec<Fr> = (0..8).map(|_| random_scalar()).collect();
+        let p = msm(&gens.g_vec, &a) + msm(&gens.h_vec, &b) + q * inner_product(&a, &b);
+
+        let proof = InnerProductProof::prove(
+            &mut Transcript::new(b"ipa test"),
+            &q,
+            gens.g_vec.clone(),
+            gens.h_vec.clone(),
+            a.clone(),
+            b.clone(),
+        )
+        .unwrap();
+        assert_eq!(proof.l_vec.len(), 3);
+        assert_eq!(proof.verify(&mut Transcript::new(b"ipa test"), &q, &gens.g_vec, &gens.h_vec, &p), Ok(()));
+
+        // Different statement, different transcript, or a tampered proof
+        let wrong_p = p + q;
+        assert!(proof.verify(&mut Transcript::new(b"ipa test"), &q, &gens.g_vec, &gens.h_vec, &wrong_p).is_err());
+        assert!(proof.verify(&mut Transcript::new(b"other"), &q, &gens.g_vec, &gens.h_vec, &p).is_err());
+        let mut tampered = proof.clone();
+        tampered.a += Fr::ONE;
+        assert!(tampered.verify(&mut Transcript::new(b"ipa test"), &q, &gens.g_vec, &gens.h_vec, &p).is_err());
+        let mut swapped = proof;
+        swapped.l_vec.swap(0, 1);
+        assert!(swapped.verify(&mut Transcript::new(b"ipa test"), &q, &gens.g_vec, &gens.h_vec, &p).is_err());
+    }
+}
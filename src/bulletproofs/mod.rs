@@ -0,0 +1,3 @@
+pub mod generators;
+pub mod inner_product;
+pub mod range_proof;
@@ -0,0 +1,405 @@
+//! Bulletproofs range proofs (Bünz, Bootle, Boneh, Poelstra, Wuille, Maxwell 2018)
+//!
+//! Proves that Pedersen commitments V_j = v_j g + γ_j h hide values in [0, 2^n)
+//! without revealing them, in 2 log(n m) + 9 group elements and scalars for m values
+//! at once. The statement is that a_L, the bits of all the v_j, satisfies
+//!   <a_L, 2^n> = v_j per block,  a_L ∘ a_R = 0,  a_R = a_L - 1
+//! Random challenges y, z fold all of that into one inner product
+//!   t(X) = <l(X), r(X)>
+//!   l(X) = a_L - z 1 + s_L X
+//!   r(X) = y^(nm) ∘ (a_R + z 1 + s_R X) + Σ_j z^(2+j) (0 .. 2^n .. 0)
+//! whose constant term is Σ z^(2+j) v_j + δ(y, z) exactly when the bits are right.
+//! The prover commits to t_1 and t_2, opens t(x) at a challenge x, and proves
+//! t(x) = <l(x), r(x)> with the inner-product argument instead of sending l and r.
+//!
+//! Everything is made non-interactive with `Transcript`: the caller passes one in
+//! and can bind whatever context it likes (a transaction, a protocol label) first.
+
+use ark_bn254::{Fr, G1Affine, G1Projective};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use super::generators::BulletproofGens;
+use super::inner_product::{InnerProductProof, msm};
+use crate::conversion::read_length_prefix;
+use crate::perdesen_commitment::{Commitment, Opening, random_scalar};
+use crate::scalar_utils::{inner_product, powers};
+use crate::transcript::Transcript;
+
+/// Encoded size of `a` through `mu`, which come before the inner-product proof
+const HEADER_LEN: usize = 7 * 32;
+
+/// 4 points, 3 scalars and the inner-product proof, 688 bytes for one 64-bit value
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RangeProof {
+    pub a: G1Affine,
+    pub s: G1Affine,
+    pub t1: G1Affine,
+    pub t2: G1Affine,
+    pub t_hat: Fr,
+    pub tau_x: Fr,
+    pub mu: Fr,
+    pub ipp: InnerProductProof,
+}
+
+/// Public inputs shared by prover and verifier
+fn check_sizes(gens: &BulletproofGens, bits: usize, m: usize) -> Result<(), &'static str> {
+    if ![8, 16, 32, 64].contains(&bits) {
+        return Err("bit size must be 8, 16, 32 or 64");
+    }
+    if !m.is_power_of_two() {
+        return Err("number of values must be a power of two");
+    }
+    if bits * m > gens.capacity() {
+        return Err("not enough generators for this many bits");
+    }
+    Ok(())
+}
+
+fn start(transcript: &mut Transcript, bits: usize, commitments: &[Commitment]) {
+    transcript.append_message(b"dom-sep", b"rangeproof v1");
+    transcript.append_u64(b"n", bits as u64);
+    transcript.append_u64(b"m", commitments.len() as u64);
+    for v in commitments {
+        transcript.append(b"V", &v.point);
+    }
+}
+
+/// y^-i H_i, so that <y^(nm) ∘ x, H'> = <x, H>
+fn scaled_h(h: &[G1Affine], y: Fr) -> Result<Vec<G1Affine>, &'static str> {
+    let y_inv = y.inverse().ok_or("zero challenge")?;
+    let scaled: Vec<G1Projective> = h.iter().zip(powers(y_inv, h.len())).map(|(h, c)| *h * c).collect();
+    Ok(G1Projective::normalize_batch(&scaled))
+}
+
+/// z^(2+j) for each value j
+fn z_powers(z: Fr, m: usize) -> Vec<Fr> {
+    powers(z, m).into_iter().map(|zj| zj * z.square()).collect()
+}
+
+/// δ(y, z) = (z - z^2) <1, y^(nm)> - Σ_j z^(3+j) <1, 2^n>
+fn delta(y: Fr, z: Fr, bits: usize, m: usize) -> Fr {
+    let sum_y: Fr = powers(y, bits * m).into_iter().sum();
+    let sum_2: Fr = powers(Fr::from(2u64), bits).into_iter().sum();
+    let sum_z: Fr = z_powers(z, m).into_iter().map(|zj| zj * z).sum();
+    (z - z.square()) * sum_y - sum_z * sum_2
+}
+
+impl RangeProof {
+    /// Commit to `value` with `blinding` and prove it lies in [0, 2^bits)
+    pub fn prove_single(
+        gens: &BulletproofGens,
+        transcript: &mut Transcript,
+        value: u64,
+        blinding: Fr,
+        bits: usize,
+    ) -> Result<(Self, Commitment), &'static str> {
+        let (proof, commitments) = Self::prove_multiple(gens, transcript, &[value], &[blinding], bits)?;
+        Ok((proof, commitments[0]))
+    }
+
+    /// One aggregated proof for several values, much shorter than separate proofs
+    pub fn prove_multiple(
+        gens: &BulletproofGens,
+        transcript: &mut Transcript,
+        values: &[u64],
+        blindings: &[Fr],
+        bits: usize,
+    ) -> Result<(Self, Vec<Commitment>), &'static str> {
+        let m = values.len();
+        check_sizes(gens, bits, m)?;
+        if blindings.len() != m {
+            return Err("one blinding factor per value");
+        }
+        if bits < 64 && values.iter().any(|v| v >> bits != 0) {
+            return Err("value out of range");
+        }
+        let pedersen = &gens.pedersen;
+        let commitments: Vec<Commitment> = values
+            .iter()
+            .zip(blindings)
+            .map(|(v, blinding)| pedersen.commit(&Opening { value: Fr::from(*v), blinding: *blinding }))
+            .collect();
+        start(transcript, bits, &commitments);
+
+        let nm = bits * m;
+        let (g, h) = (&gens.g_vec[..nm], &gens.h_vec[..nm]);
+        let a_l: Vec<Fr> = (0..nm).map(|i| Fr::from((values[i / bits] >> (i % bits)) & 1)).collect();
+        let a_r: Vec<Fr> = a_l.iter().map(|bit| *bit - Fr::ONE).collect();
+        let alpha = random_scalar();
+        let a = (msm(g, &a_l) + msm(h, &a_r) + pedersen.h * alpha).into_affine();
+
+        let s_l: Vec<Fr> = (0..nm).map(|_| random_scalar()).collect();
+        let s_r: Vec<Fr> = (0..nm).map(|_| random_scalar()).collect();
+        let rho = random_scalar();
+        let s = (msm(g, &s_l) + msm(h, &s_r) + pedersen.h * rho).into_affine();
+
+        transcript.append(b"A", &a);
+        transcript.append(b"S", &s);
+        let y = transcript.challenge_scalar(b"y");
+        let z = transcript.challenge_scalar(b"z");
+
+        // l(X) = l0 + l1 X and r(X) = r0 + r1 X
+        let y_pows = powers(y, nm);
+        let two_pows = powers(Fr::from(2u64), bits);
+        let zz = z_powers(z, m);
+        let l0: Vec<Fr> = a_l.iter().map(|x| *x - z).collect();
+        let r0: Vec<Fr> = (0..nm).map(|i| y_pows[i] * (a_r[i] + z) + zz[i / bits] * two_pows[i % bits]).collect();
+        let r1: Vec<Fr> = (0..nm).map(|i| y_pows[i] * s_r[i]).collect();
+        let t1 = inner_product(&l0, &r1) + inner_product(&s_l, &r0);
+        let t2 = inner_product(&s_l, &r1);
+
+        let (tau1, tau2) = (random_scalar(), random_scalar());
+        let t1_point = pedersen.commit(&Opening { value: t1, blinding: tau1 }).point;
+        let t2_point = pedersen.commit(&Opening { value: t2, blinding: tau2 }).point;
+        transcript.append(b"T1", &t1_point);
+        transcript.append(b"T2", &t2_point);
+        let x = transcript.challenge_scalar(b"x");
+
+        let l: Vec<Fr> = l0.iter().zip(&s_l).map(|(l0, l1)| *l0 + *l1 * x).collect();
+        let r: Vec<Fr> = r0.iter().zip(&r1).map(|(r0, r1)| *r0 + *r1 * x).collect();
+        let t_hat = inner_product(&l, &r);
+        let tau_x = tau2 * x.square() + tau1 * x + inner_product(&zz, blindings);
+        let mu = alpha + rho * x;
+        transcript.append(b"t_hat", &t_hat);
+        transcript.append(b"tau_x", &tau_x);
+        transcript.append(b"mu", &mu);
+
+        let w = transcript.challenge_scalar(b"w");
+        let q = (pedersen.g * w).into_affine();
+        let ipp = InnerProductProof::prove(transcript, &q, g.to_vec(), scaled_h(h, y)?, l, r)?;
+
+        let proof = Self { a, s, t1: t1_point, t2: t2_point, t_hat, tau_x, mu, ipp };
+        Ok((proof, commitments))
+    }
+
+    pub fn verify_single(
+        &self,
+        gens: &BulletproofGens,
+        transcript: &mut Transcript,
+        commitment: &Commitment,
+        bits: usize,
+    ) -> Result<(), &'static str> {
+        self.verify_multiple(gens, transcript, &[*commitment], bits)
+    }
+
+    pub fn verify_multiple(
+        &self,
+        gens: &BulletproofGens,
+        transcript: &mut Transcript,
+        commitments: &[Commitment],
+        bits: usize,
+    ) -> Result<(), &'static str> {
+        let m = commitments.len();
+        check_sizes(gens, bits, m)?;
+        start(transcript, bits, commitments);
+        let pedersen = &gens.pedersen;
+        let nm = bits * m;
+        let (g, h) = (&gens.g_vec[..nm], &gens.h_vec[..nm]);
+
+        transcript.append(b"A", &self.a);
+        transcript.append(b"S", &self.s);
+        let y = transcript.challenge_scalar(b"y");
+        let z = transcript.challenge_scalar(b"z");
+        transcript.append(b"T1", &self.t1);
+        transcript.append(b"T2", &self.t2);
+        let x = transcript.challenge_scalar(b"x");
+        transcript.append(b"t_hat", &self.t_hat);
+        transcript.append(b"tau_x", &self.tau_x);
+        transcript.append(b"mu", &self.mu);
+        let w = transcript.challenge_scalar(b"w");
+
+        // t_hat g + tau_x h = Σ z^(2+j) V_j + δ(y, z) g + x T1 + x^2 T2
+        let zz = z_powers(z, m);
+        let lhs = pedersen.commit(&Opening { value: self.t_hat, blinding: self.tau_x });
+        let points: Vec<G1Affine> = commitments.iter().map(|c| c.point).chain([self.t1, self.t2]).collect();
+        let mut scalars = zz.clone();
+        scalars.extend([x, x.square()]);
+        let rhs = msm(&points, &scalars) + pedersen.g * delta(y, z, bits, m);
+        if lhs != Commitment::from(rhs) {
+            return Err("t_hat does not match the committed polynomial");
+        }
+
+        // P = A + x S - z <1, G> + <z y^(nm) + Σ_j z^(2+j) 2^n, H'> - mu h + t_hat Q
+        let h_prime = scaled_h(h, y)?;
+        let y_pows = powers(y, nm);
+        let two_pows = powers(Fr::from(2u64), bits);
+        let h_scalars: Vec<Fr> = (0..nm).map(|i| z * y_pows[i] + zz[i / bits] * two_pows[i % bits]).collect();
+        let q = (pedersen.g * w).into_affine();
+        let g_sum: G1Projective = g.iter().map(|gi| gi.into_group()).sum();
+        let p = self.a.into_group() + self.s * x - g_sum * z + msm(&h_prime, &h_scalars) - pedersen.h * self.mu
+            + q * self.t_hat;
+        self.ipp.verify(transcript, &q, g, &h_prime, &p)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.serialize_compressed(&mut out).expect("writing to a Vec cannot fail");
+        out
+    }
+
+    /// Rejects points off the curve, trailing garbage and proofs with more rounds than
+    /// `gens` has room for
+    pub fn from_bytes(gens: &BulletproofGens, bytes: &[u8]) -> Result<Self, &'static str> {
+        // Bound L and R first, see `read_length_prefix`
+        let max_rounds = gens.capacity().checked_ilog2().ok_or("no generators")? as usize;
+        let rounds = read_length_prefix(bytes, HEADER_LEN)?;
+        if rounds > max_rounds as u64 {
+            return Err("more inner-product rounds than the generators allow");
+        }
+        if read_length_prefix(bytes, HEADER_LEN + 8 + 32 * rounds as usize)? != rounds {
+            return Err("L and R have different lengths");
+        }
+        let mut reader = bytes;
+        let proof = Self::deserialize_compressed(&mut reader).map_err(|_| "invalid range proof encoding")?;
+        if !reader.is_empty() {
+            return Err("trailing bytes after range proof");
+        }
+        Ok(proof)
+    }
+}
+
+pub fn entry_point() {
+    println!("--- BULLETPROOFS ---");
+    let gens = BulletproofGens::new(64 * 4);
+    let blinding = random_scalar();
+    let (proof, commitment) =
+        RangeProof::prove_single(&gens, &mut Transcript::new(b"demo"), 1_000_000, blinding, 64).unwrap();
+    let ok = proof.verify_single(&gens, &mut Transcript::new(b"demo"), &commitment, 64).is_ok();
+    println!("64-bit range proof: {} bytes, verifies: {ok}", proof.to_bytes().len());
+
+    let values = [5u64, 1 << 40, 0, u64::MAX];
+    let blindings: Vec<Fr> = values.iter().map(|_| random_scalar()).collect();
+    let (proof, commitments) =
+        RangeProof::prove_multiple(&gens, &mut Transcript::new(b"demo"), &values, &blindings, 64).unwrap();
+    let ok = proof.verify_multiple(&gens, &mut Transcript::new(b"demo"), &commitments, 64).is_ok();
+    println!("aggregated proof for 4 values: {} bytes, verifies: {ok}", proof.to_bytes().len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_range_proof() {
+        let gens = BulletproofGens::new(64);
+        for (value, bits) in [(0u64, 8), (255, 8), (12345, 16), (u64::MAX, 64)] {
+            let blinding = random_scalar();
+            let (proof, v) =
+                RangeProof::prove_single(&gens, &mut Transcript::new(b"test"), value, blinding, bits).unwrap();
+            // The commitment is an ordinary Pedersen commitment to the value
+            assert!(gens.pedersen.verify(&v, &Opening { value: Fr::from(value), blinding }));
+            assert_eq!(proof.verify_single(&gens, &mut Transcript::new(b"test"), &v, bits), Ok(()));
+            // The verifier has to use the same context and bit size
+            assert!(proof.verify_single(&gens, &mut Transcript::new(b"other"), &v, bits).is_err());
+            if bits < 64 {
+                assert!(proof.verify_single(&gens, &mut Transcript::new(b"test"), &v, bits * 2).is_err());
+            }
+        }
+        assert_eq!(
+            RangeProof::prove_single(&gens, &mut Transcript::new(b"test"), 256, random_scalar(), 8).unwrap_err(),
+            "value out of range"
+        );
+        assert!(RangeProof::prove_single(&gens, &mut Transcript::new(b"test"), 1, random_scalar(), 12).is_err());
+    }
+
+    #[test]
+    fn test_rejects_forged_proofs() {
+        let gens = BulletproofGens::new(32);
+        let (proof, v) = RangeProof::prove_single(&gens, &mut Transcript::new(b"test"), 7, random_scalar(), 32).unwrap();
+        let verify = |proof: &RangeProof, v: &Commitment| proof.verify_single(&gens, &mut Transcript::new(b"test"), v, 32);
+
+        // Another commitment, even one to the same value
+        let (other, _) = gens.pedersen.commit_random(Fr::from(7u64));
+        assert!(verify(&proof, &other).is_err());
+        // Shifting the commitment by g would move the value, the proof cannot follow
+        assert!(verify(&proof, &(v + Commitment::from(gens.pedersen.g))).is_err());
+
+        let mut tampered = proof.clone();
+        tampered.t_hat += Fr::ONE;
+        assert!(verify(&tampered, &v).is_err());
+        let mut tampered = proof.clone();
+        tampered.mu += Fr::ONE;
+        assert!(verify(&tampered, &v).is_err());
+        let mut tampered = proof.clone();
+        tampered.a = tampered.s;
+        assert!(verify(&tampered, &v).is_err());
+        let mut tampered = proof;
+        tampered.ipp.b += Fr::ONE;
+        assert!(verify(&tampered, &v).is_err());
+
+        // A value of 2^32 does not fit, a commitment to it cannot reuse a 32-bit proof
+        let big = gens.pedersen.commit(&Opening { value: Fr::from(1u64 << 32), blinding: random_scalar() });
+        let (proof, _) = RangeProof::prove_single(&gens, &mut Transcript::new(b"test"), 0, random_scalar(), 32).unwrap();
+        assert!(verify(&proof, &big).is_err());
+    }
+
+    #[test]
+    fn test_negative_value_has_no_proof() {
+        // -1 mod r is a huge field element, a prover using its low 8 bits still fails
+        let gens = BulletproofGens::new(8);
+        let blinding = random_scalar();
+        let minus_one = gens.pedersen.commit(&Opening { value: -Fr::ONE, blinding });
+        let (proof, _) = RangeProof::prove_single(&gens, &mut Transcript::new(b"test"), 255, blinding, 8).unwrap();
+        assert!(proof.verify_single(&gens, &mut Transcript::new(b"test"), &minus_one, 8).is_err());
+    }
+
+    #[test]
+    fn test_aggregated_range_proof() {
+        let gens = BulletproofGens::new(64);
+        let values = [3u64, 0, 65535, 40000];
+        let blindings: Vec<Fr> = values.iter().map(|_| random_scalar()).collect();
+        let (proof, vs) =
+            RangeProof::prove_multiple(&gens, &mut Transcript::new(b"agg"), &values, &blindings, 16).unwrap();
+        assert_eq!(proof.verify_multiple(&gens, &mut Transcript::new(b"agg"), &vs, 16), Ok(()));
+        // log2(4 * 16) = 6 rounds for all four values, log2(16) = 4 for one of them
+        assert_eq!(proof.ipp.l_vec.len(), 6);
+        let (single, _) = RangeProof::prove_single(&gens, &mut Transcript::new(b"agg"), 3, blindings[0], 16).unwrap();
+        assert_eq!(single.ipp.l_vec.len(), 4);
+
+        let mut reordered = vs.clone();
+        reordered.swap(0, 1);
+        assert!(proof.verify_multiple(&gens, &mut Transcript::new(b"agg"), &reordered, 16).is_err());
+        assert!(proof.verify_multiple(&gens, &mut Transcript::new(b"agg"), &vs[..2], 16).is_err());
+
+        assert!(RangeProof::prove_multiple(&gens, &mut Transcript::new(b"agg"), &values[..3], &blindings[..3], 16).is_err());
+        assert!(RangeProof::prove_multiple(&gens, &mut Transcript::new(b"agg"), &values, &blindings, 32).is_err());
+    }
+
+    #[test]
+    fn test_serialization() {
+        let gens = BulletproofGens::new(64);
+        let (proof, v) = RangeProof::prove_single(&gens, &mut Transcript::new(b"ser"), 42, random_scalar(), 64).unwrap();
+        let bytes = proof.to_bytes();
+        // 7 x 32 bytes, plus 2 x 6 points, 2 scalars and two 8-byte length prefixes
+        assert_eq!(bytes.len(), 7 * 32 + 12 * 32 + 8 + 8 + 64);
+        let decoded = RangeProof::from_bytes(&gens, &bytes).unwrap();
+        assert_eq!(decoded, proof);
+        assert_eq!(decoded.verify_single(&gens, &mut Transcript::new(b"ser"), &v, 64), Ok(()));
+
+        assert!(RangeProof::from_bytes(&gens, &bytes[..bytes.len() - 1]).is_err());
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert!(RangeProof::from_bytes(&gens, &extended).is_err());
+        // Fewer generators than the proof was made with
+        assert!(RangeProof::from_bytes(&BulletproofGens::new(32), &bytes).is_err());
+    }
+
+    #[test]
+    fn test_forged_length_prefix() {
+        let gens = BulletproofGens::new(64);
+        let (proof, _) = RangeProof::prove_single(&gens, &mut Transcript::new(b"ser"), 42, random_scalar(), 64).unwrap();
+        let bytes = proof.to_bytes();
+        let r_prefix = HEADER_LEN + 8 + 6 * 32;
+        for (at, claimed) in [(HEADER_LEN, u64::MAX), (HEADER_LEN, 7), (r_prefix, u64::MAX), (r_prefix, 1 << 40)] {
+            let mut forged = bytes.clone();
+            forged[at..at + 8].copy_from_slice(&claimed.to_le_bytes());
+            assert!(RangeProof::from_bytes(&gens, &forged).is_err());
+        }
+        // A short input with a huge L length
+        let mut short = vec![0u8; 700];
+        short[HEADER_LEN..HEADER_LEN + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(RangeProof::from_bytes(&gens, &short).is_err());
+    }
+}
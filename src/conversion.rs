@@ -59,4 +59,28 @@ pub fn good_bin_to_dec(num: u32) -> u32 {
         temp_num /=10;
     }
     sum
-}
\ No newline at end of file
+}
+
+/// Hex string to bytes, used for the test vectors in the crypto modules
+/// Panics on odd length or non-hex characters
+pub fn hex_to_bytes(hex: &str) -> Vec<u8> {
+    assert!(hex.len().is_multiple_of(2), "hex string must have an even length");
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("invalid hex character"))
+        .collect()
+}
+
+/// The little-endian u64 length ark-serialize writes before a `Vec`, at offset `at`
+///
+/// ark's deserializer allocates that many elements up front, so decoders of untrusted
+/// input check it against what they expect before handing the bytes over.
+pub fn read_length_prefix(bytes: &[u8], at: usize) -> Result<u64, &'static str> {
+    let prefix = bytes.get(at..at.saturating_add(8)).ok_or("input too short for a length prefix")?;
+    Ok(u64::from_le_bytes(prefix.try_into().expect("8 bytes")))
+}
+
+/// Lowercase hex encoding of a byte slice
+pub fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -138,7 +138,7 @@ pub fn entry_point() {
     // --- PHASE 2: REVEAL ---
     println!("\n[Phase 2] Revealing Values...");
     let (alice_pk, alice_salt, alice_pok) = alice.reveal();
-    let (malory_pk, malory_salt, malory_pok) = malory.reveal();
+    let (malory_pk, malory_salt, _malory_pok) = malory.reveal();
 
     // --- PHASE 3: VERIFICATION ---
     println!("\n[Phase 3] Verification...");
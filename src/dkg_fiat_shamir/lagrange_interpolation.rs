@@ -1,4 +1,4 @@
-use std::ops::{Add, Sub, Mul, Div, Rem};
+use std::ops::{Add, Sub, Mul, Div};
 
 // A small prime for demonstration (Finite Field GF(251))
 // In production, this would be a U256 prime.
@@ -0,0 +1,210 @@
+//! Pedersen's Verifiable Secret Sharing
+//!
+//! The dealer samples f(x) = s + a_1 x + ... + a_{t-1} x^{t-1} and a second, blinding
+//! polynomial f'(x) = b_0 + b_1 x + ..., broadcasts C_j = a_j G + b_j H and sends
+//! (s_i, s'_i) = (f(i), f'(i)) to participant i, who checks
+//!
+//!     s_i G + s'_i H == Σ C_j i^j
+//!
+//! Feldman's C_0 = s G pins down the secret for anyone able to take a discrete log
+//! (or to guess s from a small set). Here every C_j is a Pedersen commitment, so the
+//! broadcast is perfectly hiding: any t - 1 shares plus the commitments are
+//! consistent with every secret. Binding is computational instead, a dealer knowing
+//! log_G(H) could open inconsistent shares.
+
+use std::collections::HashSet;
+
+use ark_bn254::Fr as Scalar;
+use ark_ff::{Field, Zero};
+
+use crate::perdesen_commitment::{Commitment, Opening, PedersenParams, random_scalar};
+
+/// Participant i's share: (f(i), f'(i)) opens Σ C_j i^j
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Share {
+    pub index: u64,
+    pub opening: Opening,
+}
+
+/// What the dealer produces: public commitments and one private share per participant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dealing {
+    pub commitments: Vec<Commitment>,
+    pub shares: Vec<Share>,
+}
+
+/// f(x) by Horner's rule, coefficients from the constant term up
+fn evaluate(coeffs: &[Scalar], x: Scalar) -> Scalar {
+    coeffs.iter().rev().fold(Scalar::zero(), |acc, c| acc * x + c)
+}
+
+/// Share `secret` among participants 1..=n so that any `threshold` of them recover it
+pub fn deal(params: &PedersenParams, secret: Scalar, threshold: usize, n: u64) -> Result<Dealing, &'static str> {
+    if threshold == 0 || threshold as u64 > n {
+        return Err("threshold must be between 1 and the number of participants");
+    }
+    let mut f = vec![secret];
+    f.extend((1..threshold).map(|_| random_scalar()));
+    let blinding: Vec<Scalar> = (0..threshold).map(|_| random_scalar()).collect();
+
+    let commitments = f
+        .iter()
+        .zip(&blinding)
+        .map(|(&value, &blinding)| params.commit(&Opening { value, blinding }))
+        .collect();
+    let shares = (1..=n)
+        .map(|index| {
+            let x = Scalar::from(index);
+            Share { index, opening: Opening { value: evaluate(&f, x), blinding: evaluate(&blinding, x) } }
+        })
+        .collect();
+    Ok(Dealing { commitments, shares })
+}
+
+/// Σ C_j i^j, the commitment participant i's share has to open
+pub fn expected_commitment(commitments: &[Commitment], index: u64) -> Commitment {
+    let x = Scalar::from(index);
+    commitments.iter().rev().fold(Commitment::zero(), |acc, c| acc * x + *c)
+}
+
+/// Run by participant `share.index` on receipt, a failure is grounds for a complaint
+pub fn verify_share(params: &PedersenParams, commitments: &[Commitment], share: &Share) -> bool {
+    share.index != 0 && params.verify(&expected_commitment(commitments, share.index), &share.opening)
+}
+
+/// Checks everyone can run on the broadcast alone: exactly `threshold` commitments, so
+/// the shared polynomial has degree at most threshold - 1
+pub fn verify_commitments(commitments: &[Commitment], threshold: usize) -> Result<(), &'static str> {
+    if commitments.len() != threshold {
+        return Err("wrong number of commitments for the threshold");
+    }
+    Ok(())
+}
+
+/// Lagrange coefficients λ_i with f(0) = Σ λ_i f(i)
+fn lagrange_at_zero(indices: &[u64]) -> Vec<Scalar> {
+    indices
+        .iter()
+        .map(|&i| {
+            let xi = Scalar::from(i);
+            let (num, den) = indices.iter().filter(|&&j| j != i).fold((Scalar::ONE, Scalar::ONE), |(num, den), &j| {
+                let xj = Scalar::from(j);
+                (num * xj, den * (xj - xi))
+            });
+            num * den.inverse().expect("indices are distinct")
+        })
+        .collect()
+}
+
+/// Interpolate (f(0), f'(0)) from exactly `threshold` verified shares; the result
+/// opens C_0
+pub fn reconstruct(shares: &[Share]) -> Result<Opening, &'static str> {
+    if shares.is_empty() {
+        return Err("no shares");
+    }
+    let indices: Vec<u64> = shares.iter().map(|s| s.index).collect();
+    let distinct = indices.iter().collect::<HashSet<_>>().len() == indices.len();
+    if indices.contains(&0) || !distinct {
+        return Err("duplicate or zero share index");
+    }
+    let lambdas = lagrange_at_zero(&indices);
+    let zero = Opening { value: Scalar::zero(), blinding: Scalar::zero() };
+    Ok(shares.iter().zip(lambdas).fold(zero, |acc, (s, l)| acc + s.opening * l))
+}
+
+pub fn entry_point() {
+    println!("--- Pedersen VSS ---");
+    let params = PedersenParams::default();
+    let secret = Scalar::from(1234u64);
+    let dealing = deal(&params, secret, 3, 5).unwrap();
+
+    for share in &dealing.shares {
+        let ok = verify_share(&params, &dealing.commitments, share);
+        println!("Participant {} verifies its share: {ok}", share.index);
+    }
+
+    let recovered = reconstruct(&dealing.shares[1..4]).unwrap();
+    assert_eq!(recovered.value, secret);
+    assert!(params.verify(&dealing.commitments[0], &recovered));
+    println!("Participants 2, 3, 4 recover the secret {secret}, which opens C_0");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_honest_dealer() {
+        let params = PedersenParams::default();
+        let secret = Scalar::from(42u64);
+        let dealing = deal(&params, secret, 3, 5).unwrap();
+        assert_eq!(verify_commitments(&dealing.commitments, 3), Ok(()));
+        assert!(dealing.shares.iter().all(|s| verify_share(&params, &dealing.commitments, s)));
+
+        // Any 3 shares recover the secret together with the blinding for C_0
+        for subset in [[0, 1, 2], [0, 2, 4], [4, 3, 1]] {
+            let shares: Vec<Share> = subset.iter().map(|&i| dealing.shares[i]).collect();
+            let opening = reconstruct(&shares).unwrap();
+            assert_eq!(opening.value, secret);
+            assert!(params.verify(&dealing.commitments[0], &opening));
+        }
+        // Two are consistent with a different degree 1 polynomial, not the secret
+        assert_ne!(reconstruct(&dealing.shares[..2]).unwrap().value, secret);
+
+        assert!(deal(&params, secret, 6, 5).is_err());
+        assert!(deal(&params, secret, 0, 5).is_err());
+    }
+
+    #[test]
+    fn test_commitments_hide_the_secret() {
+        // Unlike Feldman, C_0 is not s G: two dealings of the same secret share nothing
+        let params = PedersenParams::default();
+        let secret = Scalar::from(7u64);
+        let d1 = deal(&params, secret, 2, 3).unwrap();
+        let d2 = deal(&params, secret, 2, 3).unwrap();
+        assert_ne!(d1.commitments[0], d2.commitments[0]);
+        assert_ne!(d1.commitments[0], Commitment::from(params.g * secret));
+    }
+
+    #[test]
+    fn test_cheating_dealer() {
+        let params = PedersenParams::default();
+        let mut dealing = deal(&params, Scalar::from(99u64), 3, 5).unwrap();
+
+        // A tampered value or blinding for one participant is caught by that participant
+        let honest = dealing.shares[1];
+        dealing.shares[1].opening.value += Scalar::ONE;
+        assert!(!verify_share(&params, &dealing.commitments, &dealing.shares[1]));
+        dealing.shares[1] = Share { opening: Opening { blinding: honest.opening.blinding + Scalar::ONE, ..honest.opening }, ..honest };
+        assert!(!verify_share(&params, &dealing.commitments, &dealing.shares[1]));
+        // The other participants are unaffected
+        assert!(verify_share(&params, &dealing.commitments, &dealing.shares[0]));
+
+        // A share addressed to the wrong index does not verify either
+        let swapped = Share { index: 3, ..dealing.shares[0] };
+        assert!(!verify_share(&params, &dealing.commitments, &swapped));
+
+        // Too many commitments would let the dealer share a higher degree polynomial
+        let bigger = deal(&params, Scalar::from(99u64), 4, 5).unwrap();
+        assert!(verify_commitments(&bigger.commitments, 3).is_err());
+
+        // Sharing an inconsistent polynomial: shares from f + x^3 on top of the
+        // commitments for f fail for everyone
+        let mut dealing = deal(&params, Scalar::from(99u64), 3, 5).unwrap();
+        for share in &mut dealing.shares {
+            share.opening.value += Scalar::from(share.index).pow([3]);
+        }
+        assert!(dealing.shares.iter().all(|s| !verify_share(&params, &dealing.commitments, s)));
+    }
+
+    #[test]
+    fn test_reconstruct_rejects_bad_indices() {
+        let params = PedersenParams::default();
+        let dealing = deal(&params, Scalar::from(5u64), 2, 3).unwrap();
+        assert!(reconstruct(&[dealing.shares[0], dealing.shares[0]]).is_err());
+        assert!(reconstruct(&[]).is_err());
+        let zero = Share { index: 0, ..dealing.shares[0] };
+        assert!(reconstruct(&[zero, dealing.shares[1]]).is_err());
+        assert!(!verify_share(&params, &dealing.commitments, &zero));
+    }
+}
@@ -0,0 +1,233 @@
+//! Elliptic-curve Diffie-Hellman on `BigProjectivePoint`
+//!
+//! Both sides publish Q = dG and compute d_self * Q_peer = d_a d_b G. The scalar
+//! multiplication runs in projective coordinates, which never look at b, so a peer
+//! point is only ever multiplied after it has been validated (see `invalid_curve`
+//! for what happens otherwise). Validation is not optional: every way to get a
+//! shared secret goes through `validate_public_key` first.
+//!
+//! The shared secret is the x-coordinate, fixed-width big-endian (SEC1 section 3.3.1).
+//! Use `SharedSecret::derive_key` to turn it into symmetric keys.
+
+use std::fmt;
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use super::hkdf;
+use crate::elliptic_point::curve::{DomainParams, Point};
+use crate::elliptic_point::ec_projective::BigProjectivePoint;
+
+/// How the peer point's subgroup membership is established
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CofactorMode {
+    /// Full validation including n * Q = O, then d * Q
+    Standard,
+    /// Cofactor Diffie-Hellman: skip the n * Q check and compute (h d) * Q, which
+    /// sends any small-order component to O. Both sides have to use it.
+    Cofactor,
+}
+
+/// SEC1 section 3.2.2.1 public key validation
+///
+/// Q must not be O, its coordinates must be field elements and it must satisfy the
+/// curve equation. `Standard` also checks n * Q = O, `Cofactor` leaves that to the
+/// multiplication by h.
+pub fn validate_public_key(domain: &DomainParams, point: &Point, mode: CofactorMode) -> Result<(), &'static str> {
+    let curve = &domain.curve;
+    let Point::Affine { x, y } = point else {
+        return Err("peer point is at infinity");
+    };
+    if x >= &curve.p || y >= &curve.p {
+        return Err("peer point coordinates out of range");
+    }
+    if !curve.contains(point) {
+        return Err("peer point is not on the curve");
+    }
+    if mode == CofactorMode::Standard
+        && !BigProjectivePoint::from(point.clone()).scalar_mul(&domain.n, curve).is_infinity()
+    {
+        return Err("peer point is not in the prime order subgroup");
+    }
+    Ok(())
+}
+
+/// The x-coordinate of the shared point, as many bytes as p needs
+#[derive(Clone, PartialEq, Eq)]
+pub struct SharedSecret(pub Vec<u8>);
+
+impl fmt::Debug for SharedSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedSecret").field(&"<redacted>").finish()
+    }
+}
+
+impl SharedSecret {
+    /// HKDF-SHA256 of the raw secret, `info` binds the key to its purpose
+    pub fn derive_key(&self, salt: &[u8], info: &[u8], len: usize) -> Result<Vec<u8>, &'static str> {
+        hkdf::hkdf(salt, &self.0, info, len)
+    }
+}
+
+fn agree(domain: &DomainParams, secret: &BigUint, peer: &Point, mode: CofactorMode) -> Result<SharedSecret, &'static str> {
+    validate_public_key(domain, peer, mode)?;
+    let curve = &domain.curve;
+    let scalar = match mode {
+        CofactorMode::Standard => secret.clone(),
+        CofactorMode::Cofactor => secret * &domain.h,
+    };
+    let shared = BigProjectivePoint::from(peer.clone()).scalar_mul(&scalar, curve).to_affine(curve);
+    let Point::Affine { x, .. } = shared else {
+        return Err("shared point is at infinity");
+    };
+    let len = (curve.p.bits() as usize).div_ceil(8);
+    let bytes = x.to_bytes_be();
+    let mut out = vec![0u8; len];
+    out[len - bytes.len()..].copy_from_slice(&bytes);
+    Ok(SharedSecret(out))
+}
+
+fn public_point(domain: &DomainParams, secret: &BigUint) -> Point {
+    BigProjectivePoint::from(domain.g.clone()).scalar_mul(secret, &domain.curve).to_affine(&domain.curve)
+}
+
+fn check_scalar(domain: &DomainParams, secret: &BigUint) -> Result<(), &'static str> {
+    if secret.is_zero() || secret >= &domain.n {
+        return Err("secret scalar must be in [1, n-1]");
+    }
+    Ok(())
+}
+
+/// Long-term key, reusable across any number of agreements
+#[derive(Clone)]
+pub struct StaticSecret {
+    domain: DomainParams,
+    scalar: BigUint,
+}
+
+/// The scalar never reaches logs
+impl fmt::Debug for StaticSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticSecret").field("domain", &self.domain).field("scalar", &"<redacted>").finish()
+    }
+}
+
+impl StaticSecret {
+    pub fn random(domain: &DomainParams) -> Self {
+        Self { scalar: domain.random_scalar(), domain: domain.clone() }
+    }
+
+    pub fn from_scalar(domain: &DomainParams, scalar: BigUint) -> Result<Self, &'static str> {
+        check_scalar(domain, &scalar)?;
+        Ok(Self { domain: domain.clone(), scalar })
+    }
+
+    pub fn public_key(&self) -> Point {
+        public_point(&self.domain, &self.scalar)
+    }
+
+    pub fn diffie_hellman(&self, peer: &Point, mode: CofactorMode) -> Result<SharedSecret, &'static str> {
+        agree(&self.domain, &self.scalar, peer, mode)
+    }
+}
+
+/// One-shot key: `diffie_hellman` consumes it, so it cannot be used twice
+pub struct EphemeralSecret {
+    domain: DomainParams,
+    scalar: BigUint,
+}
+
+impl fmt::Debug for EphemeralSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EphemeralSecret").field("domain", &self.domain).field("scalar", &"<redacted>").finish()
+    }
+}
+
+impl EphemeralSecret {
+    pub fn random(domain: &DomainParams) -> Self {
+        Self { scalar: domain.random_scalar(), domain: domain.clone() }
+    }
+
+    pub fn public_key(&self) -> Point {
+        public_point(&self.domain, &self.scalar)
+    }
+
+    pub fn diffie_hellman(self, peer: &Point, mode: CofactorMode) -> Result<SharedSecret, &'static str> {
+        agree(&self.domain, &self.scalar, peer, mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agreement() {
+        for domain in [DomainParams::p256(), DomainParams::secp256k1()] {
+            let alice = EphemeralSecret::random(&domain);
+            let bob = StaticSecret::random(&domain);
+            let alice_public = alice.public_key();
+            let alice_shared = alice.diffie_hellman(&bob.public_key(), CofactorMode::Standard).unwrap();
+            let bob_shared = bob.diffie_hellman(&alice_public, CofactorMode::Standard).unwrap();
+            assert_eq!(alice_shared, bob_shared);
+            assert_eq!(alice_shared.0.len(), 32);
+
+            let key = alice_shared.derive_key(b"salt", b"client key", 16).unwrap();
+            assert_eq!(key, bob_shared.derive_key(b"salt", b"client key", 16).unwrap());
+            assert_ne!(key, bob_shared.derive_key(b"salt", b"server key", 16).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_static_key_matches_affine_arithmetic() {
+        let domain = DomainParams::p256();
+        let secret = StaticSecret::from_scalar(&domain, BigUint::from(0xdeadbeefu32)).unwrap();
+        assert_eq!(secret.public_key(), domain.mul_g(&BigUint::from(0xdeadbeefu32)));
+        assert!(StaticSecret::from_scalar(&domain, BigUint::zero()).is_err());
+        assert!(StaticSecret::from_scalar(&domain, domain.n.clone()).is_err());
+
+        let printed = format!("{secret:?} {:?}", secret.diffie_hellman(&domain.g, CofactorMode::Standard).unwrap());
+        assert!(printed.contains("<redacted>"));
+        assert!(!printed.contains(&0xdeadbeefu32.to_string()));
+    }
+
+    #[test]
+    fn test_rejects_invalid_peers() {
+        let domain = DomainParams::f211();
+        let secret = StaticSecret::from_scalar(&domain, BigUint::from(23u32)).unwrap();
+        let mode = CofactorMode::Standard;
+
+        assert_eq!(secret.diffie_hellman(&Point::Infinity, mode), Err("peer point is at infinity"));
+        // (0, 1) is on y^2 = x^3 + 1, the curve of the invalid-curve attack
+        assert_eq!(secret.diffie_hellman(&Point::new(0, 1), mode), Err("peer point is not on the curve"));
+        assert_eq!(secret.diffie_hellman(&Point::new(0, 213), mode), Err("peer point coordinates out of range"));
+        // (0, 2) is on the curve but has order 3 (the cofactor)
+        let small = Point::new(0, 2);
+        assert_eq!(secret.diffie_hellman(&small, mode), Err("peer point is not in the prime order subgroup"));
+        assert_eq!(secret.diffie_hellman(&small, CofactorMode::Cofactor), Err("shared point is at infinity"));
+    }
+
+    #[test]
+    fn test_cofactor_mode() {
+        // F211 has h = 3: cofactor ECDH agrees on (3 d_a d_b) G
+        let domain = DomainParams::f211();
+        assert_eq!(domain.h, BigUint::from(3u32));
+        let alice = StaticSecret::from_scalar(&domain, BigUint::from(5u32)).unwrap();
+        let bob = StaticSecret::from_scalar(&domain, BigUint::from(17u32)).unwrap();
+        let shared = alice.diffie_hellman(&bob.public_key(), CofactorMode::Cofactor).unwrap();
+        assert_eq!(shared, bob.diffie_hellman(&alice.public_key(), CofactorMode::Cofactor).unwrap());
+
+        let Point::Affine { x, .. } = domain.mul_g(&BigUint::from(3u32 * 5 * 17)) else {
+            panic!("expected an affine point");
+        };
+        assert_eq!(shared.0, vec![x.to_bytes_be()[0]]);
+        assert_ne!(shared, alice.diffie_hellman(&bob.public_key(), CofactorMode::Standard).unwrap());
+
+        // A peer point with a small-order component: Q' = Q + T. Standard mode refuses
+        // it, cofactor mode strips T and lands on the same secret as the honest Q.
+        let curve = &domain.curve;
+        let tainted = curve.add(&bob.public_key(), &Point::new(0, 2));
+        assert!(alice.diffie_hellman(&tainted, CofactorMode::Standard).is_err());
+        assert_eq!(alice.diffie_hellman(&tainted, CofactorMode::Cofactor).unwrap(), shared);
+    }
+}
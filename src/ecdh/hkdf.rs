@@ -0,0 +1,67 @@
+//! HKDF with HMAC-SHA256 (RFC 5869)
+//!
+//! A raw ECDH output is a field element, not a key: it is biased (only about half
+//! of all x-coordinates occur) and its length depends on the curve. Extract
+//! concentrates it into a pseudorandom key, expand stretches that into as many
+//! independent keys as the protocol needs, separated by `info`.
+
+use crate::ecdsa::rfc6979::hmac_sha256;
+
+const HASH_LEN: usize = 32;
+
+/// PRK = HMAC(salt, IKM), an empty salt means HashLen zero bytes
+pub fn extract(salt: &[u8], ikm: &[u8]) -> [u8; 32] {
+    if salt.is_empty() {
+        return hmac_sha256(&[0u8; HASH_LEN], &[ikm]);
+    }
+    hmac_sha256(salt, &[ikm])
+}
+
+/// T(i) = HMAC(PRK, T(i-1) ‖ info ‖ i), concatenated and cut to `len` bytes
+pub fn expand(prk: &[u8; 32], info: &[u8], len: usize) -> Result<Vec<u8>, &'static str> {
+    if len > 255 * HASH_LEN {
+        return Err("HKDF output is limited to 255 blocks");
+    }
+    let mut okm = Vec::with_capacity(len);
+    let mut block: Vec<u8> = Vec::new();
+    let mut counter = 1u8;
+    while okm.len() < len {
+        block = hmac_sha256(prk, &[&block, info, &[counter]]).to_vec();
+        okm.extend_from_slice(&block);
+        counter = counter.wrapping_add(1);
+    }
+    okm.truncate(len);
+    Ok(okm)
+}
+
+pub fn hkdf(salt: &[u8], ikm: &[u8], info: &[u8], len: usize) -> Result<Vec<u8>, &'static str> {
+    expand(&extract(salt, ikm), info, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::{bytes_to_hex, hex_to_bytes};
+
+    #[test]
+    fn test_rfc5869_vectors() {
+        // A.1: basic test case
+        let ikm = [0x0bu8; 22];
+        let salt = hex_to_bytes("000102030405060708090a0b0c");
+        let info = hex_to_bytes("f0f1f2f3f4f5f6f7f8f9");
+        let prk = extract(&salt, &ikm);
+        assert_eq!(bytes_to_hex(&prk), "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5");
+        assert_eq!(
+            bytes_to_hex(&expand(&prk, &info, 42).unwrap()),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        );
+
+        // A.3: no salt, no info
+        assert_eq!(
+            bytes_to_hex(&hkdf(&[], &ikm, &[], 42).unwrap()),
+            "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8"
+        );
+
+        assert!(expand(&prk, &info, 255 * 32 + 1).is_err());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod rho;
+pub mod mov;
+pub mod smart;
@@ -0,0 +1,128 @@
+//! The MOV reduction (Menezes, Okamoto, Vanstone 1993)
+//!
+//! A pairing is a homomorphism into F_{p^k}^*: with ê(P, P) = α non-trivial,
+//! Q = kP gives ê(P, Q) = α^k. So an ECDLP in a subgroup of order n becomes a DLP in
+//! the order n subgroup of F_{p^k}^*, where index calculus runs in subexponential
+//! time. That only helps the attacker when the embedding degree k (the smallest k
+//! with n | p^k - 1) is small: at most 6 for supersingular curves (2 for the ones
+//! in `pairing`), astronomically large for a random curve. Frey and Rück do the same
+//! with the Tate pairing.
+//!
+//! The finite field side is solved with baby-step giant-step here, which is still
+//! O(√n): the point is the reduction itself, and that the pairing values are
+//! ordinary F_p2 elements any finite field DLP solver could take over.
+
+use std::collections::HashMap;
+
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive};
+
+use crate::elliptic_point::curve::Point;
+use crate::elliptic_point::fp2::{Fp2, QuadraticField};
+use crate::elliptic_point::pairing::PairingCurve;
+
+/// Smallest k <= max_k with n | p^k - 1
+pub fn embedding_degree(p: &BigUint, n: &BigUint, max_k: u32) -> Option<u32> {
+    let p = p % n;
+    let mut power = p.clone();
+    for k in 1..=max_k {
+        if power.is_one() {
+            return Some(k);
+        }
+        power = power * &p % n;
+    }
+    None
+}
+
+/// log_base(target) in a subgroup of F_p2^* of the given (small) order
+pub fn bsgs_fp2(field: &QuadraticField, base: &Fp2, target: &Fp2, order: u64) -> Option<u64> {
+    let m = order.isqrt() + 1;
+    let mut baby = HashMap::with_capacity(m as usize);
+    let mut power = field.one();
+    for j in 0..m {
+        baby.entry(power.clone()).or_insert(j);
+        power = field.mul(&power, base);
+    }
+    // power = base^m now, step the target down by it
+    let giant = field.inv(&power);
+    let mut gamma = target.clone();
+    for i in 0..m {
+        if let Some(j) = baby.get(&gamma) {
+            return Some((i * m + j) % order);
+        }
+        gamma = field.mul(&gamma, &giant);
+    }
+    None
+}
+
+/// log_P(Q) for P, Q in E(F_p)[r] on a supersingular y^2 = x^3 + b, via the
+/// distortion-modified Weil pairing
+pub fn mov_attack(pc: &PairingCurve, p: &Point, q: &Point) -> Result<BigUint, &'static str> {
+    let r = pc.r.to_u64().ok_or("r is too large for baby-step giant-step")?;
+    let alpha = pc.modified_weil(p, p)?;
+    if alpha.is_one() {
+        return Err("ê(P, P) = 1, P is not a generator of the order r subgroup");
+    }
+    let beta = pc.modified_weil(p, q)?;
+    let k = bsgs_fp2(&pc.field, &alpha, &beta, r).ok_or("Q is not in the subgroup generated by P")?;
+    Ok(BigUint::from(k))
+}
+
+/// y^2 = x^3 + 1 over p = 1048601 (20 bits), r = 174767 = (p + 1) / 6
+pub fn demo_curve() -> PairingCurve {
+    PairingCurve::small(1048601, 1, 174767).expect("valid pairing parameters")
+}
+
+pub fn entry_point() {
+    println!("--- MOV ---");
+    let pc = demo_curve();
+    let g = pc.generator();
+    let secret = 98765u64;
+    let q = pc.curve.mul_u64(&g, secret);
+    let alpha = pc.modified_weil(&g, &g).unwrap();
+    let beta = pc.modified_weil(&g, &q).unwrap();
+    println!("ê(P, P)  = {} + {} ω", alpha.c0, alpha.c1);
+    println!("ê(P, kP) = {} + {} ω", beta.c0, beta.c1);
+    match mov_attack(&pc, &g, &q) {
+        Ok(k) => println!("DLP in F_p2 gives k = {k} (actual {secret})"),
+        Err(e) => println!("MOV failed: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elliptic_point::curve::DomainParams;
+
+    #[test]
+    fn test_embedding_degree() {
+        let pc = demo_curve();
+        assert_eq!(embedding_degree(&pc.field.base.p, &pc.r, 100), Some(2));
+        // The standard curves are nowhere near
+        for domain in [DomainParams::secp256k1(), DomainParams::p256()] {
+            assert_eq!(embedding_degree(&domain.curve.p, &domain.n, 1000), None);
+        }
+        // BN254 has k = 12 by design, pairings need it small but not this small
+        let bn = DomainParams::bn254_g1();
+        assert_eq!(embedding_degree(&bn.curve.p, &bn.n, 100), Some(12));
+    }
+
+    #[test]
+    fn test_mov_attack() {
+        let pc = demo_curve();
+        let g = pc.generator();
+        for k in [1u64, 2, 98765, 174766] {
+            let q = pc.curve.mul_u64(&g, k);
+            assert_eq!(mov_attack(&pc, &g, &q), Ok(BigUint::from(k)));
+        }
+    }
+
+    #[test]
+    fn test_bsgs_fp2() {
+        let field = QuadraticField::new(BigUint::from(59u32)).unwrap();
+        // ω has order 3
+        let omega = field.omega();
+        assert_eq!(bsgs_fp2(&field, &omega, &field.square(&omega), 3), Some(2));
+        assert_eq!(bsgs_fp2(&field, &omega, &field.lift(BigUint::from(2u32)), 3), None);
+    }
+}
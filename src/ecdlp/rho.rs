@@ -0,0 +1,287 @@
+//! Parallel Pollard rho with the negation map
+//!
+//! Generic attack, works on every curve: O(√n) group operations for a subgroup of
+//! prime order n, which is why n needs 256 bits for 128-bit security.
+//!
+//! Each walker keeps X = aP + bQ and steps X -> X + R_j with an r-adding walk, the
+//! partition j picked from x. Walks are deterministic in X, so once two of them meet
+//! they coincide from then on. Instead of cycle detection, walkers report
+//! distinguished points (low bits of x zero) to a shared table, and two different
+//! (a, b) for the same point give a + b k = a' + b' k (van Oorschot-Wiener). Any number
+//! of threads can walk independently, the speedup is linear.
+//!
+//! Negation map: walk on classes {X, -X}, represented by the point with the smaller
+//! y. That halves the space and saves a factor √2, but creates fruitless 2-cycles
+//! (X -> X + R_j -> X when -(X + R_j) lands in partition j). Skipping to partition
+//! j + 1 when the step stays in j avoids them, longer cycles are handled by
+//! abandoning walks that run too long without a distinguished point.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread;
+
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+
+use crate::elliptic_point::curve::{Curve, DomainParams, Point};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RhoConfig {
+    /// Number of R_j in the adding walk, 20 or more behaves like a random walk
+    pub partitions: usize,
+    /// A point is distinguished when this many low bits of x are zero
+    pub distinguished_bits: u32,
+    pub walkers: usize,
+    /// Total group operations across all walkers before giving up
+    pub max_steps: u64,
+}
+
+impl RhoConfig {
+    /// About √n / 2^10 distinguished points, one walker per core
+    ///
+    /// The distinguished-point test looks at the low 64 bits of x, so past n ≈ 2^84
+    /// it stays at 32 bits; the budget saturates at u64::MAX for n beyond 2^116.
+    pub fn for_order(n: &BigUint) -> Self {
+        let walkers = thread::available_parallelism().map_or(1, |w| w.get()).min(8);
+        Self {
+            partitions: 32,
+            distinguished_bits: (n.bits() as u32 / 2).saturating_sub(10).min(32),
+            walkers,
+            max_steps: ((n.sqrt() + 64u32) * 64u32).to_u64().unwrap_or(u64::MAX),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RhoResult {
+    pub log: BigUint,
+    pub steps: u64,
+    pub distinguished: usize,
+}
+
+/// A point with its representation a P + b Q
+#[derive(Debug, Clone)]
+struct Walk {
+    point: Point,
+    a: BigUint,
+    b: BigUint,
+}
+
+struct Walker<'a> {
+    domain: &'a DomainParams,
+    steps: &'a [(Point, BigUint, BigUint)],
+    config: RhoConfig,
+}
+
+fn x_low_bits(point: &Point) -> u64 {
+    match point {
+        Point::Infinity => 0,
+        Point::Affine { x, .. } => x.to_u64_digits().first().copied().unwrap_or(0),
+    }
+}
+
+impl Walker<'_> {
+    fn curve(&self) -> &Curve {
+        &self.domain.curve
+    }
+
+    fn partition(&self, point: &Point) -> usize {
+        let bits = x_low_bits(point).checked_shr(self.config.distinguished_bits).unwrap_or(0);
+        (bits % self.config.partitions as u64) as usize
+    }
+
+    fn is_distinguished(&self, point: &Point) -> bool {
+        x_low_bits(point).trailing_zeros() >= self.config.distinguished_bits
+    }
+
+    /// ±X with the smaller y, negating the coefficients along with the point
+    fn canonical(&self, walk: Walk) -> Walk {
+        let n = &self.domain.n;
+        match &walk.point {
+            Point::Affine { y, .. } if y > &(&self.curve().p - y) => Walk {
+                point: self.curve().neg(&walk.point),
+                a: (n - walk.a) % n,
+                b: (n - walk.b) % n,
+            },
+            _ => walk,
+        }
+    }
+
+    fn random_start(&self, q: &Point) -> Walk {
+        let a = self.domain.random_scalar();
+        let b = self.domain.random_scalar();
+        let point = self.curve().add(&self.domain.mul_g(&a), &self.curve().mul(q, &b));
+        self.canonical(Walk { point, a, b })
+    }
+
+    fn step(&self, walk: &Walk) -> Walk {
+        let n = &self.domain.n;
+        let start = self.partition(&walk.point);
+        let mut next = walk.clone();
+        for offset in 0..self.config.partitions {
+            let j = (start + offset) % self.config.partitions;
+            let (r, c, d) = &self.steps[j];
+            next = self.canonical(Walk {
+                point: self.curve().add(&walk.point, r),
+                a: (&walk.a + c) % n,
+                b: (&walk.b + d) % n,
+            });
+            if self.partition(&next.point) != j {
+                break;
+            }
+        }
+        next
+    }
+}
+
+/// log_P(Q) for P = domain.g of prime order n, or None when the step budget runs out
+pub fn pollard_rho(domain: &DomainParams, q: &Point, config: RhoConfig) -> Option<RhoResult> {
+    if q.is_infinity() {
+        return Some(RhoResult { log: BigUint::zero(), steps: 0, distinguished: 0 });
+    }
+    let steps: Vec<(Point, BigUint, BigUint)> = (0..config.partitions)
+        .map(|_| {
+            let (c, d) = (domain.random_scalar(), domain.random_scalar());
+            let r = domain.curve.add(&domain.mul_g(&c), &domain.curve.mul(q, &d));
+            (r, c, d)
+        })
+        .collect();
+    let walker = Walker { domain, steps: &steps, config };
+    let table: Mutex<HashMap<Point, (BigUint, BigUint)>> = Mutex::new(HashMap::new());
+    let result: Mutex<Option<BigUint>> = Mutex::new(None);
+    let done = AtomicBool::new(false);
+    let total = AtomicU64::new(0);
+    // 32 times the expected distance between distinguished points
+    let max_walk = 1u64.checked_shl(config.distinguished_bits.saturating_add(5)).unwrap_or(u64::MAX);
+    let n = &domain.n;
+
+    thread::scope(|scope| {
+        for _ in 0..config.walkers.max(1) {
+            scope.spawn(|| {
+                let mut walk = walker.random_start(q);
+                let mut length = 0u64;
+                while !done.load(Ordering::Relaxed) {
+                    if total.fetch_add(1, Ordering::Relaxed) >= config.max_steps {
+                        done.store(true, Ordering::Relaxed);
+                        break;
+                    }
+                    walk = walker.step(&walk);
+                    length += 1;
+                    if length > max_walk || walk.point.is_infinity() {
+                        // Stuck in a fruitless cycle (or hit O by luck), start over
+                        walk = walker.random_start(q);
+                        length = 0;
+                        continue;
+                    }
+                    if !walker.is_distinguished(&walk.point) {
+                        continue;
+                    }
+                    let mut table = table.lock().unwrap();
+                    match table.get(&walk.point) {
+                        // a + b k = a' + b' k, so k = (a - a') / (b' - b)
+                        Some((a2, b2)) if *b2 != walk.b => {
+                            let num = (&walk.a + n - a2) % n;
+                            let den = (b2 + n - &walk.b) % n;
+                            let k = num * den.modinv(n).expect("n is prime") % n;
+                            *result.lock().unwrap() = Some(k);
+                            done.store(true, Ordering::Relaxed);
+                        }
+                        // Same representation: this walk retraced another one
+                        Some(_) => {}
+                        None => {
+                            table.insert(walk.point.clone(), (walk.a.clone(), walk.b.clone()));
+                        }
+                    }
+                    drop(table);
+                    walk = walker.random_start(q);
+                    length = 0;
+                }
+            });
+        }
+    });
+
+    let log = result.into_inner().unwrap()?;
+    Some(RhoResult {
+        log,
+        steps: total.into_inner().min(config.max_steps),
+        distinguished: table.into_inner().unwrap().len(),
+    })
+}
+
+/// y^2 = x^3 + 2 over p = 16777333 (24 bits), prime order 16785211
+pub fn demo_domain() -> DomainParams {
+    DomainParams {
+        curve: Curve::small(16777333, 0, 2),
+        g: Point::new(1, 6433712),
+        n: BigUint::from(16785211u32),
+        h: BigUint::from(1u32),
+    }
+}
+
+pub fn entry_point() {
+    println!("--- POLLARD RHO ---");
+    let domain = demo_domain();
+    let secret = domain.random_scalar();
+    let q = domain.mul_g(&secret);
+    let config = RhoConfig::for_order(&domain.n);
+    match pollard_rho(&domain, &q, config) {
+        Some(result) => println!(
+            "24-bit curve: log = {} (actual {secret}) after {} steps on {} walkers, {} distinguished points",
+            result.log, result.steps, config.walkers, result.distinguished
+        ),
+        None => println!("24-bit curve: ran out of steps"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_f211() {
+        let domain = DomainParams::f211();
+        let config = RhoConfig { partitions: 8, distinguished_bits: 0, walkers: 2, max_steps: 100_000 };
+        for k in [1u32, 2, 30, 60] {
+            let q = domain.mul_g(&BigUint::from(k));
+            assert_eq!(pollard_rho(&domain, &q, config).unwrap().log, BigUint::from(k));
+        }
+    }
+
+    #[test]
+    fn test_config_for_large_orders() {
+        for domain in [DomainParams::secp256k1(), DomainParams::p256()] {
+            let config = RhoConfig::for_order(&domain.n);
+            assert_eq!(config.distinguished_bits, 32);
+            assert_eq!(config.max_steps, u64::MAX);
+        }
+        // √n has more than one u64 digit: the budget comes from all of them
+        let n = BigUint::from(1u32) << 140u32;
+        assert_eq!(RhoConfig::for_order(&n).max_steps, u64::MAX);
+        let n = BigUint::from(1u32) << 100u32;
+        assert_eq!(RhoConfig::for_order(&n).max_steps, 64 * ((1u64 << 50) + 64));
+    }
+
+    #[test]
+    fn test_24_bit_curve() {
+        let domain = demo_domain();
+        assert!(domain.curve.contains(&domain.g));
+        assert!(domain.curve.mul(&domain.g, &domain.n).is_infinity());
+
+        let secret = domain.random_scalar();
+        let q = domain.mul_g(&secret);
+        let config = RhoConfig { walkers: 4, ..RhoConfig::for_order(&domain.n) };
+        let result = pollard_rho(&domain, &q, config).unwrap();
+        assert_eq!(result.log, secret);
+        // √(π n / 4) ≈ 3600 with the negation map, leave plenty of slack
+        assert!(result.steps < 100_000, "{} steps", result.steps);
+    }
+
+    #[test]
+    fn test_gives_up() {
+        let domain = demo_domain();
+        let q = domain.mul_g(&BigUint::from(123_456u32));
+        let config = RhoConfig { max_steps: 100, ..RhoConfig::for_order(&domain.n) };
+        assert_eq!(pollard_rho(&domain, &q, config), None);
+    }
+}
@@ -0,0 +1,186 @@
+//! Smart's attack on anomalous curves (#E(F_p) = p)
+//!
+//! Lift the curve and both points to Z/p^2 (Hensel's lemma on y). On the lift, pP̃
+//! reduces to O mod p, so it sits in the kernel of reduction E_1, and the p-adic
+//! elliptic logarithm ψ(x, y) = -x / y is a homomorphism from E_1 to pZ_p. With
+//! Q = kP that gives ψ(pQ̃) = k ψ(pP̃) mod p^2, and k = (ψ(pQ̃) / p) / (ψ(pP̃) / p)
+//! mod p: a linear-time division instead of a DLP.
+//!
+//! Only ψ mod p^2 is needed. Compute A = (p - 1)X̃ in affine coordinates mod p^2, then
+//! pX̃ = A + X̃ where x_A = x_X mod p: the slope λ = (y_X - y_A) / (x_X - x_A) has a
+//! single p in the denominator, x = λ^2 + ... and y = -λ^3 + ..., so
+//! ψ(pX̃) / p = u / (y_X - y_A) mod p with u = (x_X - x_A) / p.
+//!
+//! If the lift happens to be the canonical one (e.g. y^2 = x^3 + b, j = 0, lifted
+//! with the same b), pP̃ = O exactly and ψ = 0: retry with a + tp for another t.
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use crate::elliptic_point::curve::{Curve, Point};
+
+/// y^2 = x^3 + a x + b over Z/p^2
+struct Lift {
+    p: BigUint,
+    p2: BigUint,
+    a: BigUint,
+    b: BigUint,
+}
+
+impl Lift {
+    fn sub(&self, x: &BigUint, y: &BigUint) -> BigUint {
+        (x + &self.p2 - y % &self.p2) % &self.p2
+    }
+
+    /// Inverse mod p^2, which exists exactly for units (p ∤ x)
+    fn inv(&self, x: &BigUint) -> Result<BigUint, &'static str> {
+        x.modinv(&self.p2).ok_or("non-unit denominator in the lifted curve")
+    }
+
+    /// y + t p with (y + t p)^2 = x^3 + a x + b mod p^2
+    fn lift_point(&self, point: &Point) -> Result<(BigUint, BigUint), &'static str> {
+        let Point::Affine { x, y } = point else {
+            return Err("cannot lift the point at infinity");
+        };
+        let rhs = (x * x * x + &self.a * x + &self.b) % &self.p2;
+        // rhs - y^2 is a multiple of p since the point is on the curve mod p
+        let t = self.sub(&rhs, &(y * y)) / &self.p;
+        let two_y = (y * 2u32) % &self.p;
+        let t = t * two_y.modinv(&self.p).ok_or("point of order 2")? % &self.p;
+        Ok((x.clone(), (y + t * &self.p) % &self.p2))
+    }
+
+    fn add(&self, p1: &(BigUint, BigUint), p2: &(BigUint, BigUint)) -> Result<(BigUint, BigUint), &'static str> {
+        let lambda = if p1 == p2 {
+            let num = (BigUint::from(3u32) * &p1.0 * &p1.0 + &self.a) % &self.p2;
+            num * self.inv(&(&p1.1 * 2u32))? % &self.p2
+        } else {
+            self.sub(&p2.1, &p1.1) * self.inv(&self.sub(&p2.0, &p1.0))? % &self.p2
+        };
+        let x3 = self.sub(&self.sub(&(&lambda * &lambda), &p1.0), &p2.0);
+        let y3 = self.sub(&(lambda * self.sub(&p1.0, &x3)), &p1.1);
+        Ok((x3, y3))
+    }
+
+    fn mul(&self, point: &(BigUint, BigUint), k: &BigUint) -> Result<(BigUint, BigUint), &'static str> {
+        let mut result: Option<(BigUint, BigUint)> = None;
+        for i in (0..k.bits()).rev() {
+            if let Some(r) = &result {
+                result = Some(self.add(r, r)?);
+            }
+            if k.bit(i) {
+                result = Some(match &result {
+                    Some(r) => self.add(r, point)?,
+                    None => point.clone(),
+                });
+            }
+        }
+        result.ok_or("multiplication by zero")
+    }
+
+    /// ψ(p X̃) / p mod p
+    fn log_over_p(&self, point: &Point) -> Result<BigUint, &'static str> {
+        let lifted = self.lift_point(point)?;
+        let (xa, ya) = self.mul(&lifted, &(&self.p - 1u32))?;
+        let diff = self.sub(&lifted.0, &xa);
+        if !(&diff % &self.p).is_zero() {
+            return Err("(p - 1)P is not -P, the curve is not anomalous");
+        }
+        let u = diff / &self.p;
+        let dy = self.sub(&lifted.1, &ya) % &self.p;
+        let dy_inv = dy.modinv(&self.p).ok_or("point of order 2")?;
+        Ok(u * dy_inv % &self.p)
+    }
+}
+
+/// log_P(Q) on a curve with exactly p points
+pub fn smart_attack(curve: &Curve, p: &Point, q: &Point) -> Result<BigUint, &'static str> {
+    if !curve.mul(p, &curve.p).is_infinity() {
+        return Err("pP != O, the curve is not anomalous");
+    }
+    if q.is_infinity() {
+        return Ok(BigUint::zero());
+    }
+    let p2 = &curve.p * &curve.p;
+    for t in 0u32..8 {
+        let lift = Lift {
+            p: curve.p.clone(),
+            a: (&curve.a + &curve.p * t) % &p2,
+            b: curve.b.clone(),
+            p2: p2.clone(),
+        };
+        let psi_p = lift.log_over_p(p)?;
+        if psi_p.is_zero() {
+            // Canonical lift, or P = O: try the next one
+            continue;
+        }
+        let psi_q = lift.log_over_p(q)?;
+        let k = psi_q * psi_p.modinv(&curve.p).expect("p is prime") % &curve.p;
+        if &curve.mul(p, &k) == q {
+            return Ok(k);
+        }
+    }
+    Err("no lift gave a usable p-adic logarithm")
+}
+
+/// y^2 = x^3 + 2 over p = 1099598312941 (40 bits) with exactly p points
+///
+/// From the CM method with j = 0: 4p = t^2 + 3v^2 with trace t = 1.
+pub fn demo_curve() -> (Curve, Point) {
+    (Curve::small(1099598312941, 0, 2), Point::new(1, 22506907390))
+}
+
+pub fn entry_point() {
+    println!("--- SMART'S ATTACK ---");
+    let (curve, g) = demo_curve();
+    let secret = BigUint::from(123_456_789_012u64);
+    let q = curve.mul(&g, &secret);
+    match smart_attack(&curve, &g, &q) {
+        Ok(k) => println!("40-bit anomalous curve: k = {k} (actual {secret}), no rho walk needed"),
+        Err(e) => println!("Smart's attack failed: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elliptic_point::group_structure::{count_points, enumerate_points};
+
+    #[test]
+    fn test_toy_anomalous_curve() {
+        let curve = Curve::small(13, 1, 6);
+        assert_eq!(count_points(&curve), 13);
+        let points = enumerate_points(&curve);
+        let g = points.iter().find(|p| !p.is_infinity()).unwrap().clone();
+        for k in 1..13u64 {
+            let q = curve.mul_u64(&g, k);
+            assert_eq!(smart_attack(&curve, &g, &q), Ok(BigUint::from(k)));
+        }
+        assert_eq!(smart_attack(&curve, &g, &Point::Infinity), Ok(BigUint::zero()));
+    }
+
+    #[test]
+    fn test_40_bit_curve_needs_a_non_canonical_lift() {
+        let (curve, g) = demo_curve();
+        assert!(curve.contains(&g));
+        let secret = BigUint::from(987_654_321_012u64);
+        let q = curve.mul(&g, &secret);
+        assert_eq!(smart_attack(&curve, &g, &q), Ok(secret));
+
+        // j = 0 lifted with the same coefficients is the canonical lift: ψ vanishes
+        let lift = Lift {
+            p: curve.p.clone(),
+            p2: &curve.p * &curve.p,
+            a: curve.a.clone(),
+            b: curve.b.clone(),
+        };
+        assert_eq!(lift.log_over_p(&g), Ok(BigUint::zero()));
+    }
+
+    #[test]
+    fn test_rejects_non_anomalous() {
+        let curve = Curve::f211();
+        let g = Point::new(0, 2);
+        assert_eq!(smart_attack(&curve, &g, &g), Err("pP != O, the curve is not anomalous"));
+    }
+}
@@ -0,0 +1,170 @@
+//! Signature encodings
+//!
+//! - DER: `SEQUENCE { INTEGER r, INTEGER s }`, variable length (X.509, TLS, Bitcoin)
+//! - Compact: r ‖ s, each left-padded to the byte length of n (JOSE, Ethereum)
+
+use num_bigint::BigUint;
+
+use super::signature::Signature;
+
+const DER_SEQUENCE: u8 = 0x30;
+const DER_INTEGER: u8 = 0x02;
+
+fn der_length(len: usize) -> Vec<u8> {
+    if len < 0x80 {
+        vec![len as u8]
+    } else {
+        let bytes: Vec<u8> = len.to_be_bytes().into_iter().skip_while(|b| *b == 0).collect();
+        let mut out = vec![0x80 | bytes.len() as u8];
+        out.extend(bytes);
+        out
+    }
+}
+
+/// Positive INTEGER: minimal big-endian, with a 0x00 in front if the top bit is set
+fn der_integer(value: &BigUint) -> Vec<u8> {
+    let mut body = value.to_bytes_be();
+    if body[0] & 0x80 != 0 {
+        body.insert(0, 0x00);
+    }
+    let mut out = vec![DER_INTEGER];
+    out.extend(der_length(body.len()));
+    out.extend(body);
+    out
+}
+
+/// Read a tag-length-value, returning (value, rest)
+/// Rejects non-minimal length encodings, since DER has exactly one encoding per value
+fn read_tlv(bytes: &[u8], tag: u8) -> Result<(&[u8], &[u8]), &'static str> {
+    let (&found, rest) = bytes.split_first().ok_or("unexpected end of input")?;
+    if found != tag {
+        return Err("unexpected DER tag");
+    }
+    let (&first, rest) = rest.split_first().ok_or("missing DER length")?;
+    let (len, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        let n = (first & 0x7f) as usize;
+        if n == 0 || n > 2 || rest.len() < n || rest[0] == 0 {
+            return Err("invalid DER length");
+        }
+        let len = rest[..n].iter().fold(0usize, |acc, b| (acc << 8) | *b as usize);
+        if len < 0x80 {
+            return Err("non-minimal DER length");
+        }
+        (len, &rest[n..])
+    };
+    if rest.len() < len {
+        return Err("DER length exceeds input");
+    }
+    Ok(rest.split_at(len))
+}
+
+fn parse_der_integer(body: &[u8]) -> Result<BigUint, &'static str> {
+    match body {
+        [] => Err("empty DER integer"),
+        [first, ..] if first & 0x80 != 0 => Err("negative DER integer"),
+        [0x00, second, ..] if second & 0x80 == 0 => Err("non-minimal DER integer"),
+        _ => Ok(BigUint::from_bytes_be(body)),
+    }
+}
+
+impl Signature {
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut body = der_integer(&self.r);
+        body.extend(der_integer(&self.s));
+        let mut out = vec![DER_SEQUENCE];
+        out.extend(der_length(body.len()));
+        out.extend(body);
+        out
+    }
+
+    pub fn from_der(bytes: &[u8]) -> Result<Self, &'static str> {
+        let (seq, trailing) = read_tlv(bytes, DER_SEQUENCE)?;
+        if !trailing.is_empty() {
+            return Err("trailing bytes after DER signature");
+        }
+        let (r, rest) = read_tlv(seq, DER_INTEGER)?;
+        let (s, rest) = read_tlv(rest, DER_INTEGER)?;
+        if !rest.is_empty() {
+            return Err("trailing bytes inside DER sequence");
+        }
+        Ok(Self {
+            r: parse_der_integer(r)?,
+            s: parse_der_integer(s)?,
+        })
+    }
+
+    /// r ‖ s, each exactly ceil(log2(n) / 8) bytes
+    /// Fails when r or s is not below n, since it might not fit its half
+    pub fn to_compact(&self, n: &BigUint) -> Result<Vec<u8>, &'static str> {
+        if &self.r >= n || &self.s >= n {
+            return Err("signature values out of range");
+        }
+        let len = (n.bits() as usize).div_ceil(8);
+        let mut out = Vec::with_capacity(2 * len);
+        for value in [&self.r, &self.s] {
+            let bytes = value.to_bytes_be();
+            out.extend(vec![0u8; len - bytes.len()]);
+            out.extend(bytes);
+        }
+        Ok(out)
+    }
+
+    pub fn from_compact(bytes: &[u8], n: &BigUint) -> Result<Self, &'static str> {
+        let len = (n.bits() as usize).div_ceil(8);
+        if bytes.len() != 2 * len {
+            return Err("wrong compact signature length");
+        }
+        Ok(Self {
+            r: BigUint::from_bytes_be(&bytes[..len]),
+            s: BigUint::from_bytes_be(&bytes[len..]),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::bytes_to_hex;
+    use crate::ecdsa::signature::{sign, KeyPair};
+    use crate::elliptic_point::curve::DomainParams;
+
+    #[test]
+    fn test_der_layout() {
+        // r = 1, s = 0x80 needs a leading zero to stay positive
+        let sig = Signature { r: BigUint::from(1u32), s: BigUint::from(0x80u32) };
+        assert_eq!(bytes_to_hex(&sig.to_der()), "300702010102020080");
+        assert_eq!(Signature::from_der(&sig.to_der()), Ok(sig));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let domain = DomainParams::secp256k1();
+        let keys = KeyPair::generate(&domain);
+        let sig = sign(&domain, &keys.secret, b"encode me");
+        assert_eq!(Signature::from_der(&sig.to_der()), Ok(sig.clone()));
+        let compact = sig.to_compact(&domain.n).unwrap();
+        assert_eq!(compact.len(), 64);
+        assert_eq!(Signature::from_compact(&compact, &domain.n), Ok(sig.clone()));
+
+        // s = n would need 33 bytes on secp256k1
+        let wide = Signature { r: sig.r, s: domain.n.clone() };
+        assert!(wide.to_compact(&domain.n).is_err());
+    }
+
+    #[test]
+    fn test_rejects_malformed_der() {
+        let sig = Signature { r: BigUint::from(5u32), s: BigUint::from(7u32) };
+        let der = sig.to_der();
+        let mut trailing = der.clone();
+        trailing.push(0);
+        assert!(Signature::from_der(&trailing).is_err());
+        assert!(Signature::from_der(&der[..der.len() - 1]).is_err());
+        // INTEGER 5 padded with a useless zero byte
+        assert!(Signature::from_der(&[0x30, 0x07, 0x02, 0x02, 0x00, 0x05, 0x02, 0x01, 0x07]).is_err());
+        // Negative INTEGER
+        assert!(Signature::from_der(&[0x30, 0x06, 0x02, 0x01, 0x85, 0x02, 0x01, 0x07]).is_err());
+        assert!(Signature::from_compact(&[0u8; 63], &DomainParams::secp256k1().n).is_err());
+    }
+}
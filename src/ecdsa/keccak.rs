@@ -0,0 +1,108 @@
+//! Keccak-256, the hash Ethereum uses for addresses and transaction hashes
+//!
+//! This is the original Keccak submission, not FIPS 202 SHA3-256: the two only differ
+//! in the padding byte (0x01 here, 0x06 for SHA3), which is enough to change every
+//! output. Sponge with rate 136 bytes over the Keccak-f[1600] permutation.
+
+const RATE: usize = 136;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+
+/// Rotation offsets and lane order for the combined rho and pi steps
+const RHO: [u32; 24] = [1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44];
+const PI: [usize; 24] = [10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1];
+
+/// Keccak-f[1600], state lanes indexed x + 5y
+fn keccak_f(state: &mut [u64; 25]) {
+    for rc in ROUND_CONSTANTS {
+        // theta
+        let mut c = [0u64; 5];
+        for x in 0..5 {
+            c[x] = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[x + 5 * y] ^= d;
+            }
+        }
+        // rho and pi
+        let mut last = state[1];
+        for (rotation, lane) in RHO.iter().zip(PI) {
+            let tmp = state[lane];
+            state[lane] = last.rotate_left(*rotation);
+            last = tmp;
+        }
+        // chi
+        for y in 0..5 {
+            let row: [u64; 5] = std::array::from_fn(|x| state[x + 5 * y]);
+            for x in 0..5 {
+                state[x + 5 * y] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+        // iota
+        state[0] ^= rc;
+    }
+}
+
+fn absorb_block(state: &mut [u64; 25], block: &[u8]) {
+    for (lane, chunk) in state.iter_mut().zip(block.chunks(8)) {
+        *lane ^= u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    keccak_f(state);
+}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut state = [0u64; 25];
+    let mut blocks = data.chunks_exact(RATE);
+    for block in &mut blocks {
+        absorb_block(&mut state, block);
+    }
+    // pad10*1 with the Keccak domain bit
+    let rest = blocks.remainder();
+    let mut last = [0u8; RATE];
+    last[..rest.len()].copy_from_slice(rest);
+    last[rest.len()] ^= 0x01;
+    last[RATE - 1] ^= 0x80;
+    absorb_block(&mut state, &last);
+
+    let mut out = [0u8; 32];
+    for (chunk, lane) in out.chunks_mut(8).zip(state) {
+        chunk.copy_from_slice(&lane.to_le_bytes());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::bytes_to_hex;
+
+    #[test]
+    fn test_keccak256_vectors() {
+        assert_eq!(
+            bytes_to_hex(&keccak256(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(
+            bytes_to_hex(&keccak256(b"abc")),
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"
+        );
+        // Exactly one block of input forces a second, padding-only block
+        assert_eq!(
+            bytes_to_hex(&keccak256(&b"a".repeat(RATE))),
+            "a6c4d403279fe3e0af03729caada8374b5ca54d8065329a3ebcaeb4b60aa386e"
+        );
+        assert_eq!(
+            bytes_to_hex(&keccak256(&b"a".repeat(300))),
+            "5b7e0e47a96f32a88b4f14ca177982790807c40e1a105742ba0fc1babe1ef826"
+        );
+    }
+}
@@ -0,0 +1,6 @@
+pub mod signature;
+pub mod rfc6979;
+pub mod encoding;
+pub mod keccak;
+pub mod recovery;
+pub use signature::*;
@@ -0,0 +1,260 @@
+//! Public key recovery (SEC 1 v2, section 4.1.6), what Ethereum calls `ecrecover`
+//!
+//! Verification checks u1 G + u2 Q against r. Run backwards, the signature fixes Q:
+//!   s R = z G + r Q   =>   Q = r^-1 (s R - z G)
+//! where R = kG is the nonce point. Only R.x mod n survives in the signature, so the
+//! signer attaches a recovery id saying which R it was:
+//!   bit 0      parity of R.y
+//!   bits 1..   j with R.x = r + j n (R.x wraps past n only when p > n)
+//! On secp256k1 j = 1 needs R.x in [n, p), a 2^-128 event, so in practice Ethereum's
+//! v = 27 + id is 27 or 28. Toy curves hit every id.
+
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+use sha2::{Digest, Sha256};
+
+use super::keccak::keccak256;
+use super::rfc6979::NonceGenerator;
+use super::signature::{hash_to_scalar, sign_with_nonce, Signature};
+use crate::conversion::bytes_to_hex;
+use crate::elliptic_point::curve::{Curve, DomainParams, Point};
+use crate::elliptic_point::sec1::{decompress, encode};
+
+/// Offset Ethereum adds to the recovery id to get v
+pub const ETHEREUM_V_OFFSET: u8 = 27;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoverableSignature {
+    pub sig: Signature,
+    pub recovery_id: u8,
+}
+
+impl RecoverableSignature {
+    /// Low-s form: s -> n - s also negates R, which flips the parity bit
+    pub fn normalize_s(&self, n: &BigUint) -> Self {
+        if self.sig.is_low_s(n) {
+            self.clone()
+        } else {
+            Self {
+                sig: self.sig.normalize_s(n),
+                recovery_id: self.recovery_id ^ 1,
+            }
+        }
+    }
+}
+
+/// The recovery id of the nonce point R, None if x / n does not fit in 7 bits
+pub fn recovery_id(domain: &DomainParams, nonce_point: &Point) -> Option<u8> {
+    match nonce_point {
+        Point::Infinity => None,
+        Point::Affine { x, y } => {
+            let j = (x / &domain.n).to_u8().filter(|j| *j <= u8::MAX >> 1)?;
+            Some((j << 1) | y.bit(0) as u8)
+        }
+    }
+}
+
+/// Rebuild R from r and the recovery id
+fn lift_x(domain: &DomainParams, r: &BigUint, recovery_id: u8) -> Result<Point, &'static str> {
+    let x = r + &domain.n * BigUint::from(recovery_id >> 1);
+    if x >= domain.curve.p {
+        return Err("recovery id points past the field");
+    }
+    let nonce_point = decompress(&domain.curve, &x, recovery_id & 1 == 1)?;
+    // With a cofactor the lifted point may sit outside the order n subgroup
+    if !domain.curve.mul(&nonce_point, &domain.n).is_infinity() {
+        return Err("lifted point is not in the subgroup");
+    }
+    Ok(nonce_point)
+}
+
+/// Q = r^-1 (s R - z G)
+pub fn recover_public_key(
+    domain: &DomainParams,
+    hash: &[u8],
+    sig: &Signature,
+    recovery_id: u8,
+) -> Result<Point, &'static str> {
+    let n = &domain.n;
+    if sig.r.is_zero() || sig.s.is_zero() || &sig.r >= n || &sig.s >= n {
+        return Err("signature values out of range");
+    }
+    let nonce_point = lift_x(domain, &sig.r, recovery_id)?;
+    let z = hash_to_scalar(domain, hash) % n;
+    let r_inv = sig.r.modinv(n).ok_or("r is not invertible")?;
+    let u1 = (&r_inv * ((n - z) % n)) % n;
+    let u2 = (&r_inv * &sig.s) % n;
+    let public = domain
+        .curve
+        .add(&domain.mul_g(&u1), &domain.curve.mul(&nonce_point, &u2));
+    if public.is_infinity() {
+        return Err("recovered the point at infinity");
+    }
+    Ok(public)
+}
+
+/// Every recovery id that could apply to a curve: j runs while r + j n can still be < p
+fn possible_ids(domain: &DomainParams) -> impl Iterator<Item = u8> {
+    let max_j = ((&domain.curve.p - 1u32) / &domain.n).to_u8().map_or(u8::MAX >> 1, |j| j.min(u8::MAX >> 1));
+    0..=(max_j << 1 | 1)
+}
+
+/// Which recovery id turns `sig` back into `public`, for signers that did not keep it
+pub fn find_recovery_id(domain: &DomainParams, hash: &[u8], sig: &Signature, public: &Point) -> Option<u8> {
+    possible_ids(domain).find(|id| recover_public_key(domain, hash, sig, *id).as_ref() == Ok(public))
+}
+
+pub fn sign_recoverable_with_nonce(
+    domain: &DomainParams,
+    secret: &BigUint,
+    z: &BigUint,
+    k: &BigUint,
+) -> Option<RecoverableSignature> {
+    let sig = sign_with_nonce(domain, secret, z, k)?;
+    let recovery_id = recovery_id(domain, &domain.mul_g(k))?;
+    Some(RecoverableSignature { sig, recovery_id })
+}
+
+/// `sign_prehashed` that also reports the recovery id
+pub fn sign_recoverable_prehashed(domain: &DomainParams, secret: &BigUint, hash: &[u8]) -> RecoverableSignature {
+    let z = hash_to_scalar(domain, hash);
+    NonceGenerator::new(&domain.n, secret, hash)
+        .find_map(|k| sign_recoverable_with_nonce(domain, secret, &z, &k))
+        .unwrap()
+}
+
+pub fn sign_recoverable(domain: &DomainParams, secret: &BigUint, msg: &[u8]) -> RecoverableSignature {
+    sign_recoverable_prehashed(domain, secret, &Sha256::digest(msg))
+}
+
+/// Ethereum-style address: last 20 bytes of keccak256(x ‖ y)
+/// Only meaningful for secp256k1, but any curve's points hash the same way.
+pub fn address(curve: &Curve, public: &Point) -> [u8; 20] {
+    let uncompressed = encode(curve, public, false);
+    let hash = keccak256(&uncompressed[1..]);
+    hash[12..].try_into().unwrap()
+}
+
+/// EIP-55 mixed-case checksum: a hex letter is uppercased when the matching nibble of
+/// keccak256(lowercase hex) is 8 or more
+pub fn to_checksum_address(address: &[u8; 20]) -> String {
+    let hex = bytes_to_hex(address);
+    let hash = keccak256(hex.as_bytes());
+    let mixed: String = hex
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> (4 * (1 - i % 2))) & 0x0f;
+            if nibble >= 8 { c.to_ascii_uppercase() } else { c }
+        })
+        .collect();
+    format!("0x{mixed}")
+}
+
+/// The EVM precompile: secp256k1, a 32-byte hash and v = 27 or 28
+pub fn ecrecover(hash: &[u8; 32], v: u8, r: &BigUint, s: &BigUint) -> Result<[u8; 20], &'static str> {
+    let recovery_id = v.checked_sub(ETHEREUM_V_OFFSET).filter(|id| *id < 2).ok_or("v must be 27 or 28")?;
+    let domain = DomainParams::secp256k1();
+    let sig = Signature { r: r.clone(), s: s.clone() };
+    let public = recover_public_key(&domain, hash, &sig, recovery_id)?;
+    Ok(address(&domain.curve, &public))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecdsa::signature::{verify_prehashed, KeyPair};
+
+    #[test]
+    fn test_address_vectors() {
+        // The well known address of private key 1
+        let domain = DomainParams::secp256k1();
+        let addr = address(&domain.curve, &domain.g);
+        assert_eq!(to_checksum_address(&addr), "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf");
+
+        // EIP-55 test vector
+        let addr: [u8; 20] = crate::conversion::hex_to_bytes("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed")
+            .try_into()
+            .unwrap();
+        assert_eq!(to_checksum_address(&addr), "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed");
+    }
+
+    #[test]
+    fn test_secp256k1_recovery() {
+        let domain = DomainParams::secp256k1();
+        let keys = KeyPair::from_secret(&domain, BigUint::from(0xc0ffeeu32));
+        let mut seen = [false; 2];
+        for i in 0..16u32 {
+            let hash = Sha256::digest(i.to_be_bytes());
+            let signed = sign_recoverable_prehashed(&domain, &keys.secret, &hash);
+            seen[signed.recovery_id as usize] = true;
+            assert!(verify_prehashed(&domain, &keys.public, &hash, &signed.sig));
+            assert_eq!(recover_public_key(&domain, &hash, &signed.sig, signed.recovery_id), Ok(keys.public.clone()));
+
+            // The other parity gives a different (valid) key, ids 2 and 3 run off the field
+            let other = recover_public_key(&domain, &hash, &signed.sig, signed.recovery_id ^ 1).unwrap();
+            assert_ne!(other, keys.public);
+            assert!(recover_public_key(&domain, &hash, &signed.sig, signed.recovery_id | 2).is_err());
+
+            let low = signed.normalize_s(&domain.n);
+            assert_eq!(recover_public_key(&domain, &hash, &low.sig, low.recovery_id), Ok(keys.public.clone()));
+            assert_eq!(find_recovery_id(&domain, &hash, &low.sig, &keys.public), Some(low.recovery_id));
+        }
+        assert_eq!(seen, [true, true]);
+    }
+
+    #[test]
+    fn test_all_four_ids_on_toy_curve() {
+        // y^2 = x^3 + 2 over F211 has prime order 199 < 211, so R.x in [199, 211) wraps
+        let domain = DomainParams::toy(Curve::small(211, 0, 2));
+        assert_eq!(domain.n, BigUint::from(199u32));
+        let keys = KeyPair::from_secret(&domain, BigUint::from(77u32));
+        let z = BigUint::from(123u32);
+        let mut seen = [false; 4];
+        for k in 1..199u32 {
+            let Some(signed) = sign_recoverable_with_nonce(&domain, &keys.secret, &z, &BigUint::from(k)) else {
+                continue;
+            };
+            seen[signed.recovery_id as usize] = true;
+            let hash = z.to_bytes_be();
+            assert_eq!(recover_public_key(&domain, &hash, &signed.sig, signed.recovery_id), Ok(keys.public.clone()));
+        }
+        assert_eq!(seen, [true; 4]);
+    }
+
+    #[test]
+    fn test_cofactor_curve() {
+        // F211 with b = 4: n = 61, so R.x can be r + j n for j up to 3
+        let domain = DomainParams::f211();
+        let keys = KeyPair::from_secret(&domain, BigUint::from(5u32));
+        for msg in [&b"one"[..], b"two", b"three"] {
+            let signed = sign_recoverable(&domain, &keys.secret, msg);
+            let hash = Sha256::digest(msg);
+            assert_eq!(recover_public_key(&domain, &hash, &signed.sig, signed.recovery_id), Ok(keys.public.clone()));
+            assert_eq!(find_recovery_id(&domain, &hash, &signed.sig, &keys.public), Some(signed.recovery_id));
+        }
+    }
+
+    #[test]
+    fn test_recovery_id_range() {
+        // j = x / n past 127 has no room for the parity bit
+        let mut tiny_n = DomainParams::secp256k1();
+        let Point::Affine { x, .. } = tiny_n.g.clone() else { unreachable!() };
+        tiny_n.n = x.clone() / 200u32 + 1u32;
+        assert!((&x / &tiny_n.n).to_u8().unwrap() >= 128);
+        assert_eq!(recovery_id(&tiny_n, &tiny_n.g), None);
+    }
+
+    #[test]
+    fn test_ecrecover() {
+        let domain = DomainParams::secp256k1();
+        let keys = KeyPair::from_secret(&domain, BigUint::from(1u32));
+        let hash: [u8; 32] = keccak256(b"transfer 1 eth");
+        let signed = sign_recoverable_prehashed(&domain, &keys.secret, &hash).normalize_s(&domain.n);
+        let v = ETHEREUM_V_OFFSET + signed.recovery_id;
+        let addr = ecrecover(&hash, v, &signed.sig.r, &signed.sig.s).unwrap();
+        assert_eq!(to_checksum_address(&addr), "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf");
+        assert!(ecrecover(&hash, 29, &signed.sig.r, &signed.sig.s).is_err());
+        assert!(ecrecover(&hash, v, &BigUint::zero(), &signed.sig.s).is_err());
+    }
+}
@@ -0,0 +1,154 @@
+//! Deterministic ECDSA nonces (RFC 6979) with HMAC-SHA256
+//!
+//! The nonce k is derived from the private key and the message hash, so the same
+//! message always gets the same k and two different messages never share one.
+//! That removes the RNG from signing entirely, which is exactly what the PS3 signer
+//! got wrong (see `nonce_reuse`).
+
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+const BLOCK_SIZE: usize = 64;
+
+/// HMAC-SHA256 (RFC 2104) over the concatenation of `data`
+pub fn hmac_sha256(key: &[u8], data: &[&[u8]]) -> [u8; 32] {
+    // Keys longer than a block are hashed first, then everything is zero padded
+    let mut block_key = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block_key[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block_key[..key.len()].copy_from_slice(key);
+    }
+    let ipad: Vec<u8> = block_key.iter().map(|b| b ^ 0x36).collect();
+    let opad: Vec<u8> = block_key.iter().map(|b| b ^ 0x5c).collect();
+
+    let mut inner = Sha256::new();
+    inner.update(&ipad);
+    for chunk in data {
+        inner.update(chunk);
+    }
+    let inner = inner.finalize();
+    Sha256::new().chain_update(&opad).chain_update(inner).finalize().into()
+}
+
+/// Leftmost qlen bits of the input as an integer (RFC 6979 section 2.3.2)
+/// This is also how ECDSA truncates the message hash to the size of n.
+pub fn bits2int(bytes: &[u8], qlen: u64) -> BigUint {
+    let value = BigUint::from_bytes_be(bytes);
+    let blen = bytes.len() as u64 * 8;
+    if blen > qlen { value >> (blen - qlen) } else { value }
+}
+
+/// Big-endian, left-padded to rlen bytes (section 2.3.3)
+fn int2octets(x: &BigUint, rlen: usize) -> Vec<u8> {
+    let bytes = x.to_bytes_be();
+    let mut out = vec![0u8; rlen.saturating_sub(bytes.len())];
+    out.extend_from_slice(&bytes);
+    out
+}
+
+/// bits2int then reduce mod q (section 2.3.4)
+fn bits2octets(bytes: &[u8], q: &BigUint, rlen: usize) -> Vec<u8> {
+    let z = bits2int(bytes, q.bits()) % q;
+    int2octets(&z, rlen)
+}
+
+/// The HMAC_DRBG of section 3.2, yielding candidate nonces in [1, q-1]
+///
+/// Signing takes the first one, and only asks for the next if r or s came out as 0.
+pub struct NonceGenerator {
+    q: BigUint,
+    k: [u8; 32],
+    v: [u8; 32],
+}
+
+impl NonceGenerator {
+    /// `secret` is the private key x, `h1` the message hash H(m)
+    pub fn new(q: &BigUint, secret: &BigUint, h1: &[u8]) -> Self {
+        let rlen = (q.bits() as usize).div_ceil(8);
+        let x = int2octets(secret, rlen);
+        let h = bits2octets(h1, q, rlen);
+
+        // Steps b - g
+        let mut v = [0x01u8; 32];
+        let mut k = [0x00u8; 32];
+        k = hmac_sha256(&k, &[&v, &[0x00], &x, &h]);
+        v = hmac_sha256(&k, &[&v]);
+        k = hmac_sha256(&k, &[&v, &[0x01], &x, &h]);
+        v = hmac_sha256(&k, &[&v]);
+        Self { q: q.clone(), k, v }
+    }
+}
+
+impl Iterator for NonceGenerator {
+    type Item = BigUint;
+
+    /// Step h
+    fn next(&mut self) -> Option<BigUint> {
+        let qlen = self.q.bits();
+        loop {
+            let mut t = Vec::new();
+            while (t.len() as u64) * 8 < qlen {
+                self.v = hmac_sha256(&self.k, &[&self.v]);
+                t.extend_from_slice(&self.v);
+            }
+            let candidate = bits2int(&t, qlen);
+            // Prepare the state for the next candidate whether or not this one is used
+            self.k = hmac_sha256(&self.k, &[&self.v, &[0x00]]);
+            self.v = hmac_sha256(&self.k, &[&self.v]);
+            if candidate > BigUint::ZERO && candidate < self.q {
+                return Some(candidate);
+            }
+        }
+    }
+}
+
+/// The first RFC 6979 nonce for (x, H(m))
+pub fn generate_k(q: &BigUint, secret: &BigUint, h1: &[u8]) -> BigUint {
+    NonceGenerator::new(q, secret, h1).next().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::{bytes_to_hex, hex_to_bytes};
+    use crate::elliptic_point::curve::{hex_biguint, DomainParams};
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 test case 2
+        let mac = hmac_sha256(b"Jefe", &[b"what do ya want ", b"for nothing?"]);
+        assert_eq!(
+            bytes_to_hex(&mac),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Test case 6: key longer than the block size
+        let key = hex_to_bytes(&"aa".repeat(131));
+        let mac = hmac_sha256(&key, &[b"Test Using Larger Than Block-Size Key - Hash Key First"]);
+        assert_eq!(
+            bytes_to_hex(&mac),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn test_p256_nonces() {
+        // RFC 6979 appendix A.2.5, P-256 with SHA-256
+        let n = DomainParams::p256().n;
+        let x = hex_biguint("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721");
+        let k = generate_k(&n, &x, &Sha256::digest(b"sample"));
+        assert_eq!(k, hex_biguint("a6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60"));
+        let k = generate_k(&n, &x, &Sha256::digest(b"test"));
+        assert_eq!(k, hex_biguint("d16b6ae827f17175e040871a1c7ec3500192c4c92677336ec2537acaee0008e0"));
+    }
+
+    #[test]
+    fn test_toy_nonces_in_range() {
+        let n = DomainParams::f211().n;
+        let mut nonces = NonceGenerator::new(&n, &BigUint::from(7u32), &Sha256::digest(b"toy"));
+        for _ in 0..20 {
+            let k = nonces.next().unwrap();
+            assert!(k > BigUint::ZERO && k < n);
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! ECDSA key generation, signing and verification over any `DomainParams`
+//!
+//! Sign (private key x, public key Q = xG):
+//! 1. z = leftmost bits of H(m), k = nonce
+//! 2. r = (kG).x mod n
+//! 3. s = k^-1 (z + r x) mod n
+//!
+//! Verify: u1 = z s^-1, u2 = r s^-1, accept if (u1 G + u2 Q).x mod n == r
+//!
+//! The nonce must be secret and never repeat. `sign` derives it with RFC 6979;
+//! `sign_with_nonce` takes it from the caller so the attacks in `nonce_reuse` can be
+//! fed the broken signers they target.
+
+use std::fmt;
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+use sha2::{Digest, Sha256};
+
+use super::rfc6979::{bits2int, NonceGenerator};
+use crate::elliptic_point::curve::{DomainParams, Point};
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyPair {
+    pub secret: BigUint,
+    pub public: Point,
+}
+
+impl fmt::Debug for KeyPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyPair").field("secret", &"<redacted>").field("public", &self.public).finish()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    pub r: BigUint,
+    pub s: BigUint,
+}
+
+impl KeyPair {
+    /// Random private key in [1, n-1]
+    pub fn generate(domain: &DomainParams) -> Self {
+        Self::from_secret(domain, domain.random_scalar())
+    }
+
+    pub fn from_secret(domain: &DomainParams, secret: BigUint) -> Self {
+        let public = domain.mul_g(&secret);
+        Self { secret, public }
+    }
+}
+
+impl Signature {
+    /// s and n - s both verify (the equation only sees R up to sign), so Bitcoin and
+    /// Ethereum only accept the lower one to stop third parties re-signing a transaction
+    pub fn is_low_s(&self, n: &BigUint) -> bool {
+        self.s <= n >> 1
+    }
+
+    /// Flip s to n - s if it is in the upper half
+    pub fn normalize_s(&self, n: &BigUint) -> Self {
+        if self.is_low_s(n) {
+            self.clone()
+        } else {
+            Self {
+                r: self.r.clone(),
+                s: n - &self.s,
+            }
+        }
+    }
+}
+
+/// z = leftmost log2(n) bits of the hash, as an integer
+pub fn hash_to_scalar(domain: &DomainParams, hash: &[u8]) -> BigUint {
+    bits2int(hash, domain.n.bits())
+}
+
+/// Public keys coming from outside must be real points of order n
+pub fn is_valid_public_key(domain: &DomainParams, public: &Point) -> bool {
+    !public.is_infinity()
+        && domain.curve.contains(public)
+        && domain.curve.mul(public, &domain.n).is_infinity()
+}
+
+/// Raw ECDSA with a caller-chosen nonce k
+/// Returns None when r or s is 0, in which case a new k is needed
+pub fn sign_with_nonce(domain: &DomainParams, secret: &BigUint, z: &BigUint, k: &BigUint) -> Option<Signature> {
+    let n = &domain.n;
+    let r = match domain.mul_g(k) {
+        Point::Infinity => return None,
+        Point::Affine { x, .. } => x % n,
+    };
+    if r.is_zero() {
+        return None;
+    }
+    let k_inv = k.modinv(n)?;
+    let s = (k_inv * ((z + &r * secret) % n)) % n;
+    if s.is_zero() {
+        return None;
+    }
+    Some(Signature { r, s })
+}
+
+/// Sign a precomputed hash with an RFC 6979 nonce
+pub fn sign_prehashed(domain: &DomainParams, secret: &BigUint, hash: &[u8]) -> Signature {
+    let z = hash_to_scalar(domain, hash);
+    NonceGenerator::new(&domain.n, secret, hash)
+        .find_map(|k| sign_with_nonce(domain, secret, &z, &k))
+        .unwrap()
+}
+
+/// ECDSA-SHA256 with a deterministic nonce
+pub fn sign(domain: &DomainParams, secret: &BigUint, msg: &[u8]) -> Signature {
+    sign_prehashed(domain, secret, &Sha256::digest(msg))
+}
+
+pub fn verify_prehashed(domain: &DomainParams, public: &Point, hash: &[u8], sig: &Signature) -> bool {
+    let n = &domain.n;
+    if sig.r.is_zero() || sig.s.is_zero() || &sig.r >= n || &sig.s >= n {
+        return false;
+    }
+    if !is_valid_public_key(domain, public) {
+        return false;
+    }
+    let z = hash_to_scalar(domain, hash);
+    let w = match sig.s.modinv(n) {
+        Some(w) => w,
+        None => return false,
+    };
+    let u1 = (&z * &w) % n;
+    let u2 = (&sig.r * &w) % n;
+    let point = domain
+        .curve
+        .add(&domain.mul_g(&u1), &domain.curve.mul(public, &u2));
+    match point {
+        Point::Infinity => false,
+        Point::Affine { x, .. } => x % n == sig.r,
+    }
+}
+
+pub fn verify(domain: &DomainParams, public: &Point, msg: &[u8], sig: &Signature) -> bool {
+    verify_prehashed(domain, public, &Sha256::digest(msg), sig)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elliptic_point::curve::hex_biguint;
+
+    #[test]
+    fn test_p256_rfc6979_vectors() {
+        // RFC 6979 appendix A.2.5
+        let domain = DomainParams::p256();
+        let x = hex_biguint("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721");
+        let keys = KeyPair::from_secret(&domain, x);
+        assert_eq!(
+            keys.public,
+            Point::Affine {
+                x: hex_biguint("60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6"),
+                y: hex_biguint("7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299"),
+            }
+        );
+
+        let sig = sign(&domain, &keys.secret, b"sample");
+        assert_eq!(sig.r, hex_biguint("efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716"));
+        assert_eq!(sig.s, hex_biguint("f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8"));
+        assert!(verify(&domain, &keys.public, b"sample", &sig));
+
+        let sig = sign(&domain, &keys.secret, b"test");
+        assert_eq!(sig.r, hex_biguint("f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367"));
+        assert_eq!(sig.s, hex_biguint("019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083"));
+    }
+
+    #[test]
+    fn test_secp256k1_vector() {
+        // Private key 1 signing "Satoshi Nakamoto" with an RFC 6979 nonce, low-s form
+        let domain = DomainParams::secp256k1();
+        let keys = KeyPair::from_secret(&domain, BigUint::from(1u32));
+        assert_eq!(keys.public, domain.g);
+        let sig = sign(&domain, &keys.secret, b"Satoshi Nakamoto").normalize_s(&domain.n);
+        assert_eq!(sig.r, hex_biguint("934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8"));
+        assert_eq!(sig.s, hex_biguint("2442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5"));
+    }
+
+    #[test]
+    fn test_secp256k1_sign_verify() {
+        let domain = DomainParams::secp256k1();
+        let keys = KeyPair::generate(&domain);
+        let sig = sign(&domain, &keys.secret, b"hello zk");
+        assert!(verify(&domain, &keys.public, b"hello zk", &sig));
+        assert!(!verify(&domain, &keys.public, b"hello zk!", &sig));
+
+        // Both s and n - s verify, only one is low-s
+        let low = sig.normalize_s(&domain.n);
+        let high = Signature { r: low.r.clone(), s: &domain.n - &low.s };
+        assert!(low.is_low_s(&domain.n));
+        assert!(!high.is_low_s(&domain.n));
+        assert!(verify(&domain, &keys.public, b"hello zk", &low));
+        assert!(verify(&domain, &keys.public, b"hello zk", &high));
+
+        let other = KeyPair::generate(&domain);
+        assert!(!verify(&domain, &other.public, b"hello zk", &sig));
+
+        let printed = format!("{keys:?}");
+        assert!(printed.contains("<redacted>"));
+        assert!(!printed.contains(&keys.secret.to_string()));
+    }
+
+    #[test]
+    fn test_toy_curve_ecdsa() {
+        let domain = DomainParams::f211();
+        let keys = KeyPair::from_secret(&domain, BigUint::from(17u32));
+        for msg in [&b"a"[..], b"b", b"c", b"d"] {
+            let sig = sign(&domain, &keys.secret, msg);
+            assert!(verify(&domain, &keys.public, msg, &sig));
+        }
+        // Out of range values are rejected before any curve math
+        let bad = Signature { r: domain.n.clone(), s: BigUint::from(1u32) };
+        assert!(!verify(&domain, &keys.public, b"a", &bad));
+        assert!(!verify(&domain, &Point::Infinity, b"a", &sign(&domain, &keys.secret, b"a")));
+    }
+
+    #[test]
+    fn test_deterministic_vs_static_nonce() {
+        // RFC 6979: same message -> same signature, different messages -> different r
+        let domain = DomainParams::secp256k1();
+        let keys = KeyPair::from_secret(&domain, BigUint::from(0xdeadbeefu64));
+        assert_eq!(sign(&domain, &keys.secret, b"m1"), sign(&domain, &keys.secret, b"m1"));
+        assert_ne!(sign(&domain, &keys.secret, b"m1").r, sign(&domain, &keys.secret, b"m2").r);
+
+        // The PS3 signer: one static k, so every signature shares r
+        let k = BigUint::from(4u32); // chosen by fair dice roll
+        let z1 = hash_to_scalar(&domain, &Sha256::digest(b"m1"));
+        let z2 = hash_to_scalar(&domain, &Sha256::digest(b"m2"));
+        let sig1 = sign_with_nonce(&domain, &keys.secret, &z1, &k).unwrap();
+        let sig2 = sign_with_nonce(&domain, &keys.secret, &z2, &k).unwrap();
+        assert_eq!(sig1.r, sig2.r);
+        assert!(verify(&domain, &keys.public, b"m1", &sig1));
+        assert!(verify(&domain, &keys.public, b"m2", &sig2));
+    }
+}
@@ -0,0 +1,273 @@
+//! Ed25519 (RFC 8032) on the crate's own edwards25519 arithmetic
+//!
+//! Compared with ECDSA (`crate::ecdsa`):
+//! - the nonce is r = H(prefix ‖ M), derived from the key and message like RFC 6979
+//!   but without the HMAC-DRBG machinery
+//! - it is a Schnorr signature: S = r + H(R ‖ A ‖ M) s, linear in the secret, with no
+//!   modular inversion anywhere
+//! - R itself is sent, not R.x mod n, so there is no malleable sign and no recovery id
+//!
+//! Key expansion: h = SHA-512(seed), s = clamp(h[0..32]), prefix = h[32..64], A = sB.
+//!
+//! Verification comes in two flavours that only disagree on points with a
+//! small-order component: cofactored checks [8][S]B = [8]R + [8][k]A, cofactorless
+//! checks [S]B = R + [k]A. RFC 8032 allows either, and implementations that mix the
+//! two can disagree about which signatures are valid.
+
+use std::fmt;
+
+use num_bigint::BigUint;
+use rand::{RngCore, rng};
+use sha2::{Digest, Sha512};
+
+use crate::elliptic_point::edwards::{EdwardsDomain, EdwardsPoint};
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyPair {
+    /// The 32-byte private key, everything else is derived from it
+    pub seed: [u8; 32],
+    pub public: [u8; 32],
+}
+
+impl fmt::Debug for KeyPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyPair").field("seed", &"<redacted>").field("public", &self.public).finish()
+    }
+}
+
+/// What the seed expands into
+#[derive(Clone, PartialEq, Eq)]
+pub struct ExpandedSecret {
+    pub scalar: BigUint,
+    pub prefix: [u8; 32],
+}
+
+impl fmt::Debug for ExpandedSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExpandedSecret").field("scalar", &"<redacted>").field("prefix", &"<redacted>").finish()
+    }
+}
+
+/// R ‖ S, 64 bytes on the wire
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    pub r: [u8; 32],
+    pub s: [u8; 32],
+}
+
+impl Signature {
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut out = [0u8; 64];
+        out[..32].copy_from_slice(&self.r);
+        out[32..].copy_from_slice(&self.s);
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8; 64]) -> Self {
+        Self {
+            r: bytes[..32].try_into().unwrap(),
+            s: bytes[32..].try_into().unwrap(),
+        }
+    }
+}
+
+/// Which group equation `verify_with` checks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verification {
+    Cofactored,
+    Cofactorless,
+}
+
+/// SHA-512 of the concatenation, as a little-endian integer mod L
+fn hash_to_scalar(domain: &EdwardsDomain, parts: &[&[u8]]) -> BigUint {
+    let mut hasher = Sha512::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    BigUint::from_bytes_le(&hasher.finalize()) % &domain.l
+}
+
+fn scalar_to_bytes(s: &BigUint) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    let bytes = s.to_bytes_le();
+    out[..bytes.len()].copy_from_slice(&bytes);
+    out
+}
+
+fn point_to_bytes(domain: &EdwardsDomain, point: &EdwardsPoint) -> [u8; 32] {
+    domain.curve.encode(point).try_into().unwrap()
+}
+
+/// Clear the low 3 bits (a multiple of the cofactor) and bit 255, set bit 254
+pub fn expand_secret(seed: &[u8; 32]) -> ExpandedSecret {
+    let h = Sha512::digest(seed);
+    let mut scalar: [u8; 32] = h[..32].try_into().unwrap();
+    scalar[0] &= 248;
+    scalar[31] &= 127;
+    scalar[31] |= 64;
+    ExpandedSecret {
+        scalar: BigUint::from_bytes_le(&scalar),
+        prefix: h[32..].try_into().unwrap(),
+    }
+}
+
+impl KeyPair {
+    pub fn generate() -> Self {
+        let mut seed = [0u8; 32];
+        rng().fill_bytes(&mut seed);
+        Self::from_seed(seed)
+    }
+
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        let domain = EdwardsDomain::ed25519();
+        let secret = expand_secret(&seed);
+        let public = point_to_bytes(&domain, &domain.mul_base(&secret.scalar));
+        Self { seed, public }
+    }
+}
+
+/// Deterministic signing, section 5.1.6
+pub fn sign(keys: &KeyPair, msg: &[u8]) -> Signature {
+    let domain = EdwardsDomain::ed25519();
+    let secret = expand_secret(&keys.seed);
+    let r = hash_to_scalar(&domain, &[&secret.prefix, msg]);
+    let big_r = point_to_bytes(&domain, &domain.mul_base(&r));
+    let k = hash_to_scalar(&domain, &[&big_r, &keys.public, msg]);
+    let s = (r + k * secret.scalar) % &domain.l;
+    Signature { r: big_r, s: scalar_to_bytes(&s) }
+}
+
+pub fn verify_with(public: &[u8; 32], msg: &[u8], sig: &Signature, mode: Verification) -> bool {
+    let domain = EdwardsDomain::ed25519();
+    let curve = &domain.curve;
+    let (Ok(a), Ok(r)) = (curve.decode(public), curve.decode(&sig.r)) else {
+        return false;
+    };
+    // S >= L would make signatures malleable (S and S + L both verify)
+    let s = BigUint::from_bytes_le(&sig.s);
+    if s >= domain.l {
+        return false;
+    }
+    let k = hash_to_scalar(&domain, &[&sig.r, public, msg]);
+    let lhs = domain.mul_base(&s);
+    let rhs = curve.add(&r, &curve.mul(&a, &k));
+    match mode {
+        Verification::Cofactorless => lhs == rhs,
+        Verification::Cofactored => domain.clear_cofactor(&lhs) == domain.clear_cofactor(&rhs),
+    }
+}
+
+/// The cofactored check, which RFC 8032 section 5.1.7 lists first
+pub fn verify(public: &[u8; 32], msg: &[u8], sig: &Signature) -> bool {
+    verify_with(public, msg, sig, Verification::Cofactored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::{bytes_to_hex, hex_to_bytes};
+
+    fn bytes32(hex: &str) -> [u8; 32] {
+        hex_to_bytes(hex).try_into().unwrap()
+    }
+
+    #[test]
+    fn test_rfc8032_vectors() {
+        // Section 7.1, tests 1 to 3
+        let vectors = [
+            (
+                "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+                "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+                "",
+                "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+            ),
+            (
+                "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+                "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+                "72",
+                "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+            ),
+            (
+                "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+                "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+                "af82",
+                "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a",
+            ),
+        ];
+        for (seed, public, msg, sig) in vectors {
+            let keys = KeyPair::from_seed(bytes32(seed));
+            assert_eq!(bytes_to_hex(&keys.public), public);
+            let msg = hex_to_bytes(msg);
+            let signature = sign(&keys, &msg);
+            assert_eq!(bytes_to_hex(&signature.to_bytes()), sig);
+            assert!(verify(&keys.public, &msg, &signature));
+            assert!(verify_with(&keys.public, &msg, &signature, Verification::Cofactorless));
+        }
+    }
+
+    #[test]
+    fn test_rejects_tampering() {
+        let keys = KeyPair::generate();
+        let sig = sign(&keys, b"pay bob 5");
+        assert_eq!(sig, sign(&keys, b"pay bob 5"));
+        assert!(verify(&keys.public, b"pay bob 5", &sig));
+        assert!(!verify(&keys.public, b"pay bob 6", &sig));
+        assert!(!verify(&KeyPair::generate().public, b"pay bob 5", &sig));
+
+        // S + L is the same scalar but is rejected outright
+        let domain = EdwardsDomain::ed25519();
+        let s = BigUint::from_bytes_le(&sig.s) + &domain.l;
+        let malleated = Signature { r: sig.r, s: scalar_to_bytes(&s) };
+        assert!(!verify(&keys.public, b"pay bob 5", &malleated));
+        assert_eq!(Signature::from_bytes(&sig.to_bytes()), sig);
+    }
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let keys = KeyPair::from_seed([7u8; 32]);
+        let printed = format!("{keys:?}");
+        assert!(printed.contains("<redacted>"));
+        assert!(!printed.contains(&format!("{:?}", keys.seed)));
+
+        let secret = expand_secret(&keys.seed);
+        let printed = format!("{secret:?}");
+        assert!(!printed.contains(&secret.scalar.to_string()));
+        assert!(!printed.contains(&format!("{:?}", secret.prefix)));
+    }
+
+    /// A point of order exactly 8, from L times some point on the curve
+    fn torsion_point(domain: &EdwardsDomain) -> EdwardsPoint {
+        (2u32..)
+            .filter_map(|y| domain.curve.point_from_y(&BigUint::from(y), false))
+            .map(|p| domain.curve.mul(&p, &domain.l))
+            .find(|t| !domain.curve.mul(t, &BigUint::from(4u32)).is_identity())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_cofactored_and_cofactorless_disagree() {
+        // A public key with a small-order component: A' = sB + T.
+        // [S]B = R + [k]A' then only holds when 8 | k, but [8] removes T either way.
+        let domain = EdwardsDomain::ed25519();
+        let t = torsion_point(&domain);
+        let keys = KeyPair::from_seed([7u8; 32]);
+        let secret = expand_secret(&keys.seed);
+        let tainted = domain.curve.add(&domain.curve.decode(&keys.public).unwrap(), &t);
+        let public = point_to_bytes(&domain, &tainted);
+
+        let mut disagreements = 0;
+        for msg in [&b"a"[..], b"b", b"c", b"d", b"e", b"f"] {
+            let r = hash_to_scalar(&domain, &[&secret.prefix, msg]);
+            let big_r = point_to_bytes(&domain, &domain.mul_base(&r));
+            let k = hash_to_scalar(&domain, &[&big_r, &public, msg]);
+            let s = (r + &k * &secret.scalar) % &domain.l;
+            let sig = Signature { r: big_r, s: scalar_to_bytes(&s) };
+
+            assert!(verify_with(&public, msg, &sig, Verification::Cofactored));
+            let kills_t = (&k % 8u32) == BigUint::ZERO;
+            assert_eq!(verify_with(&public, msg, &sig, Verification::Cofactorless), kills_t);
+            disagreements += usize::from(!kills_t);
+        }
+        assert!(disagreements > 0);
+    }
+}
@@ -0,0 +1 @@
+pub mod ed25519;
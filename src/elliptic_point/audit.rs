@@ -0,0 +1,236 @@
+//! Curve security audit
+//!
+//! One call that counts the points of a toy curve and runs the standard checks from
+//! SafeCurves / SEC 1 against it. Every check is done by brute force on top of
+//! `group_structure`, so this only works for small p, which is exactly the point:
+//! it shows why the teaching curves must never be used for anything real.
+
+use std::fmt;
+
+use num_traits::ToPrimitive;
+
+use super::curve::Curve;
+use super::group_structure::{count_points, factorize};
+
+/// Pollard rho needs about sqrt(q) steps, so a q-sized subgroup gives log2(q)/2 bits
+pub const MIN_SECURITY_BITS: u32 = 128;
+
+/// Embedding degrees at or below this make the MOV / Frey-Rück reduction practical
+pub const MOV_DEGREE_BOUND: u64 = 6;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    /// #E = p (trace 1), Smart's attack solves the ECDLP in linear time
+    Anomalous,
+    /// p^k = 1 mod q for a small k, pairings move the ECDLP into F_{p^k}
+    SmallEmbeddingDegree(u64),
+    /// The largest prime subgroup gives fewer than `MIN_SECURITY_BITS` of rho security
+    SmallSubgroup { security_bits: u32 },
+    /// The quadratic twist has a small largest prime factor (invalid-curve attacks on x-only code)
+    WeakTwist { security_bits: u32 },
+    /// #E is made only of small primes, Pohlig-Hellman splits the DLP into tiny pieces
+    SmoothOrder { largest_prime: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditReport {
+    pub p: u64,
+    /// #E(F_p)
+    pub order: u64,
+    /// Frobenius trace t = p + 1 - #E
+    pub trace: i64,
+    /// Largest prime q dividing #E
+    pub subgroup_order: u64,
+    pub cofactor: u64,
+    /// Curve discriminant -16(4a^3 + 27b^2) mod p
+    pub discriminant: u64,
+    /// CM discriminant t^2 - 4p
+    pub cm_discriminant: i64,
+    /// Smallest k with p^k = 1 mod q, None for anomalous curves where q = p
+    pub embedding_degree: Option<u64>,
+    /// #E' = p + 1 + t for the quadratic twist
+    pub twist_order: u64,
+    pub twist_subgroup_order: u64,
+    pub factorization: Vec<(u64, u32)>,
+    pub issues: Vec<Issue>,
+}
+
+impl AuditReport {
+    pub fn is_safe(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// floor(log2(q) / 2), the generic (rho) security level of a q-sized group
+fn rho_security_bits(q: u64) -> u32 {
+    q.ilog2() / 2
+}
+
+/// Order of p in (Z/qZ)*
+fn embedding_degree(p: u64, q: u64) -> Option<u64> {
+    if p.is_multiple_of(q) {
+        return None;
+    }
+    let base = (p % q) as u128;
+    let mut acc = base;
+    let mut k = 1;
+    while acc != 1 {
+        acc = acc * base % q as u128;
+        k += 1;
+    }
+    Some(k)
+}
+
+fn largest_prime_factor(n: u64) -> u64 {
+    factorize(n).last().map(|(q, _)| *q).unwrap_or(1)
+}
+
+/// Count points, run every check and collect the problems found
+pub fn audit(curve: &Curve) -> Result<AuditReport, &'static str> {
+    if curve.is_singular() {
+        return Err("singular curve: discriminant is 0, the points do not form a group");
+    }
+    let p = curve.p.to_u64().ok_or("audit only works for toy curves (p must fit in a u64)")?;
+    let order = count_points(curve);
+    let trace = p as i64 + 1 - order as i64;
+    let factorization = factorize(order);
+    let subgroup_order = largest_prime_factor(order);
+    let cofactor = order / subgroup_order;
+    let twist_order = 2 * p + 2 - order;
+    let twist_subgroup_order = largest_prime_factor(twist_order);
+    let embedding_degree = embedding_degree(p, subgroup_order);
+
+    let mut issues = Vec::new();
+    if order == p {
+        issues.push(Issue::Anomalous);
+    }
+    if let Some(k) = embedding_degree
+        && k <= MOV_DEGREE_BOUND
+    {
+        issues.push(Issue::SmallEmbeddingDegree(k));
+    }
+    let security_bits = rho_security_bits(subgroup_order);
+    if security_bits < MIN_SECURITY_BITS {
+        issues.push(Issue::SmallSubgroup { security_bits });
+    }
+    let twist_bits = rho_security_bits(twist_subgroup_order);
+    if twist_bits < MIN_SECURITY_BITS {
+        issues.push(Issue::WeakTwist {
+            security_bits: twist_bits,
+        });
+    }
+    // Smooth: most of the group order sits outside the largest prime factor
+    if subgroup_order * subgroup_order < order {
+        issues.push(Issue::SmoothOrder {
+            largest_prime: subgroup_order,
+        });
+    }
+
+    Ok(AuditReport {
+        p,
+        order,
+        trace,
+        subgroup_order,
+        cofactor,
+        discriminant: curve.discriminant().to_u64().unwrap(),
+        cm_discriminant: trace * trace - 4 * p as i64,
+        embedding_degree,
+        twist_order,
+        twist_subgroup_order,
+        factorization,
+        issues,
+    })
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::Anomalous => write!(f, "anomalous curve (#E = p): Smart's attack applies"),
+            Issue::SmallEmbeddingDegree(k) => {
+                write!(f, "embedding degree {}: MOV/FR reduces the ECDLP to F_p^{}", k, k)
+            }
+            Issue::SmallSubgroup { security_bits } => {
+                write!(f, "prime subgroup only gives {} bits of rho security", security_bits)
+            }
+            Issue::WeakTwist { security_bits } => {
+                write!(f, "twist only gives {} bits of rho security", security_bits)
+            }
+            Issue::SmoothOrder { largest_prime } => {
+                write!(f, "smooth group order (largest prime {}): Pohlig-Hellman applies", largest_prime)
+            }
+        }
+    }
+}
+
+impl fmt::Display for AuditReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "p = {}, #E = {} (trace {})", self.p, self.order, self.trace)?;
+        writeln!(f, "factorization: {:?}", self.factorization)?;
+        writeln!(f, "prime subgroup q = {}, cofactor h = {}", self.subgroup_order, self.cofactor)?;
+        writeln!(f, "discriminant = {}, CM discriminant = {}", self.discriminant, self.cm_discriminant)?;
+        writeln!(f, "embedding degree = {:?}", self.embedding_degree)?;
+        writeln!(f, "twist #E' = {} (largest prime {})", self.twist_order, self.twist_subgroup_order)?;
+        if self.is_safe() {
+            return writeln!(f, "SAFE");
+        }
+        for issue in &self.issues {
+            writeln!(f, "UNSAFE: {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_f17() {
+        let report = audit(&Curve::f17()).unwrap();
+        let text = report.to_string();
+        assert!(text.starts_with("p = 17, #E = 18 (trace 0)\n"));
+        assert!(text.contains("UNSAFE: embedding degree 2: MOV/FR reduces the ECDLP to F_p^2\n"));
+        assert!(!text.lines().any(|line| line == "SAFE"));
+        // Supersingular: #E = p + 1, trace 0 and embedding degree 2
+        assert_eq!(report.order, 18);
+        assert_eq!(report.trace, 0);
+        assert_eq!(report.subgroup_order, 3);
+        assert_eq!(report.cofactor, 6);
+        assert_eq!(report.embedding_degree, Some(2));
+        assert!(report.issues.contains(&Issue::SmallEmbeddingDegree(2)));
+        assert!(report.issues.contains(&Issue::SmoothOrder { largest_prime: 3 }));
+        assert!(!report.is_safe());
+    }
+
+    #[test]
+    fn test_audit_f211() {
+        let curve = Curve::f211();
+        let report = audit(&curve).unwrap();
+        assert_eq!(report.order + report.twist_order, 2 * 211 + 2);
+        // Hasse: |t| <= 2√p
+        assert!((report.trace * report.trace) as u64 <= 4 * 211);
+        let text = report.to_string();
+        assert!(text.contains("UNSAFE: prime subgroup only gives"));
+        assert_eq!(text.lines().filter(|l| l.starts_with("UNSAFE: ")).count(), report.issues.len());
+        assert!(report
+            .issues
+            .iter()
+            .any(|i| matches!(i, Issue::SmallSubgroup { .. })));
+        assert!(!report.is_safe());
+    }
+
+    #[test]
+    fn test_audit_anomalous() {
+        // y^2 = x^3 + x + 6 over F_13 has exactly 13 points
+        let curve = Curve::small(13, 1, 6);
+        assert_eq!(count_points(&curve), 13);
+        let report = audit(&curve).unwrap();
+        assert!(report.issues.contains(&Issue::Anomalous));
+        assert_eq!(report.embedding_degree, None);
+    }
+
+    #[test]
+    fn test_audit_singular() {
+        assert!(audit(&Curve::small(17, 0, 0)).is_err());
+    }
+}
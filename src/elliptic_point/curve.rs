@@ -0,0 +1,470 @@
+//! Short Weierstrass curves with runtime parameters
+//!
+//! `ec_point` and `ec_projective` hardcode P, A and B as constants, which is fine for
+//! drills but means every new curve needs a new copy of the arithmetic.
+//! `Curve` carries y^2 = x^3 + ax + b over F_p as data so the same code runs on
+//! the teaching curves and on anything else we want to analyse.
+
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+
+use rand::{RngCore, rng};
+
+use super::{ec_point, ec_projective, field, group_structure};
+
+/// y^2 = x^3 + ax + b over F_p
+/// Coefficients are always stored reduced into [0, p-1]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Curve {
+    pub p: BigUint,
+    pub a: BigUint,
+    pub b: BigUint,
+}
+
+/// Jacobian coordinates, only used inside `Curve::mul`
+struct Jacobian {
+    x: BigUint,
+    y: BigUint,
+    z: BigUint,
+}
+
+/// Affine point on a `Curve`
+/// The point does not know which curve it belongs to, the `Curve` methods do the checking
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Point {
+    Infinity,
+    Affine { x: BigUint, y: BigUint },
+}
+
+impl Point {
+    /// Small-coordinate helper for the toy curves
+    pub fn new(x: u64, y: u64) -> Self {
+        Point::Affine {
+            x: BigUint::from(x),
+            y: BigUint::from(y),
+        }
+    }
+
+    pub fn is_infinity(&self) -> bool {
+        matches!(self, Point::Infinity)
+    }
+}
+
+/// Parse a hex constant, only used for the hardcoded curve parameters
+pub fn hex_biguint(hex: &str) -> BigUint {
+    BigUint::parse_bytes(hex.as_bytes(), 16).expect("invalid hex constant")
+}
+
+impl Curve {
+    pub fn new(p: BigUint, a: BigUint, b: BigUint) -> Self {
+        let a = a % &p;
+        let b = b % &p;
+        Self { p, a, b }
+    }
+
+    /// Build a toy curve from machine integers, negative coefficients are reduced mod p
+    pub fn small(p: u64, a: i64, b: i64) -> Self {
+        let reduce = |v: i64| BigUint::from(v.rem_euclid(p as i64) as u64);
+        Self::new(BigUint::from(p), reduce(a), reduce(b))
+    }
+
+    /// The F17 teaching curve from `ec_point`: y^2 = x^3 + 1
+    pub fn f17() -> Self {
+        Self::small(ec_point::P as u64, ec_point::A, ec_point::B)
+    }
+
+    /// The F211 teaching curve from `ec_projective`: y^2 = x^3 + 4
+    pub fn f211() -> Self {
+        Self::small(ec_projective::P as u64, ec_projective::A as i64, ec_projective::B as i64)
+    }
+
+    /// NIST P-256: y^2 = x^3 - 3x + b
+    pub fn p256() -> Self {
+        let p = hex_biguint("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff");
+        let a = &p - 3u32;
+        let b = hex_biguint("5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b");
+        Self::new(p, a, b)
+    }
+
+    /// BN254 G1 (the `ark_bn254` curve): y^2 = x^3 + 3
+    pub fn bn254_g1() -> Self {
+        let p = hex_biguint("30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47");
+        Self::new(p, BigUint::zero(), BigUint::from(3u32))
+    }
+
+    // --- Field helpers (all inputs assumed reduced), see `field` ---
+
+    pub fn fadd(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        field::mod_add(a, b, &self.p)
+    }
+
+    pub fn fsub(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        field::mod_sub(a, b, &self.p)
+    }
+
+    pub fn fmul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        field::mod_mul(a, b, &self.p)
+    }
+
+    pub fn fneg(&self, a: &BigUint) -> BigUint {
+        field::mod_neg(a, &self.p)
+    }
+
+    /// Multiplicative inverse, panics on 0 like the `FieldElement::inverse` drills
+    pub fn finv(&self, a: &BigUint) -> BigUint {
+        field::mod_inv(a, &self.p)
+    }
+
+    /// Right hand side of the curve equation: x^3 + ax + b
+    pub fn rhs(&self, x: &BigUint) -> BigUint {
+        let x3 = self.fmul(&self.fmul(x, x), x);
+        self.fadd(&self.fadd(&x3, &self.fmul(&self.a, x)), &self.b)
+    }
+
+    /// Curve equation check. Infinity is always on the curve.
+    pub fn contains(&self, point: &Point) -> bool {
+        match point {
+            Point::Infinity => true,
+            Point::Affine { x, y } => {
+                x < &self.p && y < &self.p && self.fmul(y, y) == self.rhs(x)
+            }
+        }
+    }
+
+    /// Discriminant -16(4a^3 + 27b^2) mod p, the curve is singular when it is 0
+    pub fn discriminant(&self) -> BigUint {
+        let a3 = self.fmul(&self.fmul(&self.a, &self.a), &self.a);
+        let b2 = self.fmul(&self.b, &self.b);
+        let inner = self.fadd(
+            &self.fmul(&BigUint::from(4u32), &a3),
+            &self.fmul(&BigUint::from(27u32), &b2),
+        );
+        self.fneg(&self.fmul(&BigUint::from(16u32), &inner))
+    }
+
+    pub fn is_singular(&self) -> bool {
+        self.discriminant().is_zero()
+    }
+
+    pub fn neg(&self, point: &Point) -> Point {
+        match point {
+            Point::Infinity => Point::Infinity,
+            Point::Affine { x, y } => Point::Affine {
+                x: x.clone(),
+                y: self.fneg(y),
+            },
+        }
+    }
+
+    /// Chord-and-tangent addition, same case split as `ECPoint::add`
+    pub fn add(&self, p1: &Point, p2: &Point) -> Point {
+        match (p1, p2) {
+            (Point::Infinity, _) => p2.clone(),
+            (_, Point::Infinity) => p1.clone(),
+            (Point::Affine { x: x1, y: y1 }, Point::Affine { x: x2, y: y2 }) => {
+                let s = if x1 == x2 {
+                    // P + (-P) or a vertical tangent
+                    if y1 != y2 || y1.is_zero() {
+                        return Point::Infinity;
+                    }
+                    // s = (3x1^2 + a) / 2y1
+                    let num = self.fadd(&self.fmul(&BigUint::from(3u32), &self.fmul(x1, x1)), &self.a);
+                    self.fmul(&num, &self.finv(&self.fmul(&BigUint::from(2u32), y1)))
+                } else {
+                    // s = (y2 - y1) / (x2 - x1)
+                    self.fmul(&self.fsub(y2, y1), &self.finv(&self.fsub(x2, x1)))
+                };
+                let x3 = self.fsub(&self.fsub(&self.fmul(&s, &s), x1), x2);
+                let y3 = self.fsub(&self.fmul(&s, &self.fsub(x1, &x3)), y1);
+                Point::Affine { x: x3, y: y3 }
+            }
+        }
+    }
+
+    pub fn double(&self, point: &Point) -> Point {
+        self.add(point, point)
+    }
+
+    /// Scalar multiplication n * P with Double-and-Add
+    ///
+    /// Runs in Jacobian coordinates (X/Z^2, Y/Z^3) so there is a single inversion at the
+    /// end instead of one per step, which matters once p is 256 bits.
+    pub fn mul(&self, point: &Point, scalar: &BigUint) -> Point {
+        let (px, py) = match point {
+            Point::Infinity => return Point::Infinity,
+            Point::Affine { x, y } => (x, y),
+        };
+        let mut acc: Option<Jacobian> = None;
+        for i in (0..scalar.bits()).rev() {
+            acc = acc.and_then(|j| self.jacobian_double(&j));
+            if scalar.bit(i) {
+                acc = match acc {
+                    None => Some(Jacobian {
+                        x: px.clone(),
+                        y: py.clone(),
+                        z: BigUint::from(1u32),
+                    }),
+                    Some(j) => self.jacobian_add_affine(&j, px, py),
+                };
+            }
+        }
+        match acc {
+            None => Point::Infinity,
+            Some(j) => {
+                let z_inv = self.finv(&j.z);
+                let z_inv2 = self.fmul(&z_inv, &z_inv);
+                Point::Affine {
+                    x: self.fmul(&j.x, &z_inv2),
+                    y: self.fmul(&j.y, &self.fmul(&z_inv2, &z_inv)),
+                }
+            }
+        }
+    }
+
+    /// dbl-2007-bl style doubling for any a, None is the point at infinity
+    fn jacobian_double(&self, p: &Jacobian) -> Option<Jacobian> {
+        if p.y.is_zero() {
+            return None;
+        }
+        let xx = self.fmul(&p.x, &p.x);
+        let yy = self.fmul(&p.y, &p.y);
+        let zz = self.fmul(&p.z, &p.z);
+        // S = 4 X Y^2, M = 3 X^2 + a Z^4
+        let s = self.fmul(&BigUint::from(4u32), &self.fmul(&p.x, &yy));
+        let m = self.fadd(
+            &self.fmul(&BigUint::from(3u32), &xx),
+            &self.fmul(&self.a, &self.fmul(&zz, &zz)),
+        );
+        let x3 = self.fsub(&self.fmul(&m, &m), &self.fadd(&s, &s));
+        let y3 = self.fsub(
+            &self.fmul(&m, &self.fsub(&s, &x3)),
+            &self.fmul(&BigUint::from(8u32), &self.fmul(&yy, &yy)),
+        );
+        let z3 = self.fmul(&BigUint::from(2u32), &self.fmul(&p.y, &p.z));
+        Some(Jacobian { x: x3, y: y3, z: z3 })
+    }
+
+    /// Mixed addition Jacobian + affine
+    fn jacobian_add_affine(&self, p: &Jacobian, x2: &BigUint, y2: &BigUint) -> Option<Jacobian> {
+        let z1z1 = self.fmul(&p.z, &p.z);
+        let u2 = self.fmul(x2, &z1z1);
+        let s2 = self.fmul(y2, &self.fmul(&z1z1, &p.z));
+        let h = self.fsub(&u2, &p.x);
+        let r = self.fsub(&s2, &p.y);
+        if h.is_zero() {
+            return if r.is_zero() { self.jacobian_double(p) } else { None };
+        }
+        let hh = self.fmul(&h, &h);
+        let hhh = self.fmul(&hh, &h);
+        let v = self.fmul(&p.x, &hh);
+        let x3 = self.fsub(&self.fsub(&self.fmul(&r, &r), &hhh), &self.fadd(&v, &v));
+        let y3 = self.fsub(&self.fmul(&r, &self.fsub(&v, &x3)), &self.fmul(&p.y, &hhh));
+        let z3 = self.fmul(&p.z, &h);
+        Some(Jacobian { x: x3, y: y3, z: z3 })
+    }
+
+    pub fn mul_u64(&self, point: &Point, scalar: u64) -> Point {
+        self.mul(point, &BigUint::from(scalar))
+    }
+}
+
+impl From<ec_point::ECPoint> for Point {
+    fn from(p: ec_point::ECPoint) -> Self {
+        match p {
+            ec_point::ECPoint::Infinity => Point::Infinity,
+            ec_point::ECPoint::Point { x, y } => Point::new(x.value as u64, y.value as u64),
+        }
+    }
+}
+
+impl From<ec_projective::AffinePoint> for Point {
+    fn from(p: ec_projective::AffinePoint) -> Self {
+        match p {
+            ec_projective::AffinePoint::Infinity => Point::Infinity,
+            ec_projective::AffinePoint::Point { x, y } => {
+                Point::new(x.value as u64, y.value as u64)
+            }
+        }
+    }
+}
+
+impl From<ec_projective::ProjectivePoint> for Point {
+    fn from(p: ec_projective::ProjectivePoint) -> Self {
+        ec_projective::AffinePoint::from(p).into()
+    }
+}
+
+/// Curve plus a base point G of prime order n and the cofactor h = #E / n
+/// (the "elliptic curve domain parameters" of SEC 1), what signature schemes work over
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DomainParams {
+    pub curve: Curve,
+    pub g: Point,
+    pub n: BigUint,
+    pub h: BigUint,
+}
+
+impl DomainParams {
+    /// secp256k1 (Bitcoin, Ethereum): y^2 = x^3 + 7
+    pub fn secp256k1() -> Self {
+        let p = hex_biguint("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f");
+        Self {
+            curve: Curve::new(p, BigUint::zero(), BigUint::from(7u32)),
+            g: Point::Affine {
+                x: hex_biguint("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+                y: hex_biguint("483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"),
+            },
+            n: hex_biguint("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"),
+            h: BigUint::from(1u32),
+        }
+    }
+
+    /// NIST P-256 / secp256r1
+    pub fn p256() -> Self {
+        Self {
+            curve: Curve::p256(),
+            g: Point::Affine {
+                x: hex_biguint("6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296"),
+                y: hex_biguint("4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"),
+            },
+            n: hex_biguint("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551"),
+            h: BigUint::from(1u32),
+        }
+    }
+
+    /// BN254 G1: generator (1, 2), prime order r, cofactor 1
+    pub fn bn254_g1() -> Self {
+        Self {
+            curve: Curve::bn254_g1(),
+            g: Point::new(1, 2),
+            n: hex_biguint("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001"),
+            h: BigUint::from(1u32),
+        }
+    }
+
+    /// Domain parameters for any toy curve: the largest prime order subgroup and its
+    /// first generator, found by brute force
+    pub fn toy(curve: Curve) -> Self {
+        let sub = group_structure::prime_order_subgroup(&curve).expect("trivial group");
+        Self {
+            g: sub.generators[0].clone(),
+            n: BigUint::from(sub.order),
+            h: BigUint::from(sub.cofactor),
+            curve,
+        }
+    }
+
+    /// The F211 teaching curve restricted to its order 61 subgroup
+    pub fn f211() -> Self {
+        Self::toy(Curve::f211())
+    }
+
+    /// Uniform scalar in [1, n-1]
+    /// Samples 128 extra bits before reducing so the modulo bias is negligible
+    pub fn random_scalar(&self) -> BigUint {
+        let mut bytes = vec![0u8; (self.n.bits() as usize).div_ceil(8) + 16];
+        loop {
+            rng().fill_bytes(&mut bytes);
+            let k = BigUint::from_bytes_be(&bytes) % &self.n;
+            if !k.is_zero() {
+                return k;
+            }
+        }
+    }
+
+    /// k * G
+    pub fn mul_g(&self, k: &BigUint) -> Point {
+        self.curve.mul(&self.g, k)
+    }
+}
+
+/// Back to the F17 drill type, coordinates are reduced mod 17
+impl From<Point> for ec_point::ECPoint {
+    fn from(p: Point) -> Self {
+        match p {
+            Point::Infinity => ec_point::ECPoint::Infinity,
+            Point::Affine { x, y } => {
+                let modulus = BigUint::from(ec_point::P as u64);
+                let x = (x % &modulus).to_i64().unwrap();
+                let y = (y % &modulus).to_i64().unwrap();
+                ec_point::ECPoint::new(x, y)
+            }
+        }
+    }
+}
+
+/// Back to the F211 drill type, coordinates are reduced mod 211
+impl From<Point> for ec_projective::AffinePoint {
+    fn from(p: Point) -> Self {
+        match p {
+            Point::Infinity => ec_projective::AffinePoint::Infinity,
+            Point::Affine { x, y } => {
+                let modulus = BigUint::from(ec_projective::P as u64);
+                let x = (x % &modulus).to_i128().unwrap();
+                let y = (y % &modulus).to_i128().unwrap();
+                ec_projective::AffinePoint::Point {
+                    x: ec_projective::FieldElement::new(x),
+                    y: ec_projective::FieldElement::new(y),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_ec_point() {
+        // Same vectors as ec_point::tests
+        let curve = Curve::f17();
+        let p1 = Point::new(0, 1);
+        let p2 = Point::new(1, 6);
+        assert!(curve.contains(&p1));
+        assert!(curve.contains(&p2));
+        assert_eq!(curve.add(&p1, &p2), Point::new(7, 15));
+        assert_eq!(curve.double(&p2), Point::new(14, 12));
+        assert_eq!(curve.add(&p1, &Point::new(0, 16)), Point::Infinity);
+
+        let drill: Point = ec_point::ECPoint::new(1, 6).add(ec_point::ECPoint::new(0, 1)).into();
+        assert_eq!(drill, curve.add(&p2, &p1));
+    }
+
+    #[test]
+    fn test_scalar_mul_matches_projective() {
+        let curve = Curve::f211();
+        let g = Point::new(0, 2);
+        assert!(curve.contains(&g));
+        let proj: ec_projective::ProjectivePoint = ec_projective::AffinePoint::Point {
+            x: ec_projective::FieldElement::new(0),
+            y: ec_projective::FieldElement::new(2),
+        }
+        .into();
+        for k in 0..10u64 {
+            assert_eq!(curve.mul_u64(&g, k), proj.scalar_mul(k).into());
+        }
+    }
+
+    #[test]
+    fn test_domain_params() {
+        for domain in [DomainParams::secp256k1(), DomainParams::p256(), DomainParams::f211()] {
+            assert!(domain.curve.contains(&domain.g));
+            assert!(!domain.g.is_infinity());
+            assert!(domain.mul_g(&domain.n).is_infinity());
+            let k = domain.random_scalar();
+            assert!(!k.is_zero() && k < domain.n);
+        }
+        assert_eq!(DomainParams::f211().n, BigUint::from(61u32));
+        // BN254 G1 generator is (1, 2)
+        assert!(Curve::bn254_g1().contains(&Point::new(1, 2)));
+    }
+
+    #[test]
+    fn test_singular_curve() {
+        // y^2 = x^3 has a cusp at the origin
+        assert!(Curve::small(17, 0, 0).is_singular());
+        assert!(!Curve::f17().is_singular());
+        assert!(!Curve::small(17, -3, 5).is_singular());
+    }
+}
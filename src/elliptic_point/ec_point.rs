@@ -2,9 +2,9 @@ use std::ops::{Add, Div, Mul, Sub};
 
 /// Define the field modulus P = 17 for this drill
 /// Curve: y^2 = x^3 + 1
-const P: i64 = 17;
-const A: i64 = 0; // Coefficient a in y^2 = x^3 + ax + b
-const B: i64 = 1; // Coefficient b
+pub const P: i64 = 17;
+pub const A: i64 = 0; // Coefficient a in y^2 = x^3 + ax + b
+pub const B: i64 = 1; // Coefficient b
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct FieldElement {
@@ -0,0 +1,327 @@
+//! Twisted Edwards curves: a x^2 + y^2 = 1 + d x^2 y^2
+//!
+//! The neutral element is the affine point (0, 1), so there is no point at infinity
+//! to special-case, and one formula covers P + Q, P + P and P + O:
+//!
+//!   x3 = (x1 y2 + y1 x2) / (1 + d x1 x2 y1 y2)
+//!   y3 = (y1 y2 - a x1 x2) / (1 - d x1 x2 y1 y2)
+//!
+//! When a is a square and d is not, the denominators never vanish (the addition law
+//! is complete), which is why Ed25519 implementations need no branches at all.
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use super::curve::Point;
+use super::field::PrimeField;
+use super::montgomery::MontgomeryCurve;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdwardsCurve {
+    pub field: PrimeField,
+    pub a: BigUint,
+    pub d: BigUint,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EdwardsPoint {
+    pub x: BigUint,
+    pub y: BigUint,
+}
+
+/// Base point, prime subgroup order and cofactor, the Edwards `DomainParams`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdwardsDomain {
+    pub curve: EdwardsCurve,
+    pub base: EdwardsPoint,
+    pub l: BigUint,
+    pub cofactor: u32,
+}
+
+impl EdwardsPoint {
+    /// (0, 1)
+    pub fn identity() -> Self {
+        Self { x: BigUint::zero(), y: BigUint::from(1u32) }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.x.is_zero() && self.y == BigUint::from(1u32)
+    }
+}
+
+impl EdwardsCurve {
+    pub fn new(p: BigUint, a: BigUint, d: BigUint) -> Self {
+        let a = a % &p;
+        let d = d % &p;
+        Self { field: PrimeField::new(p), a, d }
+    }
+
+    pub fn small(p: u64, a: i64, d: i64) -> Self {
+        let field = PrimeField::new(BigUint::from(p));
+        Self::new(field.p.clone(), field.reduce_i64(a), field.reduce_i64(d))
+    }
+
+    /// edwards25519: -x^2 + y^2 = 1 - (121665/121666) x^2 y^2 over 2^255 - 19
+    pub fn edwards25519() -> Self {
+        let field = PrimeField::new((BigUint::from(1u32) << 255) - 19u32);
+        let d = field.neg(&field.div(&BigUint::from(121665u32), &BigUint::from(121666u32)));
+        Self::new(field.p.clone(), field.reduce_i64(-1), d)
+    }
+
+    /// a d (a - d) != 0
+    pub fn is_singular(&self) -> bool {
+        self.a.is_zero() || self.d.is_zero() || self.a == self.d
+    }
+
+    /// a square, d non-square: the addition law has no exceptional cases
+    pub fn is_complete(&self) -> bool {
+        self.field.is_square(&self.a) && !self.field.is_square(&self.d)
+    }
+
+    pub fn contains(&self, point: &EdwardsPoint) -> bool {
+        let f = &self.field;
+        let (x2, y2) = (f.square(&point.x), f.square(&point.y));
+        let lhs = f.add(&f.mul(&self.a, &x2), &y2);
+        let rhs = f.add(&BigUint::from(1u32), &f.mul(&self.d, &f.mul(&x2, &y2)));
+        point.x < f.p && point.y < f.p && lhs == rhs
+    }
+
+    /// x from y: x^2 = (y^2 - 1) / (d y^2 - a), picking the root by parity
+    pub fn point_from_y(&self, y: &BigUint, x_is_odd: bool) -> Option<EdwardsPoint> {
+        let f = &self.field;
+        if y >= &f.p {
+            return None;
+        }
+        let y2 = f.square(y);
+        let den = f.sub(&f.mul(&self.d, &y2), &self.a);
+        if den.is_zero() {
+            return None;
+        }
+        let x2 = f.div(&f.sub(&y2, &BigUint::from(1u32)), &den);
+        let x = f.sqrt_with_parity(&x2, x_is_odd)?;
+        Some(EdwardsPoint { x, y: y.clone() })
+    }
+
+    pub fn neg(&self, point: &EdwardsPoint) -> EdwardsPoint {
+        EdwardsPoint { x: self.field.neg(&point.x), y: point.y.clone() }
+    }
+
+    /// The unified addition law. On an incomplete curve an exceptional pair makes a
+    /// denominator 0 and the inversion panics.
+    pub fn add(&self, p1: &EdwardsPoint, p2: &EdwardsPoint) -> EdwardsPoint {
+        let f = &self.field;
+        let one = BigUint::from(1u32);
+        let x1x2 = f.mul(&p1.x, &p2.x);
+        let y1y2 = f.mul(&p1.y, &p2.y);
+        let dxy = f.mul(&self.d, &f.mul(&x1x2, &y1y2));
+        let x3 = f.div(
+            &f.add(&f.mul(&p1.x, &p2.y), &f.mul(&p1.y, &p2.x)),
+            &f.add(&one, &dxy),
+        );
+        let y3 = f.div(&f.sub(&y1y2, &f.mul(&self.a, &x1x2)), &f.sub(&one, &dxy));
+        EdwardsPoint { x: x3, y: y3 }
+    }
+
+    pub fn double(&self, point: &EdwardsPoint) -> EdwardsPoint {
+        self.add(point, point)
+    }
+
+    pub fn mul(&self, point: &EdwardsPoint, scalar: &BigUint) -> EdwardsPoint {
+        let mut acc = EdwardsPoint::identity();
+        for i in (0..scalar.bits()).rev() {
+            acc = self.double(&acc);
+            if scalar.bit(i) {
+                acc = self.add(&acc, point);
+            }
+        }
+        acc
+    }
+
+    /// Little-endian y with the parity of x in the top bit (RFC 8032 section 5.1.2)
+    /// p is one bit short of a whole number of bytes for edwards25519, leaving room for it.
+    pub fn encode(&self, point: &EdwardsPoint) -> Vec<u8> {
+        let len = self.encoded_len();
+        let mut out = point.y.to_bytes_le();
+        out.resize(len, 0);
+        if point.x.bit(0) {
+            out[len - 1] |= 0x80;
+        }
+        out
+    }
+
+    /// Inverse of `encode`, rejecting y >= p and the non-existent "-0" for x
+    pub fn decode(&self, bytes: &[u8]) -> Result<EdwardsPoint, &'static str> {
+        let len = self.encoded_len();
+        if bytes.len() != len {
+            return Err("wrong encoded point length");
+        }
+        let mut y = bytes.to_vec();
+        let x_is_odd = y[len - 1] & 0x80 != 0;
+        y[len - 1] &= 0x7f;
+        let y = BigUint::from_bytes_le(&y);
+        if y >= self.field.p {
+            return Err("y is not a canonical field element");
+        }
+        self.point_from_y(&y, x_is_odd).ok_or("no point with this y and sign")
+    }
+
+    fn encoded_len(&self) -> usize {
+        (self.field.p.bits() as usize + 1).div_ceil(8)
+    }
+
+    /// A = 2(a + d) / (a - d),   B = 4 / (a - d)
+    pub fn to_montgomery(&self) -> MontgomeryCurve {
+        let f = &self.field;
+        let a_minus_d = f.sub(&self.a, &self.d);
+        MontgomeryCurve::new(
+            f.p.clone(),
+            f.div(&f.mul(&BigUint::from(2u32), &f.add(&self.a, &self.d)), &a_minus_d),
+            f.div(&BigUint::from(4u32), &a_minus_d),
+        )
+    }
+
+    /// (x, y) -> ((1 + y)/(1 - y), (1 + y)/((1 - y) x))
+    /// (0, 1) and (0, -1) go to infinity and (0, 0) on the Montgomery side.
+    pub fn point_to_montgomery(&self, point: &EdwardsPoint) -> Point {
+        let f = &self.field;
+        if point.is_identity() {
+            return Point::Infinity;
+        }
+        if point.x.is_zero() {
+            return Point::Affine { x: BigUint::zero(), y: BigUint::zero() };
+        }
+        let one = BigUint::from(1u32);
+        let u = f.div(&f.add(&one, &point.y), &f.sub(&one, &point.y));
+        let v = f.div(&u, &point.x);
+        Point::Affine { x: u, y: v }
+    }
+}
+
+impl EdwardsDomain {
+    /// RFC 8032: B = (x, 4/5) with x even, L = 2^252 + 27742317777372353535851937790883648493
+    pub fn ed25519() -> Self {
+        let curve = EdwardsCurve::edwards25519();
+        let y = curve.field.div(&BigUint::from(4u32), &BigUint::from(5u32));
+        let base = curve.point_from_y(&y, false).unwrap();
+        let l = (BigUint::from(1u32) << 252)
+            + BigUint::parse_bytes(b"27742317777372353535851937790883648493", 10).unwrap();
+        Self { curve, base, l, cofactor: 8 }
+    }
+
+    pub fn mul_base(&self, k: &BigUint) -> EdwardsPoint {
+        self.curve.mul(&self.base, k)
+    }
+
+    /// Multiply by the cofactor, killing any small-order component
+    pub fn clear_cofactor(&self, point: &EdwardsPoint) -> EdwardsPoint {
+        self.curve.mul(point, &BigUint::from(self.cofactor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elliptic_point::curve::hex_biguint;
+
+    /// v^2 = u^3 + 4u^2 + u over F101 and its Edwards form 6x^2 + y^2 = 1 + 2x^2y^2
+    fn toy() -> (MontgomeryCurve, EdwardsCurve) {
+        let montgomery = MontgomeryCurve::small(101, 4, 1);
+        let edwards = montgomery.to_edwards();
+        (montgomery, edwards)
+    }
+
+    fn toy_points(edwards: &EdwardsCurve) -> Vec<EdwardsPoint> {
+        (0..101u64)
+            .flat_map(|y| [false, true].map(|odd| edwards.point_from_y(&BigUint::from(y), odd)))
+            .flatten()
+            .collect::<std::collections::HashSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_toy_curve_is_complete() {
+        let (montgomery, edwards) = toy();
+        assert_eq!(edwards, EdwardsCurve::small(101, 6, 2));
+        assert!(edwards.is_complete());
+        assert!(!edwards.is_singular());
+        assert_eq!(edwards.to_montgomery(), montgomery);
+        // Same group: the 96 Montgomery points, with infinity now the affine (0, 1)
+        let points = toy_points(&edwards);
+        assert_eq!(points.len(), 96);
+        assert!(points.iter().all(|p| edwards.contains(p)));
+    }
+
+    #[test]
+    fn test_unified_addition() {
+        let (_, edwards) = toy();
+        let points = toy_points(&edwards);
+        let identity = EdwardsPoint::identity();
+        for p in &points {
+            assert_eq!(edwards.add(p, &identity), p.clone());
+            assert!(edwards.add(p, &edwards.neg(p)).is_identity());
+            assert!(edwards.contains(&edwards.double(p)));
+            assert!(edwards.mul(p, &BigUint::from(96u32)).is_identity());
+        }
+    }
+
+    #[test]
+    fn test_montgomery_map_is_a_homomorphism() {
+        let (montgomery, edwards) = toy();
+        let points = toy_points(&edwards);
+        for p1 in &points {
+            let m1 = edwards.point_to_montgomery(p1);
+            assert!(montgomery.contains(&m1));
+            assert_eq!(montgomery.point_to_edwards(&m1).as_ref(), Some(p1));
+            for p2 in points.iter().step_by(7) {
+                let m2 = edwards.point_to_montgomery(p2);
+                assert_eq!(edwards.point_to_montgomery(&edwards.add(p1, p2)), montgomery.add(&m1, &m2));
+            }
+        }
+    }
+
+    #[test]
+    fn test_encoding() {
+        let (_, edwards) = toy();
+        for point in toy_points(&edwards) {
+            let bytes = edwards.encode(&point);
+            assert_eq!(bytes.len(), 1);
+            assert_eq!(edwards.decode(&bytes), Ok(point));
+        }
+        // y = 101 is not reduced, and (0, 1) has no odd twin
+        assert!(edwards.decode(&[101]).is_err());
+        assert!(edwards.decode(&[0x81]).is_err());
+
+        let domain = EdwardsDomain::ed25519();
+        let bytes = domain.curve.encode(&domain.base);
+        assert_eq!(bytes, [&[0x58], &[0x66; 31][..]].concat());
+        assert_eq!(domain.curve.decode(&bytes), Ok(domain.base.clone()));
+        assert!(domain.mul_base(&domain.l).is_identity());
+    }
+
+    #[test]
+    fn test_edwards25519_base_point() {
+        // B = (x, 4/5) with x even (RFC 8032 section 5.1)
+        let curve = EdwardsCurve::edwards25519();
+        let f = &curve.field;
+        let y = f.div(&BigUint::from(4u32), &BigUint::from(5u32));
+        let base = curve.point_from_y(&y, false).unwrap();
+        assert_eq!(base.x, hex_biguint("216936d3cd6e53fec0a4e231fdd6dc5c692cc7609525a7b2c9562d608f25d51a"));
+        assert!(curve.is_complete());
+
+        // It is the Curve25519 base point u = 9, and the ladder agrees with Edwards mul
+        let montgomery = curve.to_montgomery();
+        assert_eq!(montgomery.a, BigUint::from(486662u32));
+        let u = match curve.point_to_montgomery(&base) {
+            Point::Affine { x, .. } => x,
+            Point::Infinity => unreachable!(),
+        };
+        assert_eq!(u, BigUint::from(9u32));
+        let k = BigUint::from(0xdead_beef_cafe_u64);
+        let expected = match curve.point_to_montgomery(&curve.mul(&base, &k)) {
+            Point::Affine { x, .. } => x,
+            Point::Infinity => unreachable!(),
+        };
+        assert_eq!(MontgomeryCurve::curve25519().ladder(&u, &k), expected);
+    }
+}
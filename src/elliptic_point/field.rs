@@ -0,0 +1,99 @@
+//! F_p arithmetic on BigUint
+//!
+//! The `mod_*` functions are the one implementation: `PrimeField` wraps them for the
+//! Montgomery, Edwards and F_p^2 types, and `Curve`'s `fadd`/`fmul`/... call them on
+//! its own p. Each curve form then only has to describe its group law.
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use crate::modular_sqrt::{is_square_big, tonelli_shanks_big};
+
+pub fn mod_add(a: &BigUint, b: &BigUint, p: &BigUint) -> BigUint {
+    (a + b) % p
+}
+
+pub fn mod_sub(a: &BigUint, b: &BigUint, p: &BigUint) -> BigUint {
+    ((a + p) - (b % p)) % p
+}
+
+pub fn mod_mul(a: &BigUint, b: &BigUint, p: &BigUint) -> BigUint {
+    (a * b) % p
+}
+
+pub fn mod_neg(a: &BigUint, p: &BigUint) -> BigUint {
+    mod_sub(&BigUint::zero(), a, p)
+}
+
+/// Panics on 0, like the `FieldElement::inverse` drills
+pub fn mod_inv(a: &BigUint, p: &BigUint) -> BigUint {
+    a.modinv(p).expect("Cannot invert 0")
+}
+
+/// The prime field F_p. Elements are BigUints, every method assumes they are reduced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrimeField {
+    pub p: BigUint,
+}
+
+impl PrimeField {
+    pub fn new(p: BigUint) -> Self {
+        Self { p }
+    }
+
+    /// Reduce a machine integer, negative values wrap around like the toy curves
+    pub fn reduce_i64(&self, v: i64) -> BigUint {
+        let magnitude = BigUint::from(v.unsigned_abs()) % &self.p;
+        if v < 0 { self.neg(&magnitude) } else { magnitude }
+    }
+
+    pub fn add(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        mod_add(a, b, &self.p)
+    }
+
+    pub fn sub(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        mod_sub(a, b, &self.p)
+    }
+
+    pub fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        mod_mul(a, b, &self.p)
+    }
+
+    pub fn square(&self, a: &BigUint) -> BigUint {
+        self.mul(a, a)
+    }
+
+    pub fn neg(&self, a: &BigUint) -> BigUint {
+        mod_neg(a, &self.p)
+    }
+
+    /// Panics on 0
+    pub fn inv(&self, a: &BigUint) -> BigUint {
+        mod_inv(a, &self.p)
+    }
+
+    pub fn div(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        self.mul(a, &self.inv(b))
+    }
+
+    pub fn pow(&self, a: &BigUint, e: &BigUint) -> BigUint {
+        a.modpow(e, &self.p)
+    }
+
+    pub fn is_square(&self, a: &BigUint) -> bool {
+        is_square_big(a, &self.p)
+    }
+
+    pub fn sqrt(&self, a: &BigUint) -> Option<BigUint> {
+        tonelli_shanks_big(a, &self.p)
+    }
+
+    /// The root with the given parity of its canonical representative
+    pub fn sqrt_with_parity(&self, a: &BigUint, odd: bool) -> Option<BigUint> {
+        let root = self.sqrt(a)?;
+        if root.is_zero() && odd {
+            return None;
+        }
+        Some(if root.bit(0) == odd { root } else { self.neg(&root) })
+    }
+}
@@ -0,0 +1,146 @@
+//! F_p2 = F_p[ω] / (ω^2 + ω + 1) for p ≡ 2 mod 3
+//!
+//! ω is a primitive cube root of unity. F_p only contains one when 3 | p - 1, so for
+//! p ≡ 2 mod 3 the polynomial is irreducible and adjoining ω gives F_p2. That is
+//! exactly the extension the distortion map of y^2 = x^3 + b needs (see `pairing`).
+//!
+//! Elements are c0 + c1 ω. Since ω^2 = -1 - ω:
+//!   (a + b ω)(c + d ω) = (ac - bd) + (ad + bc - bd) ω
+//! and Frobenius sends ω to ω^p = ω^2, so conj(a + b ω) = (a - b) - b ω.
+
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+use super::field::PrimeField;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fp2 {
+    pub c0: BigUint,
+    pub c1: BigUint,
+}
+
+impl Fp2 {
+    pub fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.c0.is_one() && self.c1.is_zero()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuadraticField {
+    pub base: PrimeField,
+}
+
+impl QuadraticField {
+    pub fn new(p: BigUint) -> Result<Self, &'static str> {
+        if &p % 3u32 != BigUint::from(2u32) {
+            return Err("ω^2 + ω + 1 is only irreducible for p = 2 mod 3");
+        }
+        Ok(Self { base: PrimeField::new(p) })
+    }
+
+    pub fn zero(&self) -> Fp2 {
+        self.lift(BigUint::zero())
+    }
+
+    pub fn one(&self) -> Fp2 {
+        self.lift(BigUint::one())
+    }
+
+    /// ω itself
+    pub fn omega(&self) -> Fp2 {
+        Fp2 { c0: BigUint::zero(), c1: BigUint::one() }
+    }
+
+    pub fn lift(&self, c0: BigUint) -> Fp2 {
+        Fp2 { c0: c0 % &self.base.p, c1: BigUint::zero() }
+    }
+
+    pub fn add(&self, a: &Fp2, b: &Fp2) -> Fp2 {
+        Fp2 { c0: self.base.add(&a.c0, &b.c0), c1: self.base.add(&a.c1, &b.c1) }
+    }
+
+    pub fn sub(&self, a: &Fp2, b: &Fp2) -> Fp2 {
+        Fp2 { c0: self.base.sub(&a.c0, &b.c0), c1: self.base.sub(&a.c1, &b.c1) }
+    }
+
+    pub fn neg(&self, a: &Fp2) -> Fp2 {
+        self.sub(&self.zero(), a)
+    }
+
+    pub fn mul(&self, a: &Fp2, b: &Fp2) -> Fp2 {
+        let f = &self.base;
+        let bd = f.mul(&a.c1, &b.c1);
+        let c0 = f.sub(&f.mul(&a.c0, &b.c0), &bd);
+        let c1 = f.sub(&f.add(&f.mul(&a.c0, &b.c1), &f.mul(&a.c1, &b.c0)), &bd);
+        Fp2 { c0, c1 }
+    }
+
+    pub fn square(&self, a: &Fp2) -> Fp2 {
+        self.mul(a, a)
+    }
+
+    /// The Frobenius x -> x^p
+    pub fn conj(&self, a: &Fp2) -> Fp2 {
+        Fp2 { c0: self.base.sub(&a.c0, &a.c1), c1: self.base.neg(&a.c1) }
+    }
+
+    /// a * conj(a) = c0^2 - c0 c1 + c1^2, an element of F_p
+    pub fn norm(&self, a: &Fp2) -> BigUint {
+        let f = &self.base;
+        f.add(&f.sub(&f.square(&a.c0), &f.mul(&a.c0, &a.c1)), &f.square(&a.c1))
+    }
+
+    /// Panics on 0, like `PrimeField::inv`
+    pub fn inv(&self, a: &Fp2) -> Fp2 {
+        let n_inv = self.base.inv(&self.norm(a));
+        let c = self.conj(a);
+        Fp2 { c0: self.base.mul(&c.c0, &n_inv), c1: self.base.mul(&c.c1, &n_inv) }
+    }
+
+    pub fn div(&self, a: &Fp2, b: &Fp2) -> Fp2 {
+        self.mul(a, &self.inv(b))
+    }
+
+    pub fn pow(&self, a: &Fp2, e: &BigUint) -> Fp2 {
+        let mut result = self.one();
+        for i in (0..e.bits()).rev() {
+            result = self.square(&result);
+            if e.bit(i) {
+                result = self.mul(&result, a);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_axioms() {
+        let p = 59u32;
+        let field = QuadraticField::new(BigUint::from(p)).unwrap();
+        assert!(QuadraticField::new(BigUint::from(61u32)).is_err());
+
+        let omega = field.omega();
+        assert!(field.pow(&omega, &BigUint::from(3u32)).is_one());
+        assert!(!omega.is_one());
+        assert!(field.add(&field.add(&field.square(&omega), &omega), &field.one()).is_zero());
+
+        // Every non-zero element is a unit, and x^(p^2 - 1) = 1
+        let order = BigUint::from(p * p - 1);
+        for c0 in (0..p).step_by(7) {
+            for c1 in (1..p).step_by(5) {
+                let a = Fp2 { c0: BigUint::from(c0), c1: BigUint::from(c1) };
+                assert!(field.mul(&a, &field.inv(&a)).is_one());
+                assert!(field.pow(&a, &order).is_one());
+                assert_eq!(field.conj(&a), field.pow(&a, &BigUint::from(p)));
+            }
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! GLV scalar multiplication for a = 0 curves (Gallant, Lambert, Vanstone 2001)
+//!
+//! On y^2 = x^3 + b, if β is a cube root of unity in F_p then φ(x, y) = (β x, y) is
+//! on the curve too, and φ is a group endomorphism. On a cyclic subgroup of prime
+//! order n it has to act as multiplication by some λ with λ^3 = 1 mod n. So
+//! φ(P) = λP costs one field multiplication instead of ~1.5 log n group operations.
+//!
+//! Split k = k1 + k2 λ mod n with |k1|, |k2| ≈ √n, then kP = k1 P + k2 φ(P) with
+//! one interleaved double-and-add of half the length. The split is a closest vector
+//! problem in the 2D lattice {(a, b) : a + b λ = 0 mod n}: the extended Euclidean
+//! algorithm on (n, λ) stopped halfway gives a short basis (Guide to ECC, alg. 3.74),
+//! and rounding k against it gives (k1, k2).
+//!
+//! Needs p = 1 mod 3 (else F_p has no β, like the F17 curve, which is supersingular)
+//! and n = 1 mod 3. F211, secp256k1 and BN254 all qualify.
+
+use std::time::{Duration, Instant};
+
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, Signed, Zero};
+
+use super::curve::{DomainParams, Point};
+use super::ec_projective::BigProjectivePoint;
+
+/// β, λ and the short lattice basis for one domain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlvParams {
+    pub beta: BigUint,
+    pub lambda: BigUint,
+    pub v1: (BigInt, BigInt),
+    pub v2: (BigInt, BigInt),
+}
+
+/// A non-trivial cube root of unity mod the prime q, when 3 | q - 1
+fn cube_root_of_unity(q: &BigUint) -> Option<BigUint> {
+    let e = q - 1u32;
+    if !(&e % 3u32).is_zero() {
+        return None;
+    }
+    let e = e / 3u32;
+    let mut g = BigUint::from(2u32);
+    while &g < q {
+        let root = g.modpow(&e, q);
+        if !root.is_one() {
+            return Some(root);
+        }
+        g += 1u32;
+    }
+    None
+}
+
+/// round(a / b) for b > 0
+fn round_div(a: &BigInt, b: &BigInt) -> BigInt {
+    let numerator: BigInt = a * 2 + b;
+    numerator.div_floor(&(b * 2))
+}
+
+impl GlvParams {
+    pub fn new(domain: &DomainParams) -> Result<Self, &'static str> {
+        let curve = &domain.curve;
+        if !curve.a.is_zero() {
+            return Err("GLV with φ(x, y) = (βx, y) needs a = 0");
+        }
+        let beta = cube_root_of_unity(&curve.p).ok_or("F_p has no cube root of unity")?;
+        let lambda = cube_root_of_unity(&domain.n).ok_or("Z_n has no cube root of unity")?;
+
+        // β pairs with one of the two roots λ, λ^2, the other one goes with β^2
+        let Point::Affine { x, y } = &domain.g else {
+            return Err("generator is the point at infinity");
+        };
+        let phi_g = Point::Affine { x: curve.fmul(&beta, x), y: y.clone() };
+        let lambda = if domain.mul_g(&lambda) == phi_g {
+            lambda
+        } else {
+            let other = lambda.modpow(&BigUint::from(2u32), &domain.n);
+            if domain.mul_g(&other) != phi_g {
+                return Err("φ does not act as a scalar on the generator");
+            }
+            other
+        };
+
+        let (v1, v2) = short_basis(&domain.n, &lambda);
+        Ok(Self { beta, lambda, v1, v2 })
+    }
+
+    /// k = k1 + k2 λ mod n with both halves around √n in absolute value
+    pub fn decompose(&self, k: &BigUint, n: &BigUint) -> (BigInt, BigInt) {
+        let k = BigInt::from(k % n);
+        let n = BigInt::from(n.clone());
+        let ((a1, b1), (a2, b2)) = (&self.v1, &self.v2);
+        let c1 = round_div(&(b2 * &k), &n);
+        let c2 = round_div(&(-b1 * &k), &n);
+        let k1 = &k - &c1 * a1 - &c2 * a2;
+        let k2 = -(&c1 * b1) - &c2 * b2;
+        (k1, k2)
+    }
+
+    /// φ(X : Y : Z) = (βX : Y : Z)
+    pub fn endomorphism(&self, point: &BigProjectivePoint, domain: &DomainParams) -> BigProjectivePoint {
+        BigProjectivePoint {
+            x: domain.curve.fmul(&self.beta, &point.x),
+            y: point.y.clone(),
+            z: point.z.clone(),
+        }
+    }
+
+    /// k P for P in the order n subgroup, interleaving k1 P and k2 φ(P)
+    pub fn mul(&self, point: &BigProjectivePoint, k: &BigUint, domain: &DomainParams) -> BigProjectivePoint {
+        let curve = &domain.curve;
+        let (k1, k2) = self.decompose(k, &domain.n);
+        let signed = |p: BigProjectivePoint, k: &BigInt| if k.is_negative() { p.neg(curve) } else { p };
+        let p1 = signed(point.clone(), &k1);
+        let p2 = signed(self.endomorphism(point, domain), &k2);
+        let both = p1.add(&p2, curve);
+        let (k1, k2) = (k1.magnitude(), k2.magnitude());
+
+        let mut result = BigProjectivePoint::infinity();
+        for i in (0..k1.bits().max(k2.bits())).rev() {
+            result = result.double(curve);
+            match (k1.bit(i), k2.bit(i)) {
+                (true, true) => result = result.add(&both, curve),
+                (true, false) => result = result.add(&p1, curve),
+                (false, true) => result = result.add(&p2, curve),
+                (false, false) => {}
+            }
+        }
+        result
+    }
+}
+
+/// Two short vectors (a, b) with a + b λ = 0 mod n, from the extended Euclidean
+/// algorithm on (n, λ): each remainder r_i = s_i n + t_i λ gives (r_i, -t_i)
+fn short_basis(n: &BigUint, lambda: &BigUint) -> ((BigInt, BigInt), (BigInt, BigInt)) {
+    let sqrt_n = BigInt::from(n.sqrt());
+    let mut r = vec![BigInt::from(n.clone()), BigInt::from(lambda.clone())];
+    let mut t = vec![BigInt::zero(), BigInt::one()];
+    while !r.last().unwrap().is_zero() {
+        let i = r.len() - 1;
+        let q = &r[i - 1] / &r[i];
+        r.push(&r[i - 1] - &q * &r[i]);
+        t.push(&t[i - 1] - &q * &t[i]);
+    }
+    // l: the last index with r_l >= √n, r_{l+1} is the first one below
+    let l = r.iter().rposition(|ri| ri >= &sqrt_n).expect("r_0 = n >= √n");
+    let vector = |i: usize| (r[i].clone(), -t[i].clone());
+    let norm = |(a, b): &(BigInt, BigInt)| a * a + b * b;
+    let v1 = vector(l + 1);
+    let candidate = vector(l);
+    let v2 = match r.get(l + 2) {
+        Some(_) if norm(&vector(l + 2)) < norm(&candidate) => vector(l + 2),
+        _ => candidate,
+    };
+    (v1, v2)
+}
+
+/// Wall-clock time of `rounds` scalar multiplications with each method
+#[derive(Debug, Clone)]
+pub struct Benchmark {
+    pub rounds: u32,
+    pub double_and_add: Duration,
+    pub glv: Duration,
+}
+
+/// Same random scalars through `BigProjectivePoint::scalar_mul` and `GlvParams::mul`
+pub fn benchmark(domain: &DomainParams, rounds: u32) -> Result<Benchmark, &'static str> {
+    let params = GlvParams::new(domain)?;
+    let g = BigProjectivePoint::from(domain.g.clone());
+    let scalars: Vec<BigUint> = (0..rounds).map(|_| domain.random_scalar()).collect();
+
+    let start = Instant::now();
+    let plain: Vec<Point> = scalars.iter().map(|k| g.scalar_mul(k, &domain.curve).to_affine(&domain.curve)).collect();
+    let double_and_add = start.elapsed();
+
+    let start = Instant::now();
+    let fast: Vec<Point> = scalars.iter().map(|k| params.mul(&g, k, domain).to_affine(&domain.curve)).collect();
+    let glv = start.elapsed();
+
+    if plain != fast {
+        return Err("GLV and double-and-add disagree");
+    }
+    Ok(Benchmark { rounds, double_and_add, glv })
+}
+
+pub fn entry_point() {
+    println!("--- GLV ---");
+    for (name, domain) in [("secp256k1", DomainParams::secp256k1()), ("BN254 G1", DomainParams::bn254_g1())] {
+        match benchmark(&domain, 20) {
+            Ok(b) => println!(
+                "{name}: {} multiplications, double-and-add {:?}, GLV {:?} ({:.2}x)",
+                b.rounds,
+                b.double_and_add,
+                b.glv,
+                b.double_and_add.as_secs_f64() / b.glv.as_secs_f64()
+            ),
+            Err(e) => println!("{name}: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elliptic_point::curve::{Curve, hex_biguint};
+
+    #[test]
+    fn test_secp256k1_constants() {
+        let domain = DomainParams::secp256k1();
+        let params = GlvParams::new(&domain).unwrap();
+        // The well-known values libsecp256k1 hardcodes
+        assert_eq!(params.beta, hex_biguint("7ae96a2b657c07106e64479eac3434e99cf0497512f58995c1396c28719501ee"));
+        assert_eq!(params.lambda, hex_biguint("5363ad4cc05c30e0a5261c028812645a122e22ea20816678df02967c1b23bd72"));
+    }
+
+    #[test]
+    fn test_decomposition() {
+        for domain in [DomainParams::secp256k1(), DomainParams::bn254_g1(), DomainParams::f211()] {
+            let params = GlvParams::new(&domain).unwrap();
+            let n = BigInt::from(domain.n.clone());
+            let lambda = BigInt::from(params.lambda.clone());
+            for (a, b) in [&params.v1, &params.v2] {
+                assert!(((a + b * &lambda) % &n).is_zero());
+            }
+            for _ in 0..20 {
+                let k = domain.random_scalar();
+                let (k1, k2) = params.decompose(&k, &domain.n);
+                assert_eq!((&k1 + &k2 * &lambda).mod_floor(&n), BigInt::from(k));
+                // Half-size, give or take a couple of bits
+                let half = domain.n.bits() / 2 + 2;
+                assert!(k1.bits() <= half && k2.bits() <= half, "{k1} {k2}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_glv_matches_double_and_add() {
+        for domain in [DomainParams::secp256k1(), DomainParams::bn254_g1(), DomainParams::f211()] {
+            let params = GlvParams::new(&domain).unwrap();
+            let g = BigProjectivePoint::from(domain.g.clone());
+            let phi = params.endomorphism(&g, &domain).to_affine(&domain.curve);
+            assert_eq!(phi, domain.mul_g(&params.lambda));
+            let n_minus_1 = &domain.n - 1u32;
+            for k in [BigUint::one(), BigUint::from(2u32), n_minus_1, domain.n.clone(), domain.random_scalar()] {
+                assert_eq!(params.mul(&g, &k, &domain).to_affine(&domain.curve), domain.mul_g(&(k % &domain.n)));
+            }
+        }
+        assert!(benchmark(&DomainParams::f211(), 10).is_ok());
+    }
+
+    #[test]
+    fn test_unsupported_curves() {
+        // F17 is supersingular: p = 2 mod 3, no β
+        assert_eq!(GlvParams::new(&DomainParams::toy(Curve::f17())), Err("F_p has no cube root of unity"));
+        assert_eq!(GlvParams::new(&DomainParams::p256()), Err("GLV with φ(x, y) = (βx, y) needs a = 0"));
+    }
+
+    /// cargo test glv -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_glv() {
+        entry_point();
+    }
+}
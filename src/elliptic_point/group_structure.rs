@@ -0,0 +1,243 @@
+//! Group structure of toy curves
+//!
+//! Brute force tooling for curves over small fields: list every point, count them,
+//! find the order of each point and decompose E(F_p) into cyclic factors.
+//! Everything here walks the whole field, so p has to be small (it must fit in a u64
+//! and realistically be in the thousands).
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
+
+use super::curve::{Curve, Point};
+use crate::modular_sqrt::tonelli_shanks;
+
+/// E(F_p) ≅ Z_n1 × Z_n2 with n2 | n1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GroupStructure {
+    pub order: u64,
+    pub n1: u64,
+    pub n2: u64,
+}
+
+impl GroupStructure {
+    pub fn is_cyclic(&self) -> bool {
+        self.n2 == 1
+    }
+}
+
+/// The largest prime order subgroup, the one cryptography actually happens in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrimeSubgroup {
+    pub order: u64,
+    pub cofactor: u64,
+    /// Every point of exact order `order`
+    pub generators: Vec<Point>,
+}
+
+fn field_size(curve: &Curve) -> u64 {
+    curve
+        .p
+        .to_u64()
+        .expect("group structure tooling only works for toy curves (p must fit in a u64)")
+}
+
+/// List every point of the curve, Infinity first then sorted by (x, y)
+///
+/// For each x we need y^2 = x^3 + ax + b, so we take a modular square root of the rhs
+/// and get the two points (x, y) and (x, p - y), or a single point when y = 0.
+pub fn enumerate_points(curve: &Curve) -> Vec<Point> {
+    let p = field_size(curve);
+    let mut points = vec![Point::Infinity];
+    for x in 0..p {
+        let rhs = curve.rhs(&BigUint::from(x)).to_u64().unwrap();
+        if rhs == 0 {
+            points.push(Point::new(x, 0));
+            continue;
+        }
+        if let Some(y) = tonelli_shanks(rhs as u128, p as u128) {
+            let y = y as u64;
+            let (lo, hi) = if y < p - y { (y, p - y) } else { (p - y, y) };
+            points.push(Point::new(x, lo));
+            points.push(Point::new(x, hi));
+        }
+    }
+    points
+}
+
+/// #E(F_p), including the point at infinity
+pub fn count_points(curve: &Curve) -> u64 {
+    enumerate_points(curve).len() as u64
+}
+
+/// Prime factorization by trial division: n = Π q^e
+pub fn factorize(mut n: u64) -> Vec<(u64, u32)> {
+    let mut factors = Vec::new();
+    let mut q = 2;
+    while q * q <= n {
+        let mut e = 0;
+        while n.is_multiple_of(q) {
+            n /= q;
+            e += 1;
+        }
+        if e > 0 {
+            factors.push((q, e));
+        }
+        q += if q == 2 { 1 } else { 2 };
+    }
+    if n > 1 {
+        factors.push((n, 1));
+    }
+    factors
+}
+
+/// Smallest k > 0 with k * P = Infinity
+///
+/// By Lagrange the order divides the group order, so start there and strip prime
+/// factors as long as the smaller multiple still kills the point.
+pub fn point_order(curve: &Curve, point: &Point, group_order: u64) -> u64 {
+    let mut order = group_order;
+    for (q, _) in factorize(group_order) {
+        while order.is_multiple_of(q) && curve.mul_u64(point, order / q).is_infinity() {
+            order /= q;
+        }
+    }
+    order
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Decompose E(F_p) into Z_n1 × Z_n2
+///
+/// n1 is the group exponent (the lcm of all point orders) and n2 = #E / n1.
+/// The group is cyclic exactly when some point has order #E.
+pub fn group_structure(curve: &Curve) -> GroupStructure {
+    let points = enumerate_points(curve);
+    let order = points.len() as u64;
+    let mut n1 = 1;
+    for point in &points {
+        let k = point_order(curve, point, order);
+        n1 = n1 / gcd(n1, k) * k;
+        if n1 == order {
+            break;
+        }
+    }
+    GroupStructure {
+        order,
+        n1,
+        n2: order / n1,
+    }
+}
+
+/// Largest prime q dividing #E, its cofactor #E / q and all points of order q
+/// Returns None for the trivial group
+pub fn prime_order_subgroup(curve: &Curve) -> Option<PrimeSubgroup> {
+    let points = enumerate_points(curve);
+    let order = points.len() as u64;
+    let (q, _) = *factorize(order).last()?;
+    let cofactor = order / q;
+    // The q-torsion: every non-identity point with q P = O has order exactly q
+    let generators = points
+        .iter()
+        .filter(|pt| !pt.is_infinity())
+        .filter(|pt| curve.mul_u64(pt, q).is_infinity())
+        .cloned()
+        .collect();
+    Some(PrimeSubgroup {
+        order: q,
+        cofactor,
+        generators,
+    })
+}
+
+/// "x,y,order" rows for plotting, Infinity has no coordinates so it is left out
+pub fn points_to_csv(curve: &Curve, points: &[Point]) -> String {
+    let group_order = count_points(curve);
+    let mut csv = String::from("x,y,order\n");
+    for point in points {
+        if let Point::Affine { x, y } = point {
+            let order = point_order(curve, point, group_order);
+            csv.push_str(&format!("{},{},{}\n", x, y, order));
+        }
+    }
+    csv
+}
+
+/// Write every point of the curve to a CSV file
+pub fn write_points_csv(curve: &Curve, path: impl AsRef<Path>) -> io::Result<()> {
+    let points = enumerate_points(curve);
+    fs::write(path, points_to_csv(curve, &points))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enumerate_f17() {
+        // y^2 = x^3 + 1 is supersingular for p = 2 mod 3, so #E = p + 1 = 18
+        let curve = Curve::f17();
+        let points = enumerate_points(&curve);
+        assert_eq!(points.len(), 18);
+        assert!(points.iter().all(|pt| curve.contains(pt)));
+        assert!(points.contains(&Point::new(1, 6)));
+        assert!(points.contains(&Point::new(16, 0)));
+    }
+
+    #[test]
+    fn test_point_orders() {
+        let curve = Curve::f17();
+        let order = count_points(&curve);
+        assert_eq!(point_order(&curve, &Point::Infinity, order), 1);
+        // (0, 1) + (0, 1) = (0, 16), then a vertical line
+        assert_eq!(point_order(&curve, &Point::new(0, 1), order), 3);
+        assert_eq!(point_order(&curve, &Point::new(16, 0), order), 2);
+        assert_eq!(point_order(&curve, &Point::new(1, 6), order), 9);
+        // lcm(9, 2) = 18, so this one generates the whole group
+        let g = curve.add(&Point::new(1, 6), &Point::new(16, 0));
+        assert_eq!(point_order(&curve, &g, order), 18);
+    }
+
+    #[test]
+    fn test_group_structure() {
+        let s = group_structure(&Curve::f17());
+        assert_eq!(s, GroupStructure { order: 18, n1: 18, n2: 1 });
+        assert!(s.is_cyclic());
+
+        // y^2 = x^3 - x over F_11 has full 2-torsion: Z_6 × Z_2
+        let s = group_structure(&Curve::small(11, -1, 0));
+        assert_eq!(s, GroupStructure { order: 12, n1: 6, n2: 2 });
+        assert!(!s.is_cyclic());
+    }
+
+    #[test]
+    fn test_prime_order_subgroup() {
+        let curve = Curve::f17();
+        let sub = prime_order_subgroup(&curve).unwrap();
+        assert_eq!(sub.order, 3);
+        assert_eq!(sub.cofactor, 6);
+        assert_eq!(sub.generators, vec![Point::new(0, 1), Point::new(0, 16)]);
+
+        let curve = Curve::f211();
+        let sub = prime_order_subgroup(&curve).unwrap();
+        assert_eq!(sub.order * sub.cofactor, count_points(&curve));
+        for g in &sub.generators {
+            assert_eq!(point_order(&curve, g, count_points(&curve)), sub.order);
+        }
+    }
+
+    #[test]
+    fn test_csv_export() {
+        let curve = Curve::f17();
+        let csv = points_to_csv(&curve, &enumerate_points(&curve));
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("x,y,order"));
+        assert_eq!(lines.count(), 17);
+        assert!(csv.contains("0,1,3\n"));
+    }
+}
@@ -0,0 +1,407 @@
+//! Hashing to elliptic curves (RFC 9380)
+//!
+//! A "nothing up my sleeve" generator is a point nobody knows the discrete log of.
+//! Picking it with `rand` is not verifiable, so instead we hash a public
+//! domain-separation tag (DST) to the curve and anyone can recompute the result.
+//!
+//! 1. `expand_message_xmd` stretches (msg, DST) into uniform bytes with SHA-256
+//! 2. `hash_to_field` turns those bytes into field elements u0, u1
+//! 3. A map sends each u to a point: simplified SWU (needs ab != 0, e.g. P-256),
+//!    Shallue-van de Woestijne (works on any curve, e.g. BN254 where a = 0),
+//!    or plain try-and-increment for the toy curves
+//! 4. Q0 + Q1, then clear the cofactor so the result lands in the prime subgroup
+
+use ark_bn254::{Fq, Fq2, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use sha2::{Digest, Sha256};
+
+use super::curve::{Curve, Point};
+use crate::modular_sqrt::{is_square_big, tonelli_shanks_big};
+
+/// SHA-256 output size in bytes
+const B_IN_BYTES: usize = 32;
+/// SHA-256 block size in bytes
+const S_IN_BYTES: usize = 64;
+/// Target security level k for hash_to_field
+const SECURITY_BITS: u64 = 128;
+
+/// DST used to derive the BN254 Pedersen generators, see `perdesen_commitment`
+pub const PEDERSEN_DST: &[u8] = b"INTRO-TO-RUST-ZK-V01-CS01-with-BN254G1_XMD:SHA-256_SVDW_RO_";
+
+/// RFC 9380 section 5.3.1
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Result<Vec<u8>, &'static str> {
+    let ell = len_in_bytes.div_ceil(B_IN_BYTES);
+    if ell > 255 || len_in_bytes > 65535 {
+        return Err("requested output is too long for expand_message_xmd");
+    }
+    // Section 5.3.3: oversized tags are hashed down first
+    let dst = if dst.len() > 255 {
+        Sha256::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(dst)
+            .finalize()
+            .to_vec()
+    } else {
+        dst.to_vec()
+    };
+    let mut dst_prime = dst.clone();
+    dst_prime.push(dst.len() as u8);
+
+    // b_0 = H(Z_pad || msg || I2OSP(len_in_bytes, 2) || 0x00 || DST_prime)
+    let b_0 = Sha256::new()
+        .chain_update([0u8; S_IN_BYTES])
+        .chain_update(msg)
+        .chain_update((len_in_bytes as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst_prime)
+        .finalize();
+
+    // b_1 = H(b_0 || 0x01 || DST_prime)
+    // b_i = H(strxor(b_0, b_(i-1)) || I2OSP(i, 1) || DST_prime)
+    let mut uniform_bytes = Vec::with_capacity(ell * B_IN_BYTES);
+    let mut b_i = Sha256::new()
+        .chain_update(b_0)
+        .chain_update([1u8])
+        .chain_update(&dst_prime)
+        .finalize();
+    uniform_bytes.extend_from_slice(&b_i);
+    for i in 2..=ell {
+        let xored: Vec<u8> = b_0.iter().zip(b_i.iter()).map(|(a, b)| a ^ b).collect();
+        b_i = Sha256::new()
+            .chain_update(xored)
+            .chain_update([i as u8])
+            .chain_update(&dst_prime)
+            .finalize();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+    uniform_bytes.truncate(len_in_bytes);
+    Ok(uniform_bytes)
+}
+
+/// RFC 9380 section 5.2 for prime fields (m = 1)
+/// Each element takes L = ceil((ceil(log2(p)) + k) / 8) bytes so the bias mod p is negligible
+pub fn hash_to_field(msg: &[u8], dst: &[u8], count: usize, p: &BigUint) -> Vec<BigUint> {
+    let l = (p.bits() + SECURITY_BITS).div_ceil(8) as usize;
+    let uniform_bytes = expand_message_xmd(msg, dst, count * l).expect("hash_to_field output too long");
+    uniform_bytes
+        .chunks(l)
+        .map(|chunk| BigUint::from_bytes_be(chunk) % p)
+        .collect()
+}
+
+/// sgn0 for prime fields: the parity of the canonical representative
+fn sgn0(x: &BigUint) -> bool {
+    x.bit(0)
+}
+
+/// sqrt of a known square with the sign fixed to match `sign`
+fn sqrt_with_sign(curve: &Curve, value: &BigUint, sign: bool) -> BigUint {
+    let y = tonelli_shanks_big(value, &curve.p).expect("value is a square by construction");
+    if sgn0(&y) == sign { y } else { curve.fneg(&y) }
+}
+
+/// Which deterministic map to use, each carries its constant Z
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mapping {
+    /// Simplified Shallue-van de Woestijne-Ulas, only for curves with a != 0 and b != 0
+    Sswu(BigUint),
+    /// Shallue-van de Woestijne, any curve
+    Svdw(BigUint),
+}
+
+/// Z for simplified SWU (RFC 9380 appendix H.2), trying 1, -1, 2, -2, ... in that order
+///
+/// Z must be a non-square, Z != -1, g(x) - Z must have no root and g(B / (Z*A)) must be
+/// a square. The root check walks the whole field, so this is only for toy curves.
+/// Real suites hardcode Z (P-256 uses -10).
+pub fn find_z_sswu(curve: &Curve) -> BigUint {
+    let p = &curve.p;
+    let candidates = (1u32..).flat_map(|c| [BigUint::from(c), curve.fneg(&BigUint::from(c))]);
+    for z in candidates {
+        if is_square_big(&z, p) || z == p - 1u32 {
+            continue;
+        }
+        let mut x = BigUint::zero();
+        let mut has_root = false;
+        while &x < p {
+            if curve.rhs(&x) == z {
+                has_root = true;
+                break;
+            }
+            x += 1u32;
+        }
+        if has_root {
+            continue;
+        }
+        let x = curve.fmul(&curve.b, &curve.finv(&curve.fmul(&z, &curve.a)));
+        if is_square_big(&curve.rhs(&x), p) {
+            return z;
+        }
+    }
+    unreachable!()
+}
+
+/// Z for Shallue-van de Woestijne (RFC 9380 appendix H.1)
+pub fn find_z_svdw(curve: &Curve) -> BigUint {
+    let p = &curve.p;
+    let candidates = (1u32..).flat_map(|c| [BigUint::from(c), curve.fneg(&BigUint::from(c))]);
+    for z in candidates {
+        let gz = curve.rhs(&z);
+        if gz.is_zero() {
+            continue;
+        }
+        // h(Z) = -(3Z^2 + 4A) / (4g(Z)) must be a non-zero square
+        let three_z2_4a = curve.fadd(
+            &curve.fmul(&BigUint::from(3u32), &curve.fmul(&z, &z)),
+            &curve.fmul(&BigUint::from(4u32), &curve.a),
+        );
+        let hz = curve.fneg(&curve.fmul(
+            &three_z2_4a,
+            &curve.finv(&curve.fmul(&BigUint::from(4u32), &gz)),
+        ));
+        if hz.is_zero() || !is_square_big(&hz, p) {
+            continue;
+        }
+        let minus_z_over_2 = curve.fneg(&curve.fmul(&z, &curve.finv(&BigUint::from(2u32))));
+        if is_square_big(&gz, p) || is_square_big(&curve.rhs(&minus_z_over_2), p) {
+            return z;
+        }
+    }
+    unreachable!()
+}
+
+/// Simplified SWU (RFC 9380 section 6.6.2)
+pub fn map_to_curve_sswu(curve: &Curve, z: &BigUint, u: &BigUint) -> Point {
+    assert!(!curve.a.is_zero() && !curve.b.is_zero(), "simplified SWU needs ab != 0");
+    let u2 = curve.fmul(u, u);
+    let z_u2 = curve.fmul(z, &u2);
+    // tv1 = 1 / (Z^2 u^4 + Z u^2), with inv0(0) = 0
+    let denom = curve.fadd(&curve.fmul(&z_u2, &z_u2), &z_u2);
+    let minus_b_over_a = curve.fneg(&curve.fmul(&curve.b, &curve.finv(&curve.a)));
+    let x1 = if denom.is_zero() {
+        // Exceptional case: x1 = B / (Z * A)
+        curve.fmul(&curve.b, &curve.finv(&curve.fmul(z, &curve.a)))
+    } else {
+        let tv1 = curve.finv(&denom);
+        curve.fmul(&minus_b_over_a, &curve.fadd(&BigUint::one(), &tv1))
+    };
+    let gx1 = curve.rhs(&x1);
+    let x = if is_square_big(&gx1, &curve.p) {
+        x1
+    } else {
+        // x2 = Z u^2 x1, and g(x2) is then guaranteed to be a square
+        curve.fmul(&z_u2, &x1)
+    };
+    let y = sqrt_with_sign(curve, &curve.rhs(&x), sgn0(u));
+    Point::Affine { x, y }
+}
+
+/// Shallue-van de Woestijne (RFC 9380 section 6.6.1)
+pub fn map_to_curve_svdw(curve: &Curve, z: &BigUint, u: &BigUint) -> Point {
+    let one = BigUint::one();
+    let gz = curve.rhs(z);
+    let three_z2_4a = curve.fadd(
+        &curve.fmul(&BigUint::from(3u32), &curve.fmul(z, z)),
+        &curve.fmul(&BigUint::from(4u32), &curve.a),
+    );
+    // c2 = -Z / 2
+    let c2 = curve.fneg(&curve.fmul(z, &curve.finv(&BigUint::from(2u32))));
+    // c3 = sqrt(-g(Z) * (3Z^2 + 4A)) with sgn0(c3) = 0
+    let c3 = sqrt_with_sign(curve, &curve.fneg(&curve.fmul(&gz, &three_z2_4a)), false);
+    // c4 = -4g(Z) / (3Z^2 + 4A)
+    let c4 = curve.fneg(&curve.fmul(
+        &curve.fmul(&BigUint::from(4u32), &gz),
+        &curve.finv(&three_z2_4a),
+    ));
+
+    let tv1 = curve.fmul(&curve.fmul(u, u), &gz);
+    let tv2 = curve.fadd(&one, &tv1);
+    let tv1 = curve.fsub(&one, &tv1);
+    let tv3 = curve.fmul(&tv1, &tv2);
+    let tv3 = if tv3.is_zero() { tv3 } else { curve.finv(&tv3) };
+    let tv4 = curve.fmul(&curve.fmul(&curve.fmul(u, &tv1), &tv3), &c3);
+
+    let x1 = curve.fsub(&c2, &tv4);
+    let x2 = curve.fadd(&c2, &tv4);
+    let x3 = {
+        let t = curve.fmul(&curve.fmul(&tv2, &tv2), &tv3);
+        curve.fadd(&curve.fmul(&curve.fmul(&t, &t), &c4), z)
+    };
+    let x = if is_square_big(&curve.rhs(&x1), &curve.p) {
+        x1
+    } else if is_square_big(&curve.rhs(&x2), &curve.p) {
+        x2
+    } else {
+        x3
+    };
+    let y = sqrt_with_sign(curve, &curve.rhs(&x), sgn0(u));
+    Point::Affine { x, y }
+}
+
+pub fn map_to_curve(curve: &Curve, mapping: &Mapping, u: &BigUint) -> Point {
+    match mapping {
+        Mapping::Sswu(z) => map_to_curve_sswu(curve, z, u),
+        Mapping::Svdw(z) => map_to_curve_svdw(curve, z, u),
+    }
+}
+
+/// hash_to_curve random oracle construction: clear_cofactor(map(u0) + map(u1))
+pub fn hash_to_curve(curve: &Curve, mapping: &Mapping, cofactor: &BigUint, msg: &[u8], dst: &[u8]) -> Point {
+    let u = hash_to_field(msg, dst, 2, &curve.p);
+    let q0 = map_to_curve(curve, mapping, &u[0]);
+    let q1 = map_to_curve(curve, mapping, &u[1]);
+    curve.mul(&curve.add(&q0, &q1), cofactor)
+}
+
+/// Try-and-increment: hash (msg || ctr) to x until x^3 + ax + b is a square
+///
+/// Not constant time (the number of tries leaks), which is fine for public generators
+/// on the toy curves where the RFC maps are overkill. Returns None if all 256
+/// counters fail, which for a real curve happens with probability 2^-256.
+pub fn try_and_increment(curve: &Curve, cofactor: &BigUint, msg: &[u8], dst: &[u8]) -> Option<Point> {
+    for ctr in 0u8..=255 {
+        let mut input = msg.to_vec();
+        input.push(ctr);
+        let u = hash_to_field(&input, dst, 2, &curve.p);
+        let rhs = curve.rhs(&u[0]);
+        if !is_square_big(&rhs, &curve.p) {
+            continue;
+        }
+        let point = Point::Affine {
+            y: sqrt_with_sign(curve, &rhs, sgn0(&u[1])),
+            x: u[0].clone(),
+        };
+        let point = curve.mul(&point, cofactor);
+        if !point.is_infinity() {
+            return Some(point);
+        }
+    }
+    None
+}
+
+/// Hash to BN254 G1 with Shallue-van de Woestijne (Z = 1)
+/// G1 has cofactor 1, so every curve point is already in the prime subgroup
+pub fn hash_to_bn254_g1(msg: &[u8], dst: &[u8]) -> G1Affine {
+    let curve = Curve::bn254_g1();
+    let z = find_z_svdw(&curve);
+    match hash_to_curve(&curve, &Mapping::Svdw(z), &BigUint::one(), msg, dst) {
+        Point::Infinity => G1Affine::identity(),
+        Point::Affine { x, y } => G1Affine::new(Fq::from(x), Fq::from(y)),
+    }
+}
+
+/// Hash to BN254 G2 by try-and-increment over F_q2, then clear the cofactor
+///
+/// G2 lives on the sextic twist y^2 = x^3 + 3 / (9 + u), which has a cofactor of
+/// about 2^254, so unlike G1 a curve point is almost never in the subgroup. Same
+/// caveats as `try_and_increment`: not constant time, not an RFC 9380 suite.
+pub fn hash_to_bn254_g2(msg: &[u8], dst: &[u8]) -> G2Affine {
+    let q = Curve::bn254_g1().p;
+    for ctr in 0u8..=255 {
+        let mut input = msg.to_vec();
+        input.push(ctr);
+        let u = hash_to_field(&input, dst, 3, &q);
+        let x = Fq2::new(Fq::from(u[0].clone()), Fq::from(u[1].clone()));
+        if let Some(point) = G2Affine::get_point_from_x_unchecked(x, sgn0(&u[2])) {
+            let point = point.clear_cofactor();
+            if !point.is_zero() {
+                return point;
+            }
+        }
+    }
+    unreachable!("256 non-squares in a row")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::{bytes_to_hex, hex_to_bytes};
+    use crate::elliptic_point::curve::hex_biguint;
+    use crate::elliptic_point::group_structure::prime_order_subgroup;
+
+    const XMD_DST: &[u8] = b"QUUX-V01-CS02-with-expander-SHA256-128";
+
+    #[test]
+    fn test_expand_message_xmd_vectors() {
+        // RFC 9380 appendix K.1
+        let cases = [
+            ("", 0x20, "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"),
+            ("abc", 0x20, "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"),
+            ("abcdef0123456789", 0x20, "eff31487c770a893cfb36f912fbfcbff40d5661771ca4b2cb4eafe524333f5c1"),
+        ];
+        for (msg, len, expected) in cases {
+            let out = expand_message_xmd(msg.as_bytes(), XMD_DST, len).unwrap();
+            assert_eq!(bytes_to_hex(&out), expected);
+        }
+        assert_eq!(expand_message_xmd(b"", XMD_DST, 0x80).unwrap().len(), 0x80);
+        assert!(expand_message_xmd(b"", XMD_DST, 256 * 32).is_err());
+        assert_eq!(hex_to_bytes("00ff"), vec![0, 255]);
+    }
+
+    #[test]
+    fn test_p256_sswu_vector() {
+        // RFC 9380 appendix J.1.1, P256_XMD:SHA-256_SSWU_RO_, msg = ""
+        let curve = Curve::p256();
+        let dst = b"QUUX-V01-CS02-with-P256_XMD:SHA-256_SSWU_RO_";
+        let u = hash_to_field(b"", dst, 2, &curve.p);
+        assert_eq!(u[0], hex_biguint("ad5342c66a6dd0ff080df1da0ea1c04b96e0330dd89406465eeba11582515009"));
+        assert_eq!(u[1], hex_biguint("8c0f1d43204bd6f6ea70ae8013070a1518b43873bcd850aafa0a9e220e2eea5a"));
+
+        let z = curve.fneg(&BigUint::from(10u32));
+        let point = hash_to_curve(&curve, &Mapping::Sswu(z), &BigUint::one(), b"", dst);
+        assert_eq!(
+            point,
+            Point::Affine {
+                x: hex_biguint("2c15230b26dbc6fc9a37051158c95b79656e17a1a920b11394ca91c44247d3e4"),
+                y: hex_biguint("8a7a74985cc5c776cdfe4b1f19884970453912e9d31528c060be9ab5c43e8415"),
+            }
+        );
+    }
+
+    #[test]
+    fn test_toy_maps_land_on_curve() {
+        // y^2 = x^3 + 2x + 3 over F_103, ab != 0 so both maps apply
+        let curve = Curve::small(103, 2, 3);
+        let sswu = Mapping::Sswu(find_z_sswu(&curve));
+        let svdw = Mapping::Svdw(find_z_svdw(&curve));
+        for u in 0..103u64 {
+            let u = BigUint::from(u);
+            assert!(curve.contains(&map_to_curve(&curve, &sswu, &u)));
+            assert!(curve.contains(&map_to_curve(&curve, &svdw, &u)));
+        }
+    }
+
+    #[test]
+    fn test_try_and_increment_toy_generator() {
+        let curve = Curve::f211();
+        let sub = prime_order_subgroup(&curve).unwrap();
+        let h = BigUint::from(sub.cofactor);
+        let g = try_and_increment(&curve, &h, b"generator", b"toy-dst").unwrap();
+        // Deterministic, on the curve and in the order 61 subgroup
+        assert_eq!(Some(g.clone()), try_and_increment(&curve, &h, b"generator", b"toy-dst"));
+        assert!(sub.generators.contains(&g));
+        assert_ne!(Some(g), try_and_increment(&curve, &h, b"generator", b"other-dst"));
+    }
+
+    #[test]
+    fn test_bn254_pedersen_generator() {
+        let h = hash_to_bn254_g1(b"H", PEDERSEN_DST);
+        assert!(h.is_on_curve());
+        assert!(h.is_in_correct_subgroup_assuming_on_curve());
+        assert_ne!(h, G1Affine::generator());
+        // Anyone recomputing from the same DST gets the same H
+        assert_eq!(h, hash_to_bn254_g1(b"H", PEDERSEN_DST));
+        assert_ne!(h, hash_to_bn254_g1(b"G", PEDERSEN_DST));
+    }
+
+    #[test]
+    fn test_bn254_g2_hash() {
+        let h = hash_to_bn254_g2(b"msg", b"toy-dst");
+        assert!(h.is_on_curve());
+        assert!(h.is_in_correct_subgroup_assuming_on_curve());
+        assert_eq!(h, hash_to_bn254_g2(b"msg", b"toy-dst"));
+        assert_ne!(h, hash_to_bn254_g2(b"msg", b"other-dst"));
+    }
+}
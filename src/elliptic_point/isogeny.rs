@@ -0,0 +1,299 @@
+//! Isogenies between toy curves with Vélu's formulas
+//!
+//! An isogeny is a group homomorphism E -> E' given by rational functions. Every
+//! finite subgroup G of E is the kernel of exactly one separable isogeny (up to
+//! isomorphism of E'), of degree #G, and Vélu's formulas write it down explicitly
+//! from the points of G. Isogenous curves over F_p have the same number of points.
+//!
+//! Vélu (Washington, Elliptic Curves, theorem 12.16), for E: y^2 = x^3 + a x + b and
+//! S a set of representatives of (G \ {O}) / ±:
+//!   g^x_Q = 3 x_Q^2 + a,  g^y_Q = -2 y_Q
+//!   v_Q = g^x_Q if 2Q = O else 2 g^x_Q,  u_Q = (g^y_Q)^2
+//!   v = Σ v_Q,  w = Σ (u_Q + x_Q v_Q)
+//!   E': y^2 = x^3 + (a - 5v) x + (b - 7w)
+//!   X = x + Σ (v_Q / (x - x_Q) + u_Q / (x - x_Q)^2)
+//!   Y = y - Σ (u_Q 2y / (x - x_Q)^3 + v_Q (y - y_Q) / (x - x_Q)^2 - g^x_Q g^y_Q / (x - x_Q)^2)
+//!
+//! Kernels here are subgroups of E(F_p), found by enumerating points, so only the
+//! ℓ-isogenies whose kernel points are all rational show up. The isogeny graph
+//! (vertices j-invariants, edges ℓ-isogenies) is what SIDH/CSIDH-style schemes walk;
+//! SIDH itself lives over F_p2 with supersingular curves, which this does not model.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use super::curve::{Curve, Point};
+use super::group_structure::{enumerate_points, factorize};
+
+/// j = 1728 * 4a^3 / (4a^3 + 27b^2), which classifies curves up to isomorphism over F̄_p
+pub fn j_invariant(curve: &Curve) -> BigUint {
+    let four_a3 = curve.fmul(&BigUint::from(4u32), &curve.fmul(&curve.a, &curve.fmul(&curve.a, &curve.a)));
+    let denominator = curve.fadd(&four_a3, &curve.fmul(&BigUint::from(27u32), &curve.fmul(&curve.b, &curve.b)));
+    let numerator = curve.fmul(&BigUint::from(1728u32), &four_a3);
+    curve.fmul(&numerator, &curve.finv(&denominator))
+}
+
+/// Every cyclic subgroup of prime order ℓ in E(F_p), as the list of its points
+/// (without O). Each one is the kernel of a different ℓ-isogeny.
+pub fn kernel_subgroups(curve: &Curve, ell: u64) -> Result<Vec<Vec<Point>>, &'static str> {
+    if factorize(ell) != [(ell, 1)] {
+        return Err("ℓ must be prime");
+    }
+    let mut seen = BTreeSet::new();
+    let mut subgroups = Vec::new();
+    for point in enumerate_points(curve) {
+        if point.is_infinity() || seen.contains(&point) || !curve.mul_u64(&point, ell).is_infinity() {
+            continue;
+        }
+        let subgroup: Vec<Point> = (1..ell).map(|k| curve.mul_u64(&point, k)).collect();
+        seen.extend(subgroup.iter().cloned());
+        subgroups.push(subgroup);
+    }
+    Ok(subgroups)
+}
+
+/// One term of Vélu's sums
+#[derive(Debug, Clone)]
+struct VeluTerm {
+    x: BigUint,
+    y: BigUint,
+    gx: BigUint,
+    gy: BigUint,
+    v: BigUint,
+    u: BigUint,
+}
+
+#[derive(Debug, Clone)]
+pub struct Isogeny {
+    pub domain: Curve,
+    pub codomain: Curve,
+    /// The kernel without O
+    pub kernel: Vec<Point>,
+    terms: Vec<VeluTerm>,
+}
+
+impl Isogeny {
+    /// The isogeny with kernel generated by `generator`, of degree its order
+    pub fn from_kernel(curve: &Curve, generator: &Point) -> Result<Self, &'static str> {
+        if !curve.contains(generator) || generator.is_infinity() {
+            return Err("kernel generator must be a finite point on the curve");
+        }
+        let mut kernel = vec![generator.clone()];
+        loop {
+            let next = curve.add(kernel.last().unwrap(), generator);
+            if next.is_infinity() {
+                break;
+            }
+            kernel.push(next);
+        }
+
+        let mut terms: Vec<VeluTerm> = Vec::new();
+        for q in &kernel {
+            let Point::Affine { x, y } = q else { unreachable!("O is not in the list") };
+            // One of Q, -Q: with x listed already, only -Q can be left
+            if terms.iter().any(|t| &t.x == x) {
+                continue;
+            }
+            let gx = curve.fadd(&curve.fmul(&BigUint::from(3u32), &curve.fmul(x, x)), &curve.a);
+            let gy = curve.fneg(&curve.fadd(y, y));
+            let v = if y.is_zero() { gx.clone() } else { curve.fadd(&gx, &gx) };
+            let u = curve.fmul(&gy, &gy);
+            terms.push(VeluTerm { x: x.clone(), y: y.clone(), gx, gy, v, u });
+        }
+
+        let v = terms.iter().fold(BigUint::zero(), |acc, t| curve.fadd(&acc, &t.v));
+        let w = terms
+            .iter()
+            .fold(BigUint::zero(), |acc, t| curve.fadd(&acc, &curve.fadd(&t.u, &curve.fmul(&t.x, &t.v))));
+        let codomain = Curve::new(
+            curve.p.clone(),
+            curve.fsub(&curve.a, &curve.fmul(&BigUint::from(5u32), &v)),
+            curve.fsub(&curve.b, &curve.fmul(&BigUint::from(7u32), &w)),
+        );
+        Ok(Self { domain: curve.clone(), codomain, kernel, terms })
+    }
+
+    pub fn degree(&self) -> u64 {
+        self.kernel.len() as u64 + 1
+    }
+
+    /// φ(P), with the kernel going to O
+    pub fn map(&self, point: &Point) -> Point {
+        let Point::Affine { x, y } = point else {
+            return Point::Infinity;
+        };
+        if self.kernel.contains(point) {
+            return Point::Infinity;
+        }
+        let c = &self.domain;
+        let (mut big_x, mut big_y) = (x.clone(), y.clone());
+        for t in &self.terms {
+            let d = c.finv(&c.fsub(x, &t.x));
+            let d2 = c.fmul(&d, &d);
+            let d3 = c.fmul(&d2, &d);
+            big_x = c.fadd(&big_x, &c.fadd(&c.fmul(&t.v, &d), &c.fmul(&t.u, &d2)));
+            let two_y = c.fadd(y, y);
+            let correction = c.fsub(
+                &c.fadd(&c.fmul(&c.fmul(&t.u, &two_y), &d3), &c.fmul(&c.fmul(&t.v, &c.fsub(y, &t.y)), &d2)),
+                &c.fmul(&c.fmul(&t.gx, &t.gy), &d2),
+            );
+            big_y = c.fsub(&big_y, &correction);
+        }
+        Point::Affine { x: big_x, y: big_y }
+    }
+}
+
+/// The ℓ-isogeny graph reachable from `curve` through rational kernels:
+/// j-invariant -> j-invariants of its ℓ-isogenous neighbours (with multiplicity)
+pub fn isogeny_graph(curve: &Curve, ell: u64) -> Result<BTreeMap<BigUint, Vec<BigUint>>, &'static str> {
+    let mut graph = BTreeMap::new();
+    let mut queue = VecDeque::from([curve.clone()]);
+    while let Some(current) = queue.pop_front() {
+        let j = j_invariant(&current);
+        if graph.contains_key(&j) {
+            continue;
+        }
+        let mut neighbours = Vec::new();
+        for kernel in kernel_subgroups(&current, ell)? {
+            let isogeny = Isogeny::from_kernel(&current, &kernel[0])?;
+            neighbours.push(j_invariant(&isogeny.codomain));
+            queue.push_back(isogeny.codomain);
+        }
+        graph.insert(j, neighbours);
+    }
+    Ok(graph)
+}
+
+/// A path of `steps` ℓ-isogenies, each time through the `choice(i)`-th kernel mod
+/// the number available. Returns the curves visited, starting with `curve`.
+pub fn walk(curve: &Curve, ell: u64, steps: usize, choice: impl Fn(usize) -> usize) -> Result<Vec<Curve>, &'static str> {
+    let mut path = vec![curve.clone()];
+    for i in 0..steps {
+        let current = path.last().unwrap();
+        let kernels = kernel_subgroups(current, ell)?;
+        if kernels.is_empty() {
+            break;
+        }
+        let kernel = &kernels[choice(i) % kernels.len()];
+        let next = Isogeny::from_kernel(current, &kernel[0]).expect("kernel points are on the curve").codomain;
+        path.push(next);
+    }
+    Ok(path)
+}
+
+pub fn entry_point() {
+    println!("--- ISOGENIES ---");
+    let curve = Curve::f211();
+    for kernel in kernel_subgroups(&curve, 3).unwrap() {
+        let isogeny = Isogeny::from_kernel(&curve, &kernel[0]).unwrap();
+        println!(
+            "kernel {:?}: y^2 = x^3 + {} x + {} (j = {}) -> y^2 = x^3 + {} x + {} (j = {})",
+            kernel,
+            curve.a,
+            curve.b,
+            j_invariant(&curve),
+            isogeny.codomain.a,
+            isogeny.codomain.b,
+            j_invariant(&isogeny.codomain)
+        );
+    }
+    let graph = isogeny_graph(&Curve::small(101, 2, 3), 2).unwrap();
+    println!("2-isogeny graph from y^2 = x^3 + 2x + 3 over F_101:");
+    for (j, neighbours) in &graph {
+        let neighbours: Vec<String> = neighbours.iter().map(|n| n.to_string()).collect();
+        println!("  {j} -> {}", neighbours.join(", "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elliptic_point::group_structure::count_points;
+
+    fn check_isogeny(isogeny: &Isogeny) {
+        let (e, e2) = (&isogeny.domain, &isogeny.codomain);
+        assert!(!e2.is_singular());
+        // Same number of points on both sides
+        assert_eq!(count_points(e), count_points(e2));
+        let points = enumerate_points(e);
+        let images: Vec<Point> = points.iter().map(|p| isogeny.map(p)).collect();
+        for image in &images {
+            assert!(e2.contains(image), "{image:?} is not on the codomain");
+        }
+        // Kernel size = degree: exactly deg points map to O
+        let zeros = images.iter().filter(|p| p.is_infinity()).count() as u64;
+        assert_eq!(zeros, isogeny.degree());
+        // A homomorphism
+        for (i, p) in points.iter().enumerate().step_by(7) {
+            for (j, q) in points.iter().enumerate().step_by(11) {
+                assert_eq!(isogeny.map(&e.add(p, q)), e2.add(&images[i], &images[j]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_j_invariant() {
+        // a = 0 always gives j = 0, b = 0 gives 1728
+        assert!(j_invariant(&Curve::f211()).is_zero());
+        assert_eq!(j_invariant(&Curve::small(101, 3, 0)), BigUint::from(1728u32 % 101));
+        // Isomorphic curves (x, y) -> (u^2 x, u^3 y) share j
+        let (a, b, u2) = (7i64, 12i64, 4i64);
+        let e = Curve::small(101, a, b);
+        let twisted = Curve::small(101, a * u2 * u2, b * u2 * u2 * u2);
+        assert_eq!(j_invariant(&e), j_invariant(&twisted));
+    }
+
+    #[test]
+    fn test_velu_3_isogeny_on_f211() {
+        // 183 = 3 * 61 points, the 3-torsion {O, (0, 2), (0, 209)} is the only kernel
+        let curve = Curve::f211();
+        let kernels = kernel_subgroups(&curve, 3).unwrap();
+        assert_eq!(kernels, vec![vec![Point::new(0, 2), Point::new(0, 209)]]);
+        let isogeny = Isogeny::from_kernel(&curve, &kernels[0][0]).unwrap();
+        assert_eq!(isogeny.degree(), 3);
+        check_isogeny(&isogeny);
+    }
+
+    #[test]
+    fn test_velu_small_degrees() {
+        // y^2 = x^3 + 2x + 3 over F_101 has 96 = 2^5 * 3 points
+        let curve = Curve::small(101, 2, 3);
+        let order = count_points(&curve);
+        let mut degrees = Vec::new();
+        for (ell, _) in factorize(order) {
+            for kernel in kernel_subgroups(&curve, ell).unwrap() {
+                let isogeny = Isogeny::from_kernel(&curve, &kernel[0]).unwrap();
+                check_isogeny(&isogeny);
+                degrees.push(isogeny.degree());
+            }
+        }
+        assert!(degrees.contains(&2) && degrees.contains(&3));
+        assert!(Isogeny::from_kernel(&curve, &Point::Infinity).is_err());
+    }
+
+    #[test]
+    fn test_isogeny_graph() {
+        let curve = Curve::small(101, 2, 3);
+        let order = count_points(&curve);
+        let ell = 2;
+        let graph = isogeny_graph(&curve, ell).unwrap();
+        assert!(graph.contains_key(&j_invariant(&curve)));
+        // Every edge lands on a vertex of the graph
+        for neighbours in graph.values() {
+            for j in neighbours {
+                assert!(graph.contains_key(j));
+            }
+        }
+        let path = walk(&curve, ell, 5, |i| i).unwrap();
+        assert!(path.len() > 1);
+        for c in &path {
+            assert_eq!(count_points(c), order);
+        }
+        assert!(isogeny_graph(&curve, 4).is_err());
+        // 4Q = O also holds for points of order 2, which would pass as a 4-isogeny kernel
+        assert!(kernel_subgroups(&curve, 4).is_err());
+        assert!(walk(&curve, 4, 1, |i| i).is_err());
+    }
+}
@@ -1,2 +1,14 @@
 pub mod ec_point;
-pub mod ec_projective;
\ No newline at end of file
+pub mod ec_projective;
+pub mod curve;
+pub mod group_structure;
+pub mod audit;
+pub mod sec1;
+pub mod hash_to_curve;
+pub mod field;
+pub mod montgomery;
+pub mod edwards;
+pub mod fp2;
+pub mod pairing;
+pub mod glv;
+pub mod isogeny;
@@ -0,0 +1,378 @@
+//! Montgomery curves: B v^2 = u^3 + A u^2 + u
+//!
+//! Every Montgomery curve is birationally equivalent to a short Weierstrass curve and
+//! to a twisted Edwards curve (see `edwards`), so the three forms describe the same
+//! group with different formulas. Montgomery's draw is the x-only ladder: kP can be
+//! computed from u(P) alone, with the same two operations for every bit of k. That
+//! is X25519.
+//!
+//! Points reuse `curve::Point`, Infinity being the neutral element here too.
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use super::curve::{Curve, Point};
+use super::edwards::{EdwardsCurve, EdwardsPoint};
+use super::field::PrimeField;
+
+/// u = 9, the Curve25519 base point
+pub const X25519_BASEPOINT: [u8; 32] = {
+    let mut u = [0u8; 32];
+    u[0] = 9;
+    u
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MontgomeryCurve {
+    pub field: PrimeField,
+    pub a: BigUint,
+    pub b: BigUint,
+}
+
+impl MontgomeryCurve {
+    pub fn new(p: BigUint, a: BigUint, b: BigUint) -> Self {
+        let a = a % &p;
+        let b = b % &p;
+        Self { field: PrimeField::new(p), a, b }
+    }
+
+    pub fn small(p: u64, a: i64, b: i64) -> Self {
+        let field = PrimeField::new(BigUint::from(p));
+        Self::new(field.p.clone(), field.reduce_i64(a), field.reduce_i64(b))
+    }
+
+    /// Curve25519: v^2 = u^3 + 486662 u^2 + u over 2^255 - 19
+    pub fn curve25519() -> Self {
+        let p = (BigUint::from(1u32) << 255) - 19u32;
+        Self::new(p, BigUint::from(486662u32), BigUint::from(1u32))
+    }
+
+    /// B(A^2 - 4) != 0
+    pub fn is_singular(&self) -> bool {
+        let f = &self.field;
+        self.b.is_zero() || f.sub(&f.square(&self.a), &BigUint::from(4u32)).is_zero()
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        let f = &self.field;
+        match point {
+            Point::Infinity => true,
+            Point::Affine { x: u, y: v } => {
+                let u2 = f.square(u);
+                let rhs = f.add(&f.add(&f.mul(&u2, u), &f.mul(&self.a, &u2)), u);
+                u < &f.p && v < &f.p && f.mul(&self.b, &f.square(v)) == rhs
+            }
+        }
+    }
+
+    pub fn neg(&self, point: &Point) -> Point {
+        match point {
+            Point::Infinity => Point::Infinity,
+            Point::Affine { x, y } => Point::Affine { x: x.clone(), y: self.field.neg(y) },
+        }
+    }
+
+    /// Chord and tangent again, only the slope and x3 pick up A and B:
+    ///   x3 = B s^2 - A - x1 - x2,   y3 = s (x1 - x3) - y1
+    pub fn add(&self, p1: &Point, p2: &Point) -> Point {
+        let f = &self.field;
+        match (p1, p2) {
+            (Point::Infinity, _) => p2.clone(),
+            (_, Point::Infinity) => p1.clone(),
+            (Point::Affine { x: x1, y: y1 }, Point::Affine { x: x2, y: y2 }) => {
+                let s = if x1 == x2 {
+                    if y1 != y2 || y1.is_zero() {
+                        return Point::Infinity;
+                    }
+                    // s = (3x1^2 + 2A x1 + 1) / 2B y1
+                    let num = f.add(
+                        &f.add(&f.mul(&BigUint::from(3u32), &f.square(x1)), &f.mul(&f.add(&self.a, &self.a), x1)),
+                        &BigUint::from(1u32),
+                    );
+                    f.div(&num, &f.mul(&f.add(&self.b, &self.b), y1))
+                } else {
+                    f.div(&f.sub(y2, y1), &f.sub(x2, x1))
+                };
+                let x3 = f.sub(&f.sub(&f.sub(&f.mul(&self.b, &f.square(&s)), &self.a), x1), x2);
+                let y3 = f.sub(&f.mul(&s, &f.sub(x1, &x3)), y1);
+                Point::Affine { x: x3, y: y3 }
+            }
+        }
+    }
+
+    pub fn mul(&self, point: &Point, scalar: &BigUint) -> Point {
+        let mut acc = Point::Infinity;
+        for i in (0..scalar.bits()).rev() {
+            acc = self.add(&acc, &acc);
+            if scalar.bit(i) {
+                acc = self.add(&acc, point);
+            }
+        }
+        acc
+    }
+
+    /// u(kP) from u(P) alone (RFC 7748 section 5)
+    ///
+    /// Keeps (X2:Z2) = u(mP) and (X3:Z3) = u((m+1)P). Each bit of k either doubles the
+    /// first and adds the pair, or the same with the roles swapped, so both branches
+    /// cost the same. The difference of the pair is always P, which is what makes
+    /// the differential addition possible without v. Infinity comes out as u = 0.
+    pub fn ladder(&self, u: &BigUint, k: &BigUint) -> BigUint {
+        let f = &self.field;
+        let a24 = f.div(&f.sub(&self.a, &BigUint::from(2u32)), &BigUint::from(4u32));
+        let x1 = u % &f.p;
+        let (mut x2, mut z2) = (BigUint::from(1u32), BigUint::zero());
+        let (mut x3, mut z3) = (x1.clone(), BigUint::from(1u32));
+        let mut swap = false;
+        for t in (0..k.bits()).rev() {
+            let bit = k.bit(t);
+            if swap ^ bit {
+                std::mem::swap(&mut x2, &mut x3);
+                std::mem::swap(&mut z2, &mut z3);
+            }
+            swap = bit;
+
+            let a = f.add(&x2, &z2);
+            let aa = f.square(&a);
+            let b = f.sub(&x2, &z2);
+            let bb = f.square(&b);
+            let e = f.sub(&aa, &bb);
+            let c = f.add(&x3, &z3);
+            let d = f.sub(&x3, &z3);
+            let da = f.mul(&d, &a);
+            let cb = f.mul(&c, &b);
+            x3 = f.square(&f.add(&da, &cb));
+            z3 = f.mul(&x1, &f.square(&f.sub(&da, &cb)));
+            x2 = f.mul(&aa, &bb);
+            z2 = f.mul(&e, &f.add(&aa, &f.mul(&a24, &e)));
+        }
+        if swap {
+            std::mem::swap(&mut x2, &mut x3);
+            std::mem::swap(&mut z2, &mut z3);
+        }
+        // z^(p-2) sends z = 0 to 0 instead of failing like an inverse would
+        f.mul(&x2, &f.pow(&z2, &(&f.p - 2u32)))
+    }
+
+    /// The short Weierstrass curve y^2 = x^3 + ax + b with
+    ///   a = (3 - A^2) / 3B^2,   b = (2A^3 - 9A) / 27B^3
+    pub fn to_weierstrass(&self) -> Curve {
+        let f = &self.field;
+        let (a, b) = (&self.a, &self.b);
+        let a2 = f.square(a);
+        let b2 = f.square(b);
+        let wa = f.div(&f.sub(&BigUint::from(3u32), &a2), &f.mul(&BigUint::from(3u32), &b2));
+        let num = f.sub(&f.mul(&BigUint::from(2u32), &f.mul(&a2, a)), &f.mul(&BigUint::from(9u32), a));
+        let wb = f.div(&num, &f.mul(&BigUint::from(27u32), &f.mul(&b2, b)));
+        Curve::new(f.p.clone(), wa, wb)
+    }
+
+    /// (u, v) -> (u/B + A/3B, v/B)
+    pub fn point_to_weierstrass(&self, point: &Point) -> Point {
+        let f = &self.field;
+        match point {
+            Point::Infinity => Point::Infinity,
+            Point::Affine { x: u, y: v } => {
+                let shift = f.div(&self.a, &f.mul(&BigUint::from(3u32), &self.b));
+                Point::Affine {
+                    x: f.add(&f.div(u, &self.b), &shift),
+                    y: f.div(v, &self.b),
+                }
+            }
+        }
+    }
+
+    /// (x, y) -> (B x - A/3, B y), the inverse of `point_to_weierstrass`
+    pub fn point_from_weierstrass(&self, point: &Point) -> Point {
+        let f = &self.field;
+        match point {
+            Point::Infinity => Point::Infinity,
+            Point::Affine { x, y } => {
+                let shift = f.div(&self.a, &BigUint::from(3u32));
+                Point::Affine {
+                    x: f.sub(&f.mul(&self.b, x), &shift),
+                    y: f.mul(&self.b, y),
+                }
+            }
+        }
+    }
+
+    /// a = (A + 2) / B,   d = (A - 2) / B
+    pub fn to_edwards(&self) -> EdwardsCurve {
+        let f = &self.field;
+        let two = BigUint::from(2u32);
+        EdwardsCurve::new(
+            f.p.clone(),
+            f.div(&f.add(&self.a, &two), &self.b),
+            f.div(&f.sub(&self.a, &two), &self.b),
+        )
+    }
+
+    /// (u, v) -> (u/v, (u - 1)/(u + 1))
+    ///
+    /// Infinity and (0, 0) go to the Edwards points (0, 1) and (0, -1). The other
+    /// points of order 2 and the ones with u = -1 land at infinity on the Edwards
+    /// side, which only happens when the Edwards curve is not complete.
+    pub fn point_to_edwards(&self, point: &Point) -> Option<EdwardsPoint> {
+        let f = &self.field;
+        let one = BigUint::from(1u32);
+        match point {
+            Point::Infinity => Some(EdwardsPoint::identity()),
+            Point::Affine { x: u, y: v } if u.is_zero() && v.is_zero() => Some(EdwardsPoint {
+                x: BigUint::zero(),
+                y: f.neg(&one),
+            }),
+            Point::Affine { x: u, y: v } => {
+                let u_plus_1 = f.add(u, &one);
+                if v.is_zero() || u_plus_1.is_zero() {
+                    return None;
+                }
+                Some(EdwardsPoint {
+                    x: f.div(u, v),
+                    y: f.div(&f.sub(u, &one), &u_plus_1),
+                })
+            }
+        }
+    }
+}
+
+/// RFC 7748 scalar clamping: clear the 3 cofactor bits, set bit 254
+fn clamp(scalar: &[u8; 32]) -> BigUint {
+    let mut k = *scalar;
+    k[0] &= 248;
+    k[31] &= 127;
+    k[31] |= 64;
+    BigUint::from_bytes_le(&k)
+}
+
+/// X25519(k, u): little-endian in and out, the top bit of u is ignored
+pub fn x25519(scalar: &[u8; 32], u: &[u8; 32]) -> [u8; 32] {
+    let curve = MontgomeryCurve::curve25519();
+    let mut u = *u;
+    u[31] &= 127;
+    let result = curve.ladder(&BigUint::from_bytes_le(&u), &clamp(scalar));
+    let mut out = [0u8; 32];
+    let bytes = result.to_bytes_le();
+    out[..bytes.len()].copy_from_slice(&bytes);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::{bytes_to_hex, hex_to_bytes};
+    use num_traits::ToPrimitive;
+
+    fn bytes32(hex: &str) -> [u8; 32] {
+        hex_to_bytes(hex).try_into().unwrap()
+    }
+
+    /// Every affine point of a toy curve, by brute force
+    fn points(curve: &MontgomeryCurve) -> Vec<Point> {
+        let p = curve.field.p.to_u64().unwrap();
+        let mut points = vec![Point::Infinity];
+        for u in 0..p {
+            for v in 0..p {
+                let point = Point::new(u, v);
+                if curve.contains(&point) {
+                    points.push(point);
+                }
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn test_x25519_rfc7748_vectors() {
+        // Section 5.2
+        let k = bytes32("a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4");
+        let u = bytes32("e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c");
+        assert_eq!(
+            bytes_to_hex(&x25519(&k, &u)),
+            "c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552"
+        );
+        // After one iteration of k = u = 9
+        assert_eq!(
+            bytes_to_hex(&x25519(&X25519_BASEPOINT, &X25519_BASEPOINT)),
+            "422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079"
+        );
+    }
+
+    #[test]
+    fn test_x25519_diffie_hellman() {
+        // Section 6.1
+        let alice = bytes32("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let bob = bytes32("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+        let alice_public = x25519(&alice, &X25519_BASEPOINT);
+        let bob_public = x25519(&bob, &X25519_BASEPOINT);
+        assert_eq!(
+            bytes_to_hex(&alice_public),
+            "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a"
+        );
+        let shared = x25519(&alice, &bob_public);
+        assert_eq!(shared, x25519(&bob, &alice_public));
+        assert_eq!(
+            bytes_to_hex(&shared),
+            "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742"
+        );
+    }
+
+    #[test]
+    fn test_ladder_matches_group_law() {
+        // v^2 = u^3 + 4u^2 + u over F101, 96 points
+        let curve = MontgomeryCurve::small(101, 4, 1);
+        let points = points(&curve);
+        assert_eq!(points.len(), 96);
+        for point in &points {
+            for k in [1u32, 2, 3, 7, 48, 95, 96] {
+                let expected = match curve.mul(point, &BigUint::from(k)) {
+                    Point::Infinity => BigUint::zero(),
+                    Point::Affine { x, .. } => x,
+                };
+                assert_eq!(curve.ladder(&point_u(point), &BigUint::from(k)), expected);
+            }
+            assert!(curve.mul(point, &BigUint::from(96u32)).is_infinity());
+        }
+    }
+
+    fn point_u(point: &Point) -> BigUint {
+        match point {
+            Point::Infinity => BigUint::zero(),
+            Point::Affine { x, .. } => x.clone(),
+        }
+    }
+
+    #[test]
+    fn test_weierstrass_map_is_a_homomorphism() {
+        let curve = MontgomeryCurve::small(101, 4, 3);
+        let weierstrass = curve.to_weierstrass();
+        let points = points(&curve);
+        for p1 in points.iter().step_by(5) {
+            let w1 = curve.point_to_weierstrass(p1);
+            assert!(weierstrass.contains(&w1));
+            assert_eq!(&curve.point_from_weierstrass(&w1), p1);
+            for p2 in &points {
+                let w2 = curve.point_to_weierstrass(p2);
+                assert_eq!(curve.point_to_weierstrass(&curve.add(p1, p2)), weierstrass.add(&w1, &w2));
+            }
+        }
+    }
+
+    #[test]
+    fn test_curve25519_weierstrass_form() {
+        let curve = MontgomeryCurve::curve25519();
+        let weierstrass = curve.to_weierstrass();
+        let f = &curve.field;
+        // v for the base point u = 9
+        let rhs = f.add(&f.add(&BigUint::from(729u32), &f.mul(&curve.a, &BigUint::from(81u32))), &BigUint::from(9u32));
+        let g = Point::Affine { x: BigUint::from(9u32), y: f.sqrt(&rhs).unwrap() };
+        assert!(curve.contains(&g));
+        let w = curve.point_to_weierstrass(&g);
+        assert!(weierstrass.contains(&w));
+        let k = BigUint::from(0x1234_5678_9abc_def0u64);
+        assert_eq!(
+            curve.point_from_weierstrass(&weierstrass.mul(&w, &k)),
+            curve.mul(&g, &k)
+        );
+    }
+}
@@ -0,0 +1,313 @@
+//! Weil and Tate pairings on supersingular y^2 = x^3 + b, p ≡ 2 mod 3
+//!
+//! For p ≡ 2 mod 3 the curve has p + 1 points, so any prime r | p + 1 also divides
+//! p^2 - 1: the embedding degree is 2 and the pairings land in the r-th roots of
+//! unity of F_p2. Both take two independent r-torsion points, but E(F_p)[r] is
+//! cyclic. The distortion map φ(x, y) = (ω x, y) fixes that: it is an endomorphism
+//! sending E(F_p)[r] outside itself, and the modified pairing ê(P, Q) = e(P, φ(Q))
+//! is non-degenerate on a single cyclic group. That is the symmetric pairing the
+//! textbook constructions (BLS, tripartite DH, ...) are written with.
+//!
+//! Miller's algorithm computes f_{r,P}, the function with divisor r(P) - r(O), by
+//! double-and-add on P while multiplying in the line through T and S divided by the
+//! vertical line through T + S, evaluated at Q. Then
+//!   Weil:         e(P, Q) = (-1)^r f_{r,P}(Q) / f_{r,Q}(P)
+//!   reduced Tate: t(P, Q) = f_{r,P}(Q)^((p^2 - 1) / r)
+//!
+//! r = 3 never works here: the points of order 3 have x = 0, which φ fixes. That
+//! rules out `ec_point`'s F17 curve (18 points), p = 59 with r = 5 is the smallest
+//! useful one.
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use super::curve::{Curve, Point};
+use super::fp2::{Fp2, QuadraticField};
+use super::group_structure::factorize;
+
+/// A point of E(F_p2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fp2Point {
+    Infinity,
+    Affine { x: Fp2, y: Fp2 },
+}
+
+impl Fp2Point {
+    pub fn is_infinity(&self) -> bool {
+        matches!(self, Fp2Point::Infinity)
+    }
+}
+
+/// y^2 = x^3 + b over F_p with a prime r | p + 1, r > 3
+#[derive(Debug, Clone)]
+pub struct PairingCurve {
+    pub field: QuadraticField,
+    pub curve: Curve,
+    pub r: BigUint,
+}
+
+impl PairingCurve {
+    pub fn small(p: u64, b: i64, r: u64) -> Result<Self, &'static str> {
+        let field = QuadraticField::new(BigUint::from(p))?;
+        let curve = Curve::small(p, 0, b);
+        if curve.b.is_zero() {
+            return Err("b = 0 gives a singular curve");
+        }
+        if r <= 3 || factorize(r) != [(r, 1)] {
+            return Err("r must be a prime greater than 3");
+        }
+        if !(p + 1).is_multiple_of(r) {
+            return Err("r must divide the group order p + 1");
+        }
+        Ok(Self { field, curve, r: BigUint::from(r) })
+    }
+
+    /// The first point of order r, by x = 0, 1, 2, ... times the cofactor
+    pub fn generator(&self) -> Point {
+        let f = &self.field.base;
+        let cofactor = (&f.p + 1u32) / &self.r;
+        let mut x = BigUint::zero();
+        loop {
+            if let Some(y) = f.sqrt(&self.curve.rhs(&x)) {
+                let g = self.curve.mul(&Point::Affine { x: x.clone(), y }, &cofactor);
+                if !g.is_infinity() {
+                    return g;
+                }
+            }
+            x += 1u32;
+        }
+    }
+
+    pub fn embed(&self, point: &Point) -> Fp2Point {
+        match point {
+            Point::Infinity => Fp2Point::Infinity,
+            Point::Affine { x, y } => Fp2Point::Affine {
+                x: self.field.lift(x.clone()),
+                y: self.field.lift(y.clone()),
+            },
+        }
+    }
+
+    /// φ(x, y) = (ω x, y), which maps E(F_p) into E(F_p2) \ E(F_p) apart from x = 0
+    pub fn distort(&self, point: &Point) -> Fp2Point {
+        match self.embed(point) {
+            Fp2Point::Infinity => Fp2Point::Infinity,
+            Fp2Point::Affine { x, y } => Fp2Point::Affine { x: self.field.mul(&self.field.omega(), &x), y },
+        }
+    }
+
+    pub fn contains(&self, point: &Fp2Point) -> bool {
+        let k = &self.field;
+        match point {
+            Fp2Point::Infinity => true,
+            Fp2Point::Affine { x, y } => {
+                let rhs = k.add(&k.mul(&k.square(x), x), &k.lift(self.curve.b.clone()));
+                k.square(y) == rhs
+            }
+        }
+    }
+
+    pub fn neg(&self, point: &Fp2Point) -> Fp2Point {
+        match point {
+            Fp2Point::Infinity => Fp2Point::Infinity,
+            Fp2Point::Affine { x, y } => Fp2Point::Affine { x: x.clone(), y: self.field.neg(y) },
+        }
+    }
+
+    /// Slope of the chord through t and s (tangent when equal), None when vertical
+    fn slope(&self, t: (&Fp2, &Fp2), s: (&Fp2, &Fp2)) -> Option<Fp2> {
+        let k = &self.field;
+        if t.0 != s.0 {
+            return Some(k.div(&k.sub(s.1, t.1), &k.sub(s.0, t.0)));
+        }
+        if t.1 != s.1 || t.1.is_zero() {
+            return None;
+        }
+        let three_x2 = k.mul(&k.lift(BigUint::from(3u32)), &k.square(t.0));
+        Some(k.div(&three_x2, &k.add(t.1, t.1)))
+    }
+
+    pub fn add(&self, p1: &Fp2Point, p2: &Fp2Point) -> Fp2Point {
+        let (Fp2Point::Affine { x: x1, y: y1 }, Fp2Point::Affine { x: x2, y: y2 }) = (p1, p2) else {
+            return if p1.is_infinity() { p2.clone() } else { p1.clone() };
+        };
+        let k = &self.field;
+        let Some(lambda) = self.slope((x1, y1), (x2, y2)) else {
+            return Fp2Point::Infinity;
+        };
+        let x3 = k.sub(&k.sub(&k.square(&lambda), x1), x2);
+        let y3 = k.sub(&k.mul(&lambda, &k.sub(x1, &x3)), y1);
+        Fp2Point::Affine { x: x3, y: y3 }
+    }
+
+    pub fn mul(&self, point: &Fp2Point, scalar: &BigUint) -> Fp2Point {
+        let mut result = Fp2Point::Infinity;
+        for i in (0..scalar.bits()).rev() {
+            result = self.add(&result, &result);
+            if scalar.bit(i) {
+                result = self.add(&result, point);
+            }
+        }
+        result
+    }
+
+    /// (l_{T,S}(Q), v_{T+S}(Q), T + S) for affine T and S
+    fn line(&self, t: &Fp2Point, s: &Fp2Point, q: &Fp2Point) -> Result<(Fp2, Fp2, Fp2Point), &'static str> {
+        let (
+            Fp2Point::Affine { x: tx, y: ty },
+            Fp2Point::Affine { x: sx, y: sy },
+            Fp2Point::Affine { x: qx, y: qy },
+        ) = (t, s, q)
+        else {
+            return Err("Miller lines are only defined for affine points");
+        };
+        let k = &self.field;
+        let (num, den, sum) = match self.slope((tx, ty), (sx, sy)) {
+            // Vertical line x - x_T, and T + S = O has no vertical to divide by
+            None => (k.sub(qx, tx), k.one(), Fp2Point::Infinity),
+            Some(lambda) => {
+                let sum = self.add(t, s);
+                let Fp2Point::Affine { x: x3, .. } = &sum else {
+                    unreachable!("a non-vertical line has a third affine point");
+                };
+                let num = k.sub(&k.sub(qy, ty), &k.mul(&lambda, &k.sub(qx, tx)));
+                (num, k.sub(qx, x3), sum)
+            }
+        };
+        if num.is_zero() || den.is_zero() {
+            return Err("Q lies on a line of the Miller loop");
+        }
+        Ok((num, den, sum))
+    }
+
+    /// f_{r,P}(Q), numerator and denominator kept apart until the one inversion
+    pub fn miller(&self, p: &Fp2Point, q: &Fp2Point) -> Result<Fp2, &'static str> {
+        let k = &self.field;
+        let (mut num, mut den) = (k.one(), k.one());
+        let mut t = p.clone();
+        for i in (0..self.r.bits() - 1).rev() {
+            let (l, v, doubled) = self.line(&t, &t, q)?;
+            num = k.mul(&k.square(&num), &l);
+            den = k.mul(&k.square(&den), &v);
+            t = doubled;
+            if self.r.bit(i) {
+                let (l, v, added) = self.line(&t, p, q)?;
+                num = k.mul(&num, &l);
+                den = k.mul(&den, &v);
+                t = added;
+            }
+        }
+        debug_assert!(t.is_infinity(), "P must have order r");
+        Ok(k.div(&num, &den))
+    }
+
+    /// e_r(P, Q) for P, Q in E[r], alternating so e(P, P) = 1
+    pub fn weil(&self, p: &Fp2Point, q: &Fp2Point) -> Result<Fp2, &'static str> {
+        let k = &self.field;
+        if p.is_infinity() || q.is_infinity() || p == q {
+            return Ok(k.one());
+        }
+        let ratio = k.div(&self.miller(p, q)?, &self.miller(q, p)?);
+        // r is an odd prime
+        Ok(k.neg(&ratio))
+    }
+
+    /// f_{r,P}(Q)^((p^2 - 1) / r), which only depends on Q modulo rE
+    pub fn tate(&self, p: &Fp2Point, q: &Fp2Point) -> Result<Fp2, &'static str> {
+        let k = &self.field;
+        if p.is_infinity() || q.is_infinity() {
+            return Ok(k.one());
+        }
+        let exponent = (&k.base.p * &k.base.p - 1u32) / &self.r;
+        Ok(k.pow(&self.miller(p, q)?, &exponent))
+    }
+
+    /// ê(P, Q) = e(P, φ(Q)) on E(F_p)[r]
+    pub fn modified_weil(&self, p: &Point, q: &Point) -> Result<Fp2, &'static str> {
+        self.weil(&self.embed(p), &self.distort(q))
+    }
+
+    /// ê(P, Q) = t(P, φ(Q)) on E(F_p)[r]
+    pub fn modified_tate(&self, p: &Point, q: &Point) -> Result<Fp2, &'static str> {
+        self.tate(&self.embed(p), &self.distort(q))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curve_checks() {
+        assert!(PairingCurve::small(59, 1, 5).is_ok());
+        assert!(PairingCurve::small(61, 1, 31).is_err());
+        // ec_point's curve only has r = 3, which the distortion map cannot separate
+        assert!(PairingCurve::small(17, 1, 3).is_err());
+        assert!(PairingCurve::small(101, 1, 51).is_err());
+        assert!(PairingCurve::small(101, 1, 13).is_err());
+    }
+
+    #[test]
+    fn test_distortion_map() {
+        let pc = PairingCurve::small(101, 1, 17).unwrap();
+        let g = pc.generator();
+        assert!(pc.curve.mul(&g, &pc.r).is_infinity());
+        let phi = pc.distort(&g);
+        assert!(pc.contains(&phi));
+        assert!(pc.mul(&phi, &pc.r).is_infinity());
+        // φ is a group homomorphism that leaves E(F_p)
+        let g5 = pc.curve.mul_u64(&g, 5);
+        assert_eq!(pc.mul(&phi, &BigUint::from(5u32)), pc.distort(&g5));
+        assert_ne!(phi, pc.embed(&g));
+    }
+
+    #[test]
+    fn test_bilinear_and_non_degenerate() {
+        for (p, r) in [(59u64, 5u64), (101, 17), (1019, 17), (10007, 139)] {
+            let pc = PairingCurve::small(p, 1, r).unwrap();
+            let g = pc.generator();
+            let k = &pc.field;
+            for pairing in [PairingCurve::modified_weil, PairingCurve::modified_tate] {
+                let base = pairing(&pc, &g, &g).unwrap();
+                assert!(!base.is_one());
+                assert!(k.pow(&base, &pc.r).is_one());
+                for (a, b) in [(2u64, 3u64), (7, 11), (r - 1, 4)] {
+                    let pa = pc.curve.mul_u64(&g, a);
+                    let pb = pc.curve.mul_u64(&g, b);
+                    let expected = k.pow(&base, &BigUint::from(a * b));
+                    assert_eq!(pairing(&pc, &pa, &pb).unwrap(), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_weil_is_alternating() {
+        let pc = PairingCurve::small(1019, 1, 17).unwrap();
+        let k = &pc.field;
+        let p = pc.embed(&pc.generator());
+        let q = pc.distort(&pc.curve.mul_u64(&pc.generator(), 3));
+        assert!(pc.weil(&p, &p).unwrap().is_one());
+        let pq = pc.weil(&p, &q).unwrap();
+        assert!(k.mul(&pq, &pc.weil(&q, &p).unwrap()).is_one());
+        // Linear in the first argument: e(P + Q, Q) = e(P, Q) e(Q, Q)
+        let sum = pc.add(&p, &q);
+        assert_eq!(pc.weil(&sum, &q).unwrap(), pc.weil(&p, &q).unwrap());
+        // Without φ everything lives in one cyclic group, where e is trivial
+        assert!(pc.weil(&p, &pc.mul(&p, &BigUint::from(3u32))).unwrap().is_one());
+    }
+
+    #[test]
+    fn test_tate_ignores_multiples_of_r() {
+        // t(P, Q + rR) = t(P, Q): here R is any point of E(F_p), so rR lands in the
+        // cofactor part and the final exponentiation wipes out its contribution
+        let pc = PairingCurve::small(101, 1, 17).unwrap();
+        let g = pc.generator();
+        let phi = pc.distort(&g);
+        let other = pc.embed(&Point::new(0, 1));
+        let shifted = pc.add(&phi, &other);
+        let t1 = pc.tate(&pc.embed(&g), &phi).unwrap();
+        assert!(!t1.is_one());
+        assert_eq!(pc.tate(&pc.embed(&g), &shifted).unwrap(), t1);
+    }
+}
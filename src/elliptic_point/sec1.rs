@@ -0,0 +1,255 @@
+//! SEC1 point encoding (SEC 1 v2, section 2.3.3 / 2.3.4)
+//!
+//! | Prefix | Layout        | Meaning                        |
+//! |--------|---------------|--------------------------------|
+//! | 0x00   | -             | point at infinity              |
+//! | 0x02   | x             | compressed, y is even          |
+//! | 0x03   | x             | compressed, y is odd           |
+//! | 0x04   | x ‖ y         | uncompressed                   |
+//!
+//! Coordinates are big-endian and left-padded to the byte length of p.
+//! Decoding never trusts its input: the point must satisfy the curve equation and,
+//! when asked, lie in the prime order subgroup.
+
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+
+use super::curve::{Curve, Point};
+use super::ec_point::ECPoint;
+use super::ec_projective::{AffinePoint, ProjectivePoint};
+use super::group_structure::prime_order_subgroup;
+use crate::modular_sqrt::{sqrt_mod_p3mod4, tonelli_shanks, tonelli_shanks_big};
+
+/// Byte length of a field element
+pub fn field_len(curve: &Curve) -> usize {
+    curve.p.bits().div_ceil(8) as usize
+}
+
+fn to_fixed_bytes(value: &BigUint, len: usize) -> Vec<u8> {
+    let bytes = value.to_bytes_be();
+    let mut out = vec![0u8; len - bytes.len()];
+    out.extend_from_slice(&bytes);
+    out
+}
+
+/// SEC1 encoding, compressed (1 + len bytes) or uncompressed (1 + 2*len bytes)
+/// Coordinates are reduced mod p first, so they always fit in `field_len` bytes
+pub fn encode(curve: &Curve, point: &Point, compressed: bool) -> Vec<u8> {
+    match point {
+        Point::Infinity => vec![0x00],
+        Point::Affine { x, y } => {
+            let len = field_len(curve);
+            let (x, y) = (x % &curve.p, y % &curve.p);
+            if compressed {
+                let prefix = if y.bit(0) { 0x03 } else { 0x02 };
+                let mut out = vec![prefix];
+                out.extend(to_fixed_bytes(&x, len));
+                out
+            } else {
+                let mut out = vec![0x04];
+                out.extend(to_fixed_bytes(&x, len));
+                out.extend(to_fixed_bytes(&y, len));
+                out
+            }
+        }
+    }
+}
+
+/// Recover y from x and the parity bit
+///
+/// y^2 = x^3 + ax + b, so y is a modular square root of the rhs.
+/// Small p goes through the u128 routines (the direct formula for p = 3 mod 4,
+/// Tonelli-Shanks otherwise), real curves through the BigUint Tonelli-Shanks.
+pub fn decompress(curve: &Curve, x: &BigUint, y_is_odd: bool) -> Result<Point, &'static str> {
+    if x >= &curve.p {
+        return Err("x coordinate is not a field element");
+    }
+    let rhs = curve.rhs(x);
+    let root = match (curve.p.to_u64(), rhs.to_u64()) {
+        (Some(p), Some(rhs)) => {
+            let (p, rhs) = (p as u128, rhs as u128);
+            let root = if rhs == 0 {
+                Some(0)
+            } else if p % 4 == 3 {
+                sqrt_mod_p3mod4(rhs, p)
+            } else {
+                tonelli_shanks(rhs, p)
+            };
+            root.map(BigUint::from)
+        }
+        _ => tonelli_shanks_big(&rhs, &curve.p),
+    };
+    let root = root.ok_or("x is not on the curve (rhs is not a square)")?;
+    if root.is_zero() && y_is_odd {
+        return Err("y = 0 has no odd square root");
+    }
+    let y = if root.bit(0) == y_is_odd {
+        root
+    } else {
+        curve.fneg(&root)
+    };
+    Ok(Point::Affine { x: x.clone(), y })
+}
+
+/// Parse a SEC1 encoding and check the point is on the curve
+pub fn decode(curve: &Curve, bytes: &[u8]) -> Result<Point, &'static str> {
+    let len = field_len(curve);
+    let (&prefix, body) = bytes.split_first().ok_or("empty encoding")?;
+    let point = match prefix {
+        0x00 if body.is_empty() => Point::Infinity,
+        0x02 | 0x03 if body.len() == len => {
+            decompress(curve, &BigUint::from_bytes_be(body), prefix == 0x03)?
+        }
+        0x04 if body.len() == 2 * len => Point::Affine {
+            x: BigUint::from_bytes_be(&body[..len]),
+            y: BigUint::from_bytes_be(&body[len..]),
+        },
+        0x00 | 0x02 | 0x03 | 0x04 => return Err("wrong encoding length"),
+        _ => return Err("unknown SEC1 prefix"),
+    };
+    if !curve.contains(&point) {
+        return Err("point is not on the curve");
+    }
+    Ok(point)
+}
+
+/// `decode` plus a check that q * P = Infinity for the subgroup order q
+pub fn decode_in_subgroup(
+    curve: &Curve,
+    bytes: &[u8],
+    subgroup_order: &BigUint,
+) -> Result<Point, &'static str> {
+    let point = decode(curve, bytes)?;
+    if !curve.mul(&point, subgroup_order).is_infinity() {
+        return Err("point is not in the prime order subgroup");
+    }
+    Ok(point)
+}
+
+/// The teaching curves are tiny, so their subgroup order is just counted
+fn toy_subgroup_order(curve: &Curve) -> BigUint {
+    BigUint::from(prime_order_subgroup(curve).map(|s| s.order).unwrap_or(1))
+}
+
+impl ECPoint {
+    pub fn to_sec1(self, compressed: bool) -> Vec<u8> {
+        encode(&Curve::f17(), &self.into(), compressed)
+    }
+
+    /// Decode, rejecting points off y^2 = x^3 + 1 or outside the order 3 subgroup
+    pub fn from_sec1(bytes: &[u8]) -> Result<Self, &'static str> {
+        let curve = Curve::f17();
+        let q = toy_subgroup_order(&curve);
+        decode_in_subgroup(&curve, bytes, &q).map(Into::into)
+    }
+}
+
+impl AffinePoint {
+    pub fn to_sec1(self, compressed: bool) -> Vec<u8> {
+        encode(&Curve::f211(), &self.into(), compressed)
+    }
+
+    /// Decode, rejecting points off the F211 curve or outside its prime order subgroup
+    pub fn from_sec1(bytes: &[u8]) -> Result<Self, &'static str> {
+        let curve = Curve::f211();
+        let q = toy_subgroup_order(&curve);
+        decode_in_subgroup(&curve, bytes, &q).map(Into::into)
+    }
+}
+
+impl ProjectivePoint {
+    /// Normalizes to affine first, so every representative of a point encodes the same
+    pub fn to_sec1(self, compressed: bool) -> Vec<u8> {
+        AffinePoint::from(self).to_sec1(compressed)
+    }
+
+    pub fn from_sec1(bytes: &[u8]) -> Result<Self, &'static str> {
+        AffinePoint::from_sec1(bytes).map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elliptic_point::curve::DomainParams;
+    use crate::elliptic_point::ec_projective::FieldElement;
+    use crate::elliptic_point::group_structure::enumerate_points;
+
+    #[test]
+    fn test_roundtrip_all_points() {
+        // p = 17 is 1 mod 4 (Tonelli-Shanks), p = 211 is 3 mod 4 (direct formula)
+        for curve in [Curve::f17(), Curve::f211()] {
+            for point in enumerate_points(&curve) {
+                for compressed in [true, false] {
+                    let bytes = encode(&curve, &point, compressed);
+                    assert_eq!(decode(&curve, &bytes), Ok(point.clone()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_secp256k1_compressed() {
+        let domain = DomainParams::secp256k1();
+        let point = domain.mul_g(&BigUint::from(1234567u32));
+        let bytes = encode(&domain.curve, &point, true);
+        assert_eq!(bytes.len(), 33);
+        assert_eq!(decode_in_subgroup(&domain.curve, &bytes, &domain.n), Ok(point));
+    }
+
+    #[test]
+    fn test_encoding_layout() {
+        let curve = Curve::f17();
+        assert_eq!(encode(&curve, &Point::Infinity, true), vec![0x00]);
+        assert_eq!(encode(&curve, &Point::new(1, 6), true), vec![0x02, 1]);
+        assert_eq!(encode(&curve, &Point::new(1, 11), true), vec![0x03, 1]);
+        assert_eq!(encode(&curve, &Point::new(1, 6), false), vec![0x04, 1, 6]);
+        // Unreduced coordinates used to underflow the padding, they encode as their residues
+        assert_eq!(encode(&curve, &Point::new(1, 6 + 17 * 300), false), vec![0x04, 1, 6]);
+        assert_eq!(encode(&curve, &Point::new(18, 28), true), vec![0x03, 1]);
+    }
+
+    #[test]
+    fn test_rejects_invalid() {
+        let curve = Curve::f17();
+        assert!(decode(&curve, &[]).is_err());
+        assert!(decode(&curve, &[0x05, 1]).is_err());
+        assert!(decode(&curve, &[0x04, 1]).is_err());
+        // (1, 7) is not on y^2 = x^3 + 1
+        assert_eq!(decode(&curve, &[0x04, 1, 7]), Err("point is not on the curve"));
+        // x = 17 is not a field element
+        assert!(decode(&curve, &[0x02, 17]).is_err());
+        // (1, 6) is on the curve but has order 9, not 3
+        assert!(decode_in_subgroup(&curve, &[0x02, 1], &BigUint::from(3u32)).is_err());
+        assert!(ECPoint::from_sec1(&[0x02, 1]).is_err());
+    }
+
+    #[test]
+    fn test_typed_points() {
+        let p = ECPoint::new(0, 1);
+        assert_eq!(ECPoint::from_sec1(&p.to_sec1(true)), Ok(p));
+        assert_eq!(ECPoint::from_sec1(&[0x00]), Ok(ECPoint::Infinity));
+
+        // #E = 183 = 3 * 61 and (0, 2) only has order 3
+        let small = AffinePoint::Point {
+            x: FieldElement::new(0),
+            y: FieldElement::new(2),
+        };
+        assert!(AffinePoint::from_sec1(&small.to_sec1(false)).is_err());
+
+        let curve = Curve::f211();
+        let g: AffinePoint = prime_order_subgroup(&curve).unwrap().generators[0].clone().into();
+        assert_eq!(AffinePoint::from_sec1(&g.to_sec1(false)), Ok(g));
+
+        // Any projective representative of g encodes the same way
+        let (x, y) = match g {
+            AffinePoint::Point { x, y } => (x, y),
+            AffinePoint::Infinity => unreachable!(),
+        };
+        let z = FieldElement::new(5);
+        let scaled = ProjectivePoint { x: x * z, y: y * z, z };
+        assert_eq!(scaled.to_sec1(true), g.to_sec1(true));
+        let decoded = ProjectivePoint::from_sec1(&scaled.to_sec1(true)).unwrap();
+        assert_eq!(AffinePoint::from(decoded), g);
+    }
+}
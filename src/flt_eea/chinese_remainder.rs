@@ -0,0 +1,38 @@
+use super::eea_gcd::eea_gcd;
+
+/// Chinese Remainder Theorem: the unique x mod M = m1 * m2 * ... with x = r_i mod m_i
+///
+/// Folds the congruences in one at a time. With x = r1 mod m1 and x = r2 mod m2,
+/// eea_gcd gives u, v with u m1 + v m2 = 1, and
+///   x = r1 + m1 u (r2 - r1)  mod m1 m2
+/// Returns None if two moduli share a factor.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut x = 0i128;
+    let mut m = 1i128;
+    for &(r, modulus) in congruences {
+        let (u, _, g) = eea_gcd(m as i64, modulus);
+        if g != 1 {
+            return None;
+        }
+        let modulus = modulus as i128;
+        let new_m = m * modulus;
+        let step = (r as i128 - x).rem_euclid(modulus) * (u as i128).rem_euclid(modulus) % modulus;
+        x = (x + m * step).rem_euclid(new_m);
+        m = new_m;
+    }
+    Some((x as i64, m as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crt() {
+        // Sunzi: x = 2 mod 3, 3 mod 5, 2 mod 7  ->  23 mod 105
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(5, 18)]), Some((5, 18)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), None);
+    }
+}
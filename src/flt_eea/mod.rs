@@ -1,3 +1,4 @@
 pub mod extended_euclidean_algo;
 pub mod fermat_little_theorem;
-pub mod eea_gcd;
\ No newline at end of file
+pub mod eea_gcd;
+pub mod chinese_remainder;
@@ -0,0 +1,260 @@
+//! Invalid-curve and small-subgroup attacks on a naive static-key ECDH
+//!
+//! The toy point types trust their inputs: `ECPoint::new` never checks the curve
+//! equation, and neither the affine nor the projective formulas ever touch b. Feed
+//! them a point from y^2 = x^3 + b' and they compute on that curve instead, so a
+//! server doing ECDH with a static secret d hands out d * Q on a curve of the
+//! attacker's choosing.
+//!
+//! The attack: pick curves (same p and a, any b') whose order has small prime
+//! factors q, send a point Q of order q, and brute force which j * Q matches the
+//! derived key. That leaks d mod q. Enough of them and the CRT gives d.
+//! Points of small order on the real curve (when it has a cofactor) leak d mod q
+//! the same way, which is the small-subgroup attack.
+//!
+//! The defence is two checks: decode peer points with validation (`sec1::decode`
+//! rejects anything off the curve) and clear the cofactor, so a small-order point
+//! becomes the point at infinity and the handshake aborts.
+
+use num_bigint::BigUint;
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+use crate::elliptic_point::curve::{Curve, DomainParams, Point};
+use crate::elliptic_point::ec_point::ECPoint;
+use crate::elliptic_point::ec_projective::{AffinePoint, ProjectivePoint};
+use crate::elliptic_point::group_structure::{count_points, enumerate_points, factorize};
+use crate::elliptic_point::sec1;
+use crate::flt_eea::chinese_remainder::crt;
+
+/// Shared key = SHA-256 of the uncompressed SEC1 shared point
+pub fn derive_key(curve: &Curve, shared: &Point) -> [u8; 32] {
+    Sha256::digest(sec1::encode(curve, shared, false)).into()
+}
+
+/// Static-key ECDH over F17 with `ECPoint` and no validation at all
+pub struct NaiveAffineServer {
+    secret: u64,
+}
+
+/// Static-key ECDH over F211 with `ProjectivePoint` and no validation at all
+pub struct NaiveProjectiveServer {
+    secret: u64,
+}
+
+/// Double-and-add on `ECPoint`, which only has `add`
+fn ecpoint_mul(point: ECPoint, mut scalar: u64) -> ECPoint {
+    let mut result = ECPoint::Infinity;
+    let mut addend = point;
+    while scalar > 0 {
+        if scalar & 1 == 1 {
+            result = result.add(addend);
+        }
+        addend = addend.add(addend);
+        scalar >>= 1;
+    }
+    result
+}
+
+impl NaiveAffineServer {
+    pub fn new(secret: u64) -> Self {
+        Self { secret }
+    }
+
+    pub fn handshake(&self, peer: &Point) -> [u8; 32] {
+        let shared = ecpoint_mul(ECPoint::from(peer.clone()), self.secret);
+        derive_key(&Curve::f17(), &shared.into())
+    }
+}
+
+impl NaiveProjectiveServer {
+    pub fn new(secret: u64) -> Self {
+        Self { secret }
+    }
+
+    pub fn handshake(&self, peer: &Point) -> [u8; 32] {
+        let peer = ProjectivePoint::from(AffinePoint::from(peer.clone()));
+        let shared = peer.scalar_mul(self.secret);
+        derive_key(&Curve::f211(), &shared.into())
+    }
+}
+
+/// The same handshake done properly: SEC1-decoded peer point and cofactor clearing
+pub struct ValidatingServer {
+    domain: DomainParams,
+    secret: BigUint,
+}
+
+impl ValidatingServer {
+    pub fn new(domain: DomainParams, secret: BigUint) -> Self {
+        Self { domain, secret }
+    }
+
+    pub fn public_key(&self) -> Point {
+        self.domain.mul_g(&self.secret)
+    }
+
+    /// Shared point = (h * d) * Q, refused when Q is off the curve or of small order
+    pub fn handshake(&self, peer: &[u8]) -> Result<[u8; 32], &'static str> {
+        let curve = &self.domain.curve;
+        let peer = sec1::decode(curve, peer)?;
+        let scalar = &self.secret * &self.domain.h;
+        let shared = curve.mul(&peer, &scalar);
+        if shared.is_infinity() {
+            return Err("peer point has small order");
+        }
+        Ok(derive_key(curve, &shared))
+    }
+}
+
+/// A point of prime order q on `curve`, whose order is `order`
+///
+/// Multiplying by `order / q` is not enough: y^2 = x^3 + 1 over F211 is Z_114 × Z_2,
+/// so 114 P = O for every P even though 2 | 228. Strip all of q from the order
+/// instead, which leaves a point of q-power order, and step it down to order q.
+fn point_of_order(curve: &Curve, order: u64, q: u64) -> Option<Point> {
+    let mut cofactor = order;
+    while cofactor.is_multiple_of(q) {
+        cofactor /= q;
+    }
+    enumerate_points(curve).into_iter().find_map(|point| {
+        let mut point = curve.mul_u64(&point, cofactor);
+        if point.is_infinity() {
+            return None;
+        }
+        loop {
+            let next = curve.mul_u64(&point, q);
+            if next.is_infinity() {
+                return Some(point);
+            }
+            point = next;
+        }
+    })
+}
+
+/// One leaked congruence: secret = residue mod modulus
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leak {
+    pub b: u64,
+    pub residue: u64,
+    pub modulus: u64,
+}
+
+/// Query `oracle` with small-order points on every y^2 = x^3 + a x + b' over F_p
+/// until the leaked moduli multiply past `bound`
+///
+/// `oracle` is the server's handshake, returning None when it refuses the point.
+/// `kdf_curve` is the curve the server derives keys with, so the attacker can
+/// recompute candidate keys. Returns the leaks and the recovered secret mod their
+/// product.
+pub fn invalid_curve_attack(
+    p: u64,
+    a: i64,
+    kdf_curve: &Curve,
+    bound: u64,
+    max_prime: u64,
+    oracle: impl Fn(&Point) -> Option<[u8; 32]>,
+) -> Option<(Vec<Leak>, u64)> {
+    let mut leaks: Vec<Leak> = Vec::new();
+    let mut modulus = 1u64;
+    for b in 0..p {
+        let curve = Curve::small(p, a, b as i64);
+        if curve.is_singular() {
+            continue;
+        }
+        let order = count_points(&curve);
+        for (q, _) in factorize(order) {
+            if q > max_prime || leaks.iter().any(|leak| leak.modulus == q) {
+                continue;
+            }
+            let Some(point) = point_of_order(&curve, order, q) else {
+                continue;
+            };
+            let Some(key) = oracle(&point) else {
+                continue;
+            };
+            // j * Q for j in [0, q) are all distinct, exactly one matches
+            let Some(residue) = (0..q).find(|j| derive_key(kdf_curve, &curve.mul_u64(&point, *j)) == key) else {
+                continue;
+            };
+            leaks.push(Leak { b, residue, modulus: q });
+            modulus *= q;
+            if modulus > bound {
+                let congruences: Vec<(i64, i64)> =
+                    leaks.iter().map(|leak| (leak.residue as i64, leak.modulus as i64)).collect();
+                let (secret, _) = crt(&congruences)?;
+                return Some((leaks, secret as u64));
+            }
+        }
+    }
+    None
+}
+
+pub fn entry_point() {
+    println!("--- INVALID CURVE ATTACK ---");
+    // The F211 server's secret lives in the order 61 subgroup
+    let secret = rand::rng().random_range(1..61u64);
+    let server = NaiveProjectiveServer::new(secret);
+    let result = invalid_curve_attack(211, 0, &Curve::f211(), 61, 50, |q| Some(server.handshake(q)));
+    match result {
+        Some((leaks, found)) => {
+            for leak in &leaks {
+                println!("y^2 = x^3 + {}: secret = {} mod {}", leak.b, leak.residue, leak.modulus);
+            }
+            println!("CRT gives the secret: {found} (actual {secret})");
+        }
+        None => println!("Not enough small subgroups to recover the secret"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovers_f211_secret() {
+        // Orders of y^2 = x^3 + b' over F211 include 196 = 2^2 7^2, 225 = 3^2 5^2,
+        // 228 = 2^2 3 19, so q in {2, 3, 5, 7, 19} is available
+        for secret in [1u64, 17, 42, 60] {
+            let server = NaiveProjectiveServer::new(secret);
+            let (leaks, found) =
+                invalid_curve_attack(211, 0, &Curve::f211(), 61, 50, |q| Some(server.handshake(q))).unwrap();
+            assert_eq!(found, secret);
+            // Most of the points came from curves the server never agreed to
+            assert!(leaks.iter().any(|leak| leak.b != 4));
+        }
+    }
+
+    #[test]
+    fn test_small_subgroup_attack_on_f17() {
+        // y^2 = x^3 + 1 over F17 has 18 points: the valid curve alone leaks d mod 2 and 3,
+        // and every other b' has order 18 as well (p = 2 mod 3), so 2 * 3 is all there is
+        let server = NaiveAffineServer::new(5);
+        let (leaks, found) =
+            invalid_curve_attack(17, 0, &Curve::f17(), 5, 17, |q| Some(server.handshake(q))).unwrap();
+        assert_eq!(found, 5);
+        assert_eq!(leaks.iter().map(|leak| leak.modulus).product::<u64>(), 6);
+    }
+
+    #[test]
+    fn test_validating_server() {
+        let domain = DomainParams::f211();
+        let server = ValidatingServer::new(domain.clone(), BigUint::from(23u32));
+        let curve = domain.curve.clone();
+
+        // Honest peer: both sides agree on (h * d_a * d_b) G
+        let peer_secret = BigUint::from(9u32);
+        let peer_public = domain.mul_g(&peer_secret);
+        let key = server.handshake(&sec1::encode(&curve, &peer_public, true)).unwrap();
+        let peer_shared = curve.mul(&server.public_key(), &(&peer_secret * &domain.h));
+        assert_eq!(key, derive_key(&curve, &peer_shared));
+
+        // (0, 2) is on the curve but has order 3: the cofactor kills it
+        let small = Point::new(0, 2);
+        assert_eq!(server.handshake(&sec1::encode(&curve, &small, false)), Err("peer point has small order"));
+
+        // The attack finds nothing to work with
+        let oracle = |q: &Point| server.handshake(&sec1::encode(&curve, q, false)).ok();
+        assert_eq!(invalid_curve_attack(211, 0, &curve, 61, 50, oracle), None);
+    }
+}
@@ -0,0 +1,316 @@
+//! Powers-of-tau trusted setup
+//!
+//! Participant k takes the current SRS [τ^i]_1, [τ^i]_2 and publishes [τ^i s^i]_1,
+//! [τ^i s^i]_2 for a secret s it then forgets, so the final τ is the product of all
+//! contributions: one honest participant is enough for nobody to know it. Anyone can
+//! check a contribution from the public data alone:
+//! - it multiplies the previous τ by the s behind [s]_1, [s]_2:
+//!   e([τ']_1, [1]_2) = e([τ]_1, [s]_2) and e([s]_1, [1]_2) = e([1]_1, [s]_2)
+//! - it still is a sequence of powers, checked with a random linear combination:
+//!   e(Σ r_i [τ^i]_1, [τ]_2) = e(Σ r_i [τ^(i+1)]_1, [1]_2), same for G2
+//! - the contributor knows s: a Schnorr proof for [s]_1, bound to the previous SRS
+//!   and the participant's name so it cannot be replayed from another ceremony
+//!
+//! Contributions serialize with `to_bytes` / `write_to_file`, so every participant
+//! can run on their own machine and only exchange files.
+
+use std::fs;
+use std::path::Path;
+
+use ark_bn254::{Bn254, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup, PrimeGroup, VariableBaseMSM};
+use ark_ff::Zero;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use super::polynomial::Polynomial;
+use super::scheme::Srs;
+use crate::conversion::read_length_prefix;
+use crate::perdesen_commitment::random_scalar;
+use crate::scalar_utils::powers;
+use crate::transcript::Transcript;
+
+/// Compressed sizes of the points in a contribution
+const G1_LEN: usize = 32;
+const G2_LEN: usize = 64;
+
+/// [s]_1 and [s]_2 for the contributor's s, plus a Schnorr proof of knowledge of s
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct UpdateProof {
+    pub s_g1: G1Affine,
+    pub s_g2: G2Affine,
+    pub commitment: G1Affine,
+    pub response: Fr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Contribution {
+    pub participant: Vec<u8>,
+    pub srs: Srs,
+    pub proof: UpdateProof,
+}
+
+/// τ = 1: every power is the generator, the starting point of every ceremony
+pub fn initial_srs(max_degree: usize) -> Srs {
+    Srs {
+        g1_powers: vec![G1Affine::generator(); max_degree + 1],
+        g2_powers: vec![G2Affine::generator(); max_degree + 1],
+    }
+}
+
+fn pok_challenge(participant: &[u8], prev: &Srs, s_g1: &G1Affine, commitment: &G1Affine) -> Fr {
+    let mut transcript = Transcript::new(b"powers of tau update v1");
+    transcript.append_message(b"participant", participant);
+    transcript.append(b"previous tau", &prev.g1_powers[1]);
+    transcript.append(b"s", s_g1);
+    transcript.append(b"R", commitment);
+    transcript.challenge_scalar(b"c")
+}
+
+/// Update `prev` with a known s; `contribute` is the version that forgets it
+pub fn contribute_with(prev: &Srs, participant: &[u8], s: Fr) -> Result<Contribution, &'static str> {
+    if s.is_zero() {
+        return Err("a zero contribution would erase tau");
+    }
+    if prev.g1_powers.len() < 2 {
+        return Err("the SRS needs at least [τ]_1");
+    }
+    let s_pows = powers(s, prev.g1_powers.len());
+    let g1: Vec<G1Projective> = prev.g1_powers.iter().zip(&s_pows).map(|(p, k)| *p * k).collect();
+    let g2: Vec<G2Projective> = prev.g2_powers.iter().zip(&s_pows).map(|(p, k)| *p * k).collect();
+    let srs = Srs { g1_powers: G1Projective::normalize_batch(&g1), g2_powers: G2Projective::normalize_batch(&g2) };
+
+    let s_g1 = (G1Projective::generator() * s).into_affine();
+    let s_g2 = (G2Projective::generator() * s).into_affine();
+    let k = random_scalar();
+    let commitment = (G1Projective::generator() * k).into_affine();
+    let response = k + pok_challenge(participant, prev, &s_g1, &commitment) * s;
+    let proof = UpdateProof { s_g1, s_g2, commitment, response };
+    Ok(Contribution { participant: participant.to_vec(), srs, proof })
+}
+
+/// Fresh random s, dropped as soon as the new SRS is computed
+pub fn contribute(prev: &Srs, participant: &[u8]) -> Result<Contribution, &'static str> {
+    contribute_with(prev, participant, random_scalar())
+}
+
+fn pairing_eq(a1: G1Projective, a2: G2Projective, b1: G1Projective, b2: G2Projective) -> bool {
+    Bn254::multi_pairing([a1, -b1], [a2, b2]).is_zero()
+}
+
+/// Every check from the module docs, against the SRS the contribution started from
+pub fn verify_contribution(prev: &Srs, contribution: &Contribution) -> Result<(), &'static str> {
+    let srs = &contribution.srs;
+    let proof = &contribution.proof;
+    let (g1, g2) = (G1Projective::generator(), G2Projective::generator());
+    let d = prev.g1_powers.len();
+    if d < 2 || srs.g1_powers.len() != d || srs.g2_powers.len() != prev.g2_powers.len() || srs.g2_powers.len() < 2 {
+        return Err("contribution changes the SRS size");
+    }
+    if srs.g1_powers[0] != G1Affine::generator() || srs.g2_powers[0] != G2Affine::generator() {
+        return Err("the first powers must be the generators");
+    }
+    if srs.g1_powers[1].is_zero() || proof.s_g1.is_zero() {
+        return Err("degenerate contribution");
+    }
+
+    // Knowledge of s
+    let c = pok_challenge(&contribution.participant, prev, &proof.s_g1, &proof.commitment);
+    if g1 * proof.response != proof.commitment + proof.s_g1 * c {
+        return Err("invalid proof of knowledge");
+    }
+    // [s]_1 and [s]_2 agree, and τ' = τ s
+    if !pairing_eq(proof.s_g1.into_group(), g2, g1, proof.s_g2.into_group()) {
+        return Err("[s]_1 and [s]_2 disagree");
+    }
+    if !pairing_eq(srs.g1_powers[1].into_group(), g2, prev.g1_powers[1].into_group(), proof.s_g2.into_group()) {
+        return Err("new tau is not the previous tau times s");
+    }
+
+    // Consecutive powers, one random combination per group
+    let r: Vec<Fr> = (0..d.max(srs.g2_powers.len())).map(|_| random_scalar()).collect();
+    let (lo1, hi1) = (&srs.g1_powers[..d - 1], &srs.g1_powers[1..]);
+    let lhs = G1Projective::msm(lo1, &r[..d - 1]).expect("lengths match");
+    let rhs = G1Projective::msm(hi1, &r[..d - 1]).expect("lengths match");
+    if !pairing_eq(lhs, srs.g2_powers[1].into_group(), rhs, g2) {
+        return Err("G1 elements are not consecutive powers");
+    }
+    let d2 = srs.g2_powers.len();
+    let (lo2, hi2) = (&srs.g2_powers[..d2 - 1], &srs.g2_powers[1..]);
+    let lhs = G2Projective::msm(lo2, &r[..d2 - 1]).expect("lengths match");
+    let rhs = G2Projective::msm(hi2, &r[..d2 - 1]).expect("lengths match");
+    if !pairing_eq(srs.g1_powers[1].into_group(), lhs, g1, rhs) {
+        return Err("G2 elements are not consecutive powers");
+    }
+    Ok(())
+}
+
+/// Replay the whole chain from `initial_srs` and return the final SRS
+pub fn verify_ceremony(max_degree: usize, contributions: &[Contribution]) -> Result<Srs, &'static str> {
+    if contributions.is_empty() {
+        return Err("a ceremony needs at least one contribution");
+    }
+    let mut current = initial_srs(max_degree);
+    for contribution in contributions {
+        verify_contribution(&current, contribution)?;
+        current = contribution.srs.clone();
+    }
+    Ok(current)
+}
+
+impl Contribution {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.serialize_compressed(&mut out).expect("writing to a Vec cannot fail");
+        out
+    }
+
+    /// Checks every point is on the curve and in the prime order subgroup, and that
+    /// the SRS has exactly `max_degree + 1` powers
+    pub fn from_bytes(max_degree: usize, bytes: &[u8]) -> Result<Self, &'static str> {
+        // Bound the name and both power lists first, see `read_length_prefix`
+        let name_len = read_length_prefix(bytes, 0)?;
+        let name_len = usize::try_from(name_len)
+            .ok()
+            .filter(|len| *len <= bytes.len())
+            .ok_or("participant name longer than the contribution")?;
+        let count = max_degree.checked_add(1).filter(|count| *count <= bytes.len() / G1_LEN);
+        let count = count.ok_or("contribution too short for max_degree")?;
+        let g1_at = 8 + name_len;
+        let g2_at = g1_at + 8 + G1_LEN * count;
+        if read_length_prefix(bytes, g1_at)? != count as u64 || read_length_prefix(bytes, g2_at)? != count as u64 {
+            return Err("SRS size does not match max_degree");
+        }
+        let mut reader = bytes;
+        let contribution = Self::deserialize_compressed(&mut reader).map_err(|_| "invalid contribution encoding")?;
+        if !reader.is_empty() {
+            return Err("trailing bytes after contribution");
+        }
+        Ok(contribution)
+    }
+
+    pub fn write_to_file(&self, path: &Path) -> Result<(), &'static str> {
+        fs::write(path, self.to_bytes()).map_err(|_| "cannot write contribution file")
+    }
+
+    pub fn read_from_file(max_degree: usize, path: &Path) -> Result<Self, &'static str> {
+        Self::from_bytes(max_degree, &fs::read(path).map_err(|_| "cannot read contribution file")?)
+    }
+}
+
+pub fn entry_point() {
+    println!("--- POWERS OF TAU ---");
+    let dir = std::env::temp_dir().join("powers_of_tau_demo");
+    fs::create_dir_all(&dir).unwrap();
+
+    // Each participant reads the latest file, contributes and writes the next one
+    let mut current = initial_srs(8);
+    for (i, name) in ["alice", "bob", "carol"].iter().enumerate() {
+        let contribution = contribute(&current, name.as_bytes()).unwrap();
+        let path = dir.join(format!("contribution_{i}.bin"));
+        contribution.write_to_file(&path).unwrap();
+        println!("{name} wrote {} ({} bytes)", path.display(), contribution.to_bytes().len());
+        current = contribution.srs;
+    }
+
+    // Anyone can replay the files from scratch
+    let contributions: Vec<Contribution> =
+        (0..3).map(|i| Contribution::read_from_file(8, &dir.join(format!("contribution_{i}.bin"))).unwrap()).collect();
+    let srs = verify_ceremony(8, &contributions).unwrap();
+    let p = Polynomial::new([1u64, 2, 3].map(Fr::from).to_vec());
+    let c = srs.commit(&p).unwrap();
+    let (y, proof) = srs.open(&p, Fr::from(5u64)).unwrap();
+    println!("ceremony verified, KZG opening with the final SRS: {}", srs.verify(&c, Fr::from(5u64), y, &proof));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ceremony_matches_product_of_contributions() {
+        let (s1, s2, s3) = (random_scalar(), random_scalar(), random_scalar());
+        let c1 = contribute_with(&initial_srs(4), b"alice", s1).unwrap();
+        let c2 = contribute_with(&c1.srs, b"bob", s2).unwrap();
+        let c3 = contribute_with(&c2.srs, b"carol", s3).unwrap();
+        let srs = verify_ceremony(4, &[c1, c2, c3]).unwrap();
+        assert_eq!(srs, Srs::from_tau(s1 * s2 * s3, 4));
+
+        // The result works as a KZG setup
+        let p = Polynomial::new((0..5).map(|_| random_scalar()).collect());
+        let z = random_scalar();
+        let (y, proof) = srs.open(&p, z).unwrap();
+        assert!(srs.verify(&srs.commit(&p).unwrap(), z, y, &proof));
+    }
+
+    #[test]
+    fn test_rejects_bad_contributions() {
+        let prev = contribute(&initial_srs(4), b"alice").unwrap().srs;
+        let good = contribute(&prev, b"bob").unwrap();
+        assert_eq!(verify_contribution(&prev, &good), Ok(()));
+
+        // Someone else cannot claim the contribution, the proof is bound to the name
+        let stolen = Contribution { participant: b"mallory".to_vec(), ..good.clone() };
+        assert_eq!(verify_contribution(&prev, &stolen), Err("invalid proof of knowledge"));
+        // Nor replay it on top of a different SRS
+        assert!(verify_contribution(&initial_srs(4), &good).is_err());
+
+        // Not a sequence of powers: [τ^3]_1 replaced
+        let mut broken = good.clone();
+        broken.srs.g1_powers[3] = (broken.srs.g1_powers[3] + G1Affine::generator()).into_affine();
+        assert_eq!(verify_contribution(&prev, &broken), Err("G1 elements are not consecutive powers"));
+        let mut broken = good.clone();
+        broken.srs.g2_powers[2] = (broken.srs.g2_powers[2] + G2Affine::generator()).into_affine();
+        assert_eq!(verify_contribution(&prev, &broken), Err("G2 elements are not consecutive powers"));
+
+        // A fresh SRS from a τ of the attacker's choosing, ignoring the previous one
+        let mut reset = contribute_with(&initial_srs(4), b"bob", random_scalar()).unwrap();
+        reset.proof = good.proof.clone();
+        assert_eq!(verify_contribution(&prev, &reset), Err("new tau is not the previous tau times s"));
+
+        // [s]_2 for a different s than [s]_1
+        let mut mismatched = good.clone();
+        mismatched.proof.s_g2 = (mismatched.proof.s_g2 + G2Affine::generator()).into_affine();
+        assert_eq!(verify_contribution(&prev, &mismatched), Err("[s]_1 and [s]_2 disagree"));
+
+        let mut shrunk = good;
+        shrunk.srs.g1_powers.pop();
+        assert_eq!(verify_contribution(&prev, &shrunk), Err("contribution changes the SRS size"));
+
+        assert!(contribute_with(&prev, b"zero", Fr::zero()).is_err());
+        assert!(verify_ceremony(4, &[]).is_err());
+    }
+
+    #[test]
+    fn test_contribution_files() {
+        let contribution = contribute(&initial_srs(3), b"alice").unwrap();
+        let bytes = contribution.to_bytes();
+        assert_eq!(Contribution::from_bytes(3, &bytes), Ok(contribution.clone()));
+        assert!(Contribution::from_bytes(3, &bytes[..bytes.len() - 1]).is_err());
+        assert!(Contribution::from_bytes(2, &bytes).is_err());
+        assert!(Contribution::from_bytes(4, &bytes).is_err());
+        assert!(Contribution::from_bytes(usize::MAX, &bytes).is_err());
+
+        let path = std::env::temp_dir().join(format!("ptau_test_{}.bin", std::process::id()));
+        contribution.write_to_file(&path).unwrap();
+        let read = Contribution::read_from_file(3, &path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read, Ok(contribution));
+        assert!(Contribution::read_from_file(3, &path).is_err());
+    }
+
+    #[test]
+    fn test_forged_length_prefixes() {
+        let bytes = contribute(&initial_srs(3), b"alice").unwrap().to_bytes();
+        let g1_at = 8 + b"alice".len();
+        let g2_at = g1_at + 8 + G1_LEN * 4;
+        assert_eq!(bytes.len(), g2_at + 8 + G2_LEN * 4 + 2 * G1_LEN + G2_LEN + 32);
+        for at in [0, g1_at, g2_at] {
+            for claimed in [u64::MAX, 1 << 40, 6] {
+                let mut forged = bytes.clone();
+                forged[at..at + 8].copy_from_slice(&claimed.to_le_bytes());
+                assert!(Contribution::from_bytes(3, &forged).is_err());
+            }
+        }
+    }
+}
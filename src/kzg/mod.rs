@@ -0,0 +1,3 @@
+pub mod polynomial;
+pub mod scheme;
+pub mod ceremony;
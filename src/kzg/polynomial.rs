@@ -0,0 +1,180 @@
+//! Dense univariate polynomials over the BN254 scalar field
+//!
+//! Coefficients are stored from the constant term up and kept trimmed, so the zero
+//! polynomial is the empty vector and `degree` is None for it.
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use ark_bn254::Fr;
+use ark_ff::{Field, Zero};
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Polynomial {
+    pub coeffs: Vec<Fr>,
+}
+
+impl Polynomial {
+    pub fn new(mut coeffs: Vec<Fr>) -> Self {
+        while coeffs.last().is_some_and(|c| c.is_zero()) {
+            coeffs.pop();
+        }
+        Self { coeffs }
+    }
+
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn constant(c: Fr) -> Self {
+        Self::new(vec![c])
+    }
+
+    /// X - root
+    pub fn linear(root: Fr) -> Self {
+        Self::new(vec![-root, Fr::ONE])
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    /// Horner's rule
+    pub fn evaluate(&self, x: Fr) -> Fr {
+        self.coeffs.iter().rev().fold(Fr::zero(), |acc, c| acc * x + c)
+    }
+
+    pub fn scale(&self, k: Fr) -> Self {
+        Self::new(self.coeffs.iter().map(|c| *c * k).collect())
+    }
+
+    /// Quotient and remainder of long division by a non-zero divisor
+    pub fn div_rem(&self, divisor: &Self) -> Result<(Self, Self), &'static str> {
+        let d = divisor.degree().ok_or("division by the zero polynomial")?;
+        let lead_inv = divisor.coeffs[d].inverse().expect("trimmed, so the leading coefficient is non-zero");
+        let mut rem = self.coeffs.clone();
+        if rem.len() <= d {
+            return Ok((Self::zero(), self.clone()));
+        }
+        let mut quot = vec![Fr::zero(); rem.len() - d];
+        for i in (0..quot.len()).rev() {
+            let q = rem[i + d] * lead_inv;
+            quot[i] = q;
+            for (j, c) in divisor.coeffs.iter().enumerate() {
+                rem[i + j] -= q * c;
+            }
+        }
+        rem.truncate(d);
+        Ok((Self::new(quot), Self::new(rem)))
+    }
+
+    /// (p(X) - p(z)) / (X - z) by synthetic division, exact by construction
+    pub fn divide_by_linear(&self, z: Fr) -> Self {
+        let mut quot = vec![Fr::zero(); self.coeffs.len().saturating_sub(1)];
+        let mut carry = Fr::zero();
+        for i in (0..quot.len()).rev() {
+            carry = self.coeffs[i + 1] + carry * z;
+            quot[i] = carry;
+        }
+        Self::new(quot)
+    }
+
+    /// ∏ (X - x_i)
+    pub fn vanishing(points: &[Fr]) -> Self {
+        points.iter().fold(Self::constant(Fr::ONE), |acc, x| &acc * &Self::linear(*x))
+    }
+
+    /// The unique polynomial of degree < n through n points with distinct x
+    pub fn interpolate(points: &[(Fr, Fr)]) -> Result<Self, &'static str> {
+        let mut result = Self::zero();
+        for (i, (xi, yi)) in points.iter().enumerate() {
+            let others: Vec<Fr> = points.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, (x, _))| *x).collect();
+            let basis = Self::vanishing(&others);
+            let denom = basis.evaluate(*xi).inverse().ok_or("interpolation points must be distinct")?;
+            result = &result + &basis.scale(*yi * denom);
+        }
+        Ok(result)
+    }
+}
+
+impl Add for &Polynomial {
+    type Output = Polynomial;
+    fn add(self, other: Self) -> Polynomial {
+        let n = self.coeffs.len().max(other.coeffs.len());
+        let at = |p: &Polynomial, i: usize| p.coeffs.get(i).copied().unwrap_or_default();
+        Polynomial::new((0..n).map(|i| at(self, i) + at(other, i)).collect())
+    }
+}
+
+impl Neg for &Polynomial {
+    type Output = Polynomial;
+    fn neg(self) -> Polynomial {
+        Polynomial::new(self.coeffs.iter().map(|c| -*c).collect())
+    }
+}
+
+impl Sub for &Polynomial {
+    type Output = Polynomial;
+    fn sub(self, other: Self) -> Polynomial {
+        self + &-other
+    }
+}
+
+/// Schoolbook multiplication, quadratic but the degrees here are small
+impl Mul for &Polynomial {
+    type Output = Polynomial;
+    fn mul(self, other: Self) -> Polynomial {
+        if self.is_zero() || other.is_zero() {
+            return Polynomial::zero();
+        }
+        let mut coeffs = vec![Fr::zero(); self.coeffs.len() + other.coeffs.len() - 1];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            for (j, b) in other.coeffs.iter().enumerate() {
+                coeffs[i + j] += *a * b;
+            }
+        }
+        Polynomial::new(coeffs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn poly(coeffs: &[u64]) -> Polynomial {
+        Polynomial::new(coeffs.iter().map(|c| Fr::from(*c)).collect())
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let p = poly(&[1, 2, 3]);
+        let q = poly(&[5, 1]);
+        assert_eq!(p.evaluate(Fr::from(2u64)), Fr::from(17u64));
+        assert_eq!(&p * &q, poly(&[5, 11, 17, 3]));
+        assert_eq!(&(&p + &q) - &q, p);
+        assert_eq!((&p - &p).degree(), None);
+        assert_eq!(poly(&[1, 0, 0]).degree(), Some(0));
+
+        let (quot, rem) = (&(&p * &q) + &poly(&[4])).div_rem(&q).unwrap();
+        assert_eq!((quot, rem), (p.clone(), poly(&[4])));
+        assert!(p.div_rem(&Polynomial::zero()).is_err());
+
+        // Synthetic division drops the remainder p(z)
+        let z = Fr::from(7u64);
+        let shifted = &p - &Polynomial::constant(p.evaluate(z));
+        assert_eq!(p.divide_by_linear(z), shifted.div_rem(&Polynomial::linear(z)).unwrap().0);
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let p = poly(&[9, 0, 4, 1]);
+        let points: Vec<(Fr, Fr)> = (1..=4u64).map(|x| (Fr::from(x), p.evaluate(Fr::from(x)))).collect();
+        assert_eq!(Polynomial::interpolate(&points), Ok(p));
+        assert!(Polynomial::vanishing(&[Fr::from(3u64), Fr::from(8u64)]).evaluate(Fr::from(8u64)).is_zero());
+        let duplicate = [(Fr::from(1u64), Fr::from(1u64)), (Fr::from(1u64), Fr::from(2u64))];
+        assert!(Polynomial::interpolate(&duplicate).is_err());
+    }
+}
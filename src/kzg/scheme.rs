@@ -0,0 +1,244 @@
+//! KZG polynomial commitments (Kate, Zaverucha, Goldberg 2010) on BN254
+//!
+//! The structured reference string is [τ^i]_1 and [τ^i]_2 for a secret τ nobody may
+//! know. A polynomial commits to C = [p(τ)]_1 = Σ p_i [τ^i]_1, one G1 point for any
+//! degree. To open at z with p(z) = y, the prover sends π = [q(τ)]_1 for
+//!   q(X) = (p(X) - y) / (X - z)
+//! which is a polynomial exactly when y is right, and the verifier checks
+//!   e(C - [y]_1, [1]_2) = e(π, [τ - z]_2)
+//! Whoever knows τ can open C to anything, hence `Srs::from_tau` is for tests only:
+//! a real SRS comes out of `ceremony`, where nobody learns τ.
+//!
+//! Batching:
+//! - several polynomials at one point: open Σ γ^i p_i for a Fiat-Shamir γ, one proof
+//! - one polynomial at several points: divide by the vanishing polynomial Z of the
+//!   points, e(C - [I(τ)]_1, [1]_2) = e(π, [Z(τ)]_2) with I interpolating the values
+
+use ark_bn254::{Bn254, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup, PrimeGroup, VariableBaseMSM};
+use ark_ff::Zero;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use super::polynomial::Polynomial;
+use crate::scalar_utils::powers;
+use crate::transcript::Transcript;
+
+/// [τ^0]_1 .. [τ^d]_1 and [τ^0]_2 .. [τ^d]_2
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Srs {
+    pub g1_powers: Vec<G1Affine>,
+    pub g2_powers: Vec<G2Affine>,
+}
+
+impl Srs {
+    /// An SRS for polynomials up to `max_degree` from a known τ: toxic waste, tests only
+    pub fn from_tau(tau: Fr, max_degree: usize) -> Self {
+        let taus = powers(tau, max_degree + 1);
+        let g1: Vec<G1Projective> = taus.iter().map(|t| G1Projective::generator() * t).collect();
+        let g2: Vec<G2Projective> = taus.iter().map(|t| G2Projective::generator() * t).collect();
+        Self { g1_powers: G1Projective::normalize_batch(&g1), g2_powers: G2Projective::normalize_batch(&g2) }
+    }
+
+    pub fn max_degree(&self) -> usize {
+        self.g1_powers.len().saturating_sub(1)
+    }
+
+    /// [p(τ)]_1
+    pub fn commit(&self, p: &Polynomial) -> Result<G1Affine, &'static str> {
+        if p.coeffs.len() > self.g1_powers.len() {
+            return Err("polynomial degree exceeds the SRS");
+        }
+        let c = G1Projective::msm(&self.g1_powers[..p.coeffs.len()], &p.coeffs).expect("lengths match");
+        Ok(c.into_affine())
+    }
+
+    /// [p(τ)]_2, only needed for the vanishing polynomial in multi-point openings
+    fn commit_g2(&self, p: &Polynomial) -> Result<G2Affine, &'static str> {
+        if p.coeffs.len() > self.g2_powers.len() {
+            return Err("too many opening points for the SRS");
+        }
+        let c = G2Projective::msm(&self.g2_powers[..p.coeffs.len()], &p.coeffs).expect("lengths match");
+        Ok(c.into_affine())
+    }
+
+    /// p(z) and the proof π = [(p(X) - p(z)) / (X - z)]_1
+    pub fn open(&self, p: &Polynomial, z: Fr) -> Result<(Fr, G1Affine), &'static str> {
+        Ok((p.evaluate(z), self.commit(&p.divide_by_linear(z))?))
+    }
+
+    /// e(C - [y]_1, [1]_2) = e(π, [τ]_2 - [z]_2)
+    pub fn verify(&self, commitment: &G1Affine, z: Fr, y: Fr, proof: &G1Affine) -> bool {
+        let [g2, tau_g2, ..] = self.g2_powers[..] else {
+            return false;
+        };
+        let lhs = commitment.into_group() - G1Projective::generator() * y;
+        let rhs = tau_g2.into_group() - g2 * z;
+        Bn254::multi_pairing([lhs, -proof.into_group()], [g2.into_group(), rhs]).is_zero()
+    }
+
+    /// γ binding all commitments, the point and the claimed values
+    fn batch_challenge(transcript: &mut Transcript, commitments: &[G1Affine], z: Fr, values: &[Fr]) -> Fr {
+        transcript.append_message(b"dom-sep", b"kzg batch v1");
+        transcript.append(b"z", &z);
+        for (c, y) in commitments.iter().zip(values) {
+            transcript.append(b"C", c);
+            transcript.append(b"y", y);
+        }
+        transcript.challenge_scalar(b"gamma")
+    }
+
+    /// Open several polynomials at the same z with a single proof
+    pub fn open_batch(
+        &self,
+        transcript: &mut Transcript,
+        polys: &[Polynomial],
+        z: Fr,
+    ) -> Result<(Vec<G1Affine>, Vec<Fr>, G1Affine), &'static str> {
+        let commitments = polys.iter().map(|p| self.commit(p)).collect::<Result<Vec<_>, _>>()?;
+        let values: Vec<Fr> = polys.iter().map(|p| p.evaluate(z)).collect();
+        let gamma = Self::batch_challenge(transcript, &commitments, z, &values);
+        let combined = polys
+            .iter()
+            .zip(powers(gamma, polys.len()))
+            .fold(Polynomial::zero(), |acc, (p, g)| &acc + &p.scale(g));
+        let (_, proof) = self.open(&combined, z)?;
+        Ok((commitments, values, proof))
+    }
+
+    pub fn verify_batch(
+        &self,
+        transcript: &mut Transcript,
+        commitments: &[G1Affine],
+        z: Fr,
+        values: &[Fr],
+        proof: &G1Affine,
+    ) -> bool {
+        if commitments.is_empty() || commitments.len() != values.len() {
+            return false;
+        }
+        let gamma = Self::batch_challenge(transcript, commitments, z, values);
+        let gammas = powers(gamma, commitments.len());
+        let c = G1Projective::msm(commitments, &gammas).expect("lengths match").into_affine();
+        let y: Fr = values.iter().zip(&gammas).map(|(y, g)| *y * g).sum();
+        self.verify(&c, z, y, proof)
+    }
+
+    /// p at every point, with π = [(p - I) / Z]_1
+    pub fn open_multi(&self, p: &Polynomial, points: &[Fr]) -> Result<(Vec<Fr>, G1Affine), &'static str> {
+        let values: Vec<Fr> = points.iter().map(|x| p.evaluate(*x)).collect();
+        let pairs: Vec<(Fr, Fr)> = points.iter().copied().zip(values.iter().copied()).collect();
+        let interpolant = Polynomial::interpolate(&pairs)?;
+        let (quotient, rem) = (p - &interpolant).div_rem(&Polynomial::vanishing(points))?;
+        debug_assert!(rem.is_zero());
+        Ok((values, self.commit(&quotient)?))
+    }
+
+    /// e(C - [I(τ)]_1, [1]_2) = e(π, [Z(τ)]_2)
+    pub fn verify_multi(&self, commitment: &G1Affine, points: &[Fr], values: &[Fr], proof: &G1Affine) -> bool {
+        if points.is_empty() || points.len() != values.len() {
+            return false;
+        }
+        let pairs: Vec<(Fr, Fr)> = points.iter().copied().zip(values.iter().copied()).collect();
+        let (Ok(interpolant), Ok(z_tau)) =
+            (Polynomial::interpolate(&pairs), self.commit_g2(&Polynomial::vanishing(points)))
+        else {
+            return false;
+        };
+        let Ok(i_tau) = self.commit(&interpolant) else {
+            return false;
+        };
+        let lhs = commitment.into_group() - i_tau;
+        Bn254::multi_pairing([lhs, -proof.into_group()], [self.g2_powers[0].into_group(), z_tau.into_group()]).is_zero()
+    }
+}
+
+pub fn entry_point() {
+    println!("--- KZG ---");
+    let srs = Srs::from_tau(Fr::from(123_456_789u64), 8);
+    let p = Polynomial::new([3u64, 0, 2, 5].map(Fr::from).to_vec());
+    let c = srs.commit(&p).unwrap();
+    let z = Fr::from(10u64);
+    let (y, proof) = srs.open(&p, z).unwrap();
+    println!("p(10) = {y}, proof verifies: {}", srs.verify(&c, z, y, &proof));
+    println!("p(10) = {}, proof verifies: {}", y + Fr::from(1u64), srs.verify(&c, z, y + Fr::from(1u64), &proof));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perdesen_commitment::random_scalar;
+
+    fn random_poly(degree: usize) -> Polynomial {
+        Polynomial::new((0..=degree).map(|_| random_scalar()).collect())
+    }
+
+    #[test]
+    fn test_commit_open_verify() {
+        let srs = Srs::from_tau(random_scalar(), 16);
+        let p = random_poly(16);
+        let c = srs.commit(&p).unwrap();
+        let z = random_scalar();
+        let (y, proof) = srs.open(&p, z).unwrap();
+        assert!(srs.verify(&c, z, y, &proof));
+        assert!(!srs.verify(&c, z, y + Fr::from(1u64), &proof));
+        assert!(!srs.verify(&c, z + Fr::from(1u64), y, &proof));
+        assert!(!srs.verify(&srs.commit(&random_poly(3)).unwrap(), z, y, &proof));
+
+        assert!(srs.commit(&random_poly(17)).is_err());
+        assert_eq!(srs.commit(&Polynomial::zero()), Ok(G1Affine::identity()));
+    }
+
+    #[test]
+    fn test_toxic_waste_breaks_binding() {
+        // With τ known, C = [p(τ)]_1 opens to any y at z: π = [(p(τ) - y) / (τ - z)]_1
+        let tau = random_scalar();
+        let srs = Srs::from_tau(tau, 4);
+        let p = random_poly(4);
+        let c = srs.commit(&p).unwrap();
+        let (z, fake_y) = (Fr::from(5u64), Fr::from(42u64));
+        let forged = (G1Projective::generator() * ((p.evaluate(tau) - fake_y) / (tau - z))).into_affine();
+        assert!(srs.verify(&c, z, fake_y, &forged));
+    }
+
+    #[test]
+    fn test_batch_opening() {
+        let srs = Srs::from_tau(random_scalar(), 8);
+        let polys: Vec<Polynomial> = (0..4).map(random_poly).collect();
+        let z = random_scalar();
+        let (cs, ys, proof) = srs.open_batch(&mut Transcript::new(b"kzg"), &polys, z).unwrap();
+        assert!(srs.verify_batch(&mut Transcript::new(b"kzg"), &cs, z, &ys, &proof));
+
+        let mut wrong = ys.clone();
+        wrong[2] += Fr::from(1u64);
+        assert!(!srs.verify_batch(&mut Transcript::new(b"kzg"), &cs, z, &wrong, &proof));
+        assert!(!srs.verify_batch(&mut Transcript::new(b"kzg"), &cs[..3], z, &ys[..3], &proof));
+        assert!(!srs.verify_batch(&mut Transcript::new(b"other"), &cs, z, &ys, &proof));
+    }
+
+    #[test]
+    fn test_multi_point_opening() {
+        let srs = Srs::from_tau(random_scalar(), 8);
+        let p = random_poly(8);
+        let c = srs.commit(&p).unwrap();
+        let points: Vec<Fr> = (1..=4u64).map(Fr::from).collect();
+        let (values, proof) = srs.open_multi(&p, &points).unwrap();
+        assert!(srs.verify_multi(&c, &points, &values, &proof));
+
+        let mut wrong = values.clone();
+        wrong[0] += Fr::from(1u64);
+        assert!(!srs.verify_multi(&c, &points, &wrong, &proof));
+        assert!(!srs.verify_multi(&c, &points[..3], &values[..3], &proof));
+        // A single point is the ordinary opening
+        let (y, single) = srs.open(&p, points[0]).unwrap();
+        assert!(srs.verify_multi(&c, &points[..1], &[y], &single));
+    }
+
+    #[test]
+    fn test_srs_serialization() {
+        let srs = Srs::from_tau(random_scalar(), 3);
+        let mut bytes = Vec::new();
+        srs.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(Srs::deserialize_compressed(&bytes[..]).unwrap(), srs);
+    }
+}
@@ -1,8 +1,24 @@
-// mod dkg_fiat_shamir;
+mod dkg_fiat_shamir;
 // use dkg_fiat_shamir::commit_reveal_pok;
 
+mod bls;
+mod bulletproofs;
 mod ceaser_cipher;
+mod conversion;
+mod ecdh;
+mod ecdlp;
+mod ecdsa;
+mod eddsa;
+mod nonce_reuse;
+mod perdesen_commitment;
+mod scalar_utils;
+mod sigma;
+mod transcript;
+mod elliptic_point;
 mod flt_eea;
+mod invalid_curve;
+mod kzg;
+mod merkle;
 mod modular_sqrt;
 
 use flt_eea::eea_gcd;
@@ -0,0 +1,203 @@
+//! Sparse Merkle tree: a key-value map with a 256-level tree over SHA-256(key)
+//!
+//! Every possible key has a leaf, almost all of them empty. Empty subtrees at each
+//! depth all have the same hash, precomputed once, so only the paths to present
+//! keys are ever hashed. Because the position of a key is fixed, the same proof
+//! shows either the value at a key or that the leaf there is empty (non-membership),
+//! which an RFC 6962 tree ordered by insertion cannot do.
+//!
+//! Proofs carry 256 siblings, most of them the empty-subtree hash; a bitmap marks
+//! the ones that are not, and only those are sent.
+
+use std::collections::BTreeMap;
+
+use sha2::{Digest, Sha256};
+
+use super::tree::{Hash, hash_leaf, hash_node};
+
+const DEPTH: usize = 256;
+
+/// Hash of an empty leaf, distinct from every `hash_leaf` output in practice
+const EMPTY_LEAF: Hash = [0u8; 32];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseProof {
+    /// Bit d set when the sibling at depth d + 1 is not an empty subtree
+    pub bitmap: [u8; 32],
+    /// The non-empty siblings, from the root down
+    pub siblings: Vec<Hash>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseMerkleTree {
+    leaves: BTreeMap<Hash, Vec<u8>>,
+    /// empty[d] is the root of an empty subtree whose top is at depth d
+    empty: Vec<Hash>,
+}
+
+fn bit(path: &Hash, depth: usize) -> bool {
+    (path[depth / 8] >> (7 - depth % 8)) & 1 == 1
+}
+
+fn leaf_hash(path: &Hash, value: &[u8]) -> Hash {
+    hash_leaf(&[path.as_slice(), value].concat())
+}
+
+pub fn key_path(key: &[u8]) -> Hash {
+    Sha256::digest(key).into()
+}
+
+fn empty_hashes() -> Vec<Hash> {
+    let mut empty = vec![EMPTY_LEAF; DEPTH + 1];
+    for d in (0..DEPTH).rev() {
+        empty[d] = hash_node(&empty[d + 1], &empty[d + 1]);
+    }
+    empty
+}
+
+impl Default for SparseMerkleTree {
+    fn default() -> Self {
+        Self { leaves: BTreeMap::new(), empty: empty_hashes() }
+    }
+}
+
+impl SparseMerkleTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, key: &[u8], value: &[u8]) {
+        self.leaves.insert(key_path(key), value.to_vec());
+    }
+
+    pub fn remove(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        self.leaves.remove(&key_path(key))
+    }
+
+    pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        self.leaves.get(&key_path(key)).map(|v| v.as_slice())
+    }
+
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Root of the subtree at `depth` holding `entries`, which share their first
+    /// `depth` bits and are sorted by path
+    fn subtree(&self, depth: usize, entries: &[(&Hash, &Vec<u8>)]) -> Hash {
+        match entries {
+            [] => self.empty[depth],
+            [(path, value)] if depth == DEPTH => leaf_hash(path, value),
+            _ => {
+                let at = entries.partition_point(|(p, _)| !bit(p, depth));
+                hash_node(&self.subtree(depth + 1, &entries[..at]), &self.subtree(depth + 1, &entries[at..]))
+            }
+        }
+    }
+
+    pub fn root(&self) -> Hash {
+        let entries: Vec<_> = self.leaves.iter().collect();
+        self.subtree(0, &entries)
+    }
+
+    /// The value at `key` (None if absent) and a proof for it against `root`
+    pub fn prove(&self, key: &[u8]) -> (Option<&[u8]>, SparseProof) {
+        let path = key_path(key);
+        let mut entries: Vec<_> = self.leaves.iter().collect();
+        let mut proof = SparseProof { bitmap: [0u8; 32], siblings: Vec::new() };
+        for depth in 0..DEPTH {
+            let at = entries.partition_point(|(p, _)| !bit(p, depth));
+            let (left, right) = entries.split_at(at);
+            let (mine, other) = if bit(&path, depth) { (right, left) } else { (left, right) };
+            if !other.is_empty() {
+                proof.bitmap[depth / 8] |= 0x80 >> (depth % 8);
+                proof.siblings.push(self.subtree(depth + 1, other));
+            }
+            entries = mine.to_vec();
+        }
+        (self.get(key), proof)
+    }
+}
+
+/// Check that `key` maps to `value` (Some) or is absent (None) under `root`
+pub fn verify_sparse(root: &Hash, key: &[u8], value: Option<&[u8]>, proof: &SparseProof) -> bool {
+    let empty = empty_hashes();
+    let path = key_path(key);
+    let mut node = value.map_or(EMPTY_LEAF, |v| leaf_hash(&path, v));
+    let mut siblings = proof.siblings.iter().rev();
+    for depth in (0..DEPTH).rev() {
+        let sibling = if (proof.bitmap[depth / 8] >> (7 - depth % 8)) & 1 == 1 {
+            match siblings.next() {
+                Some(s) => *s,
+                None => return false,
+            }
+        } else {
+            empty[depth + 1]
+        };
+        node = if bit(&path, depth) { hash_node(&sibling, &node) } else { hash_node(&node, &sibling) };
+    }
+    siblings.next().is_none() && node == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_membership_and_non_membership() {
+        let mut tree = SparseMerkleTree::new();
+        let empty_root = tree.root();
+        for i in 0..20u32 {
+            tree.insert(format!("account {i}").as_bytes(), &i.to_be_bytes());
+        }
+        let root = tree.root();
+        assert_ne!(root, empty_root);
+
+        let (value, proof) = tree.prove(b"account 7");
+        assert_eq!(value, Some(7u32.to_be_bytes().as_slice()));
+        assert!(verify_sparse(&root, b"account 7", value, &proof));
+        assert!(!verify_sparse(&root, b"account 7", Some(b"other"), &proof));
+        assert!(!verify_sparse(&root, b"account 7", None, &proof));
+        // About log2(20) non-empty siblings, not 256
+        assert!(proof.siblings.len() < 12, "{} siblings", proof.siblings.len());
+
+        let (value, proof) = tree.prove(b"account 99");
+        assert_eq!(value, None);
+        assert!(verify_sparse(&root, b"account 99", None, &proof));
+        assert!(!verify_sparse(&root, b"account 99", Some(b""), &proof));
+        assert!(!verify_sparse(&root, b"account 98", None, &proof));
+    }
+
+    #[test]
+    fn test_updates() {
+        let mut tree = SparseMerkleTree::new();
+        let empty_root = tree.root();
+        tree.insert(b"a", b"1");
+        let (_, old_proof) = tree.prove(b"a");
+        let old_root = tree.root();
+        tree.insert(b"b", b"2");
+        tree.insert(b"a", b"3");
+        assert_eq!(tree.len(), 2);
+        assert!(!verify_sparse(&tree.root(), b"a", Some(b"1"), &old_proof));
+        let (value, proof) = tree.prove(b"a");
+        assert!(verify_sparse(&tree.root(), b"a", value, &proof));
+
+        // The root only depends on the contents, not on the history
+        assert_eq!(tree.remove(b"b"), Some(b"2".to_vec()));
+        tree.insert(b"a", b"1");
+        assert_eq!(tree.root(), old_root);
+        tree.remove(b"a");
+        assert_eq!(tree.root(), empty_root);
+        let (value, proof) = tree.prove(b"a");
+        assert!(proof.siblings.is_empty());
+        assert!(verify_sparse(&empty_root, b"a", value, &proof));
+
+        let mut tampered = old_proof;
+        tampered.siblings.push(empty_root);
+        assert!(!verify_sparse(&old_root, b"a", Some(b"1"), &tampered));
+    }
+}
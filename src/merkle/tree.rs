@@ -0,0 +1,405 @@
+//! Append-only Merkle tree in the shape of RFC 6962 (Certificate Transparency)
+//!
+//! Leaves hash as SHA-256(0x00 || data) and nodes as SHA-256(0x01 || left || right).
+//! Without the prefixes an inner node would double as the leaf "left || right", and
+//! a proof for a 64-byte leaf could be passed off for a shorter tree. A tree of n
+//! leaves splits at k, the largest power of two below n, so it can grow without
+//! rehashing the full left subtrees.
+//!
+//! Proofs:
+//! - inclusion: the sibling hashes from a leaf to the root (RFC 9162 2.1.3)
+//! - multiproof: several leaves at once, sending only the subtree hashes that none
+//!   of them can recompute, shared siblings travel once
+//! - consistency: the tree of size m is a prefix of the tree of size n, so a log
+//!   never rewrote history (RFC 9162 2.1.4)
+
+use sha2::{Digest, Sha256};
+
+pub type Hash = [u8; 32];
+
+pub fn hash_leaf(data: &[u8]) -> Hash {
+    Sha256::new().chain_update([0x00]).chain_update(data).finalize().into()
+}
+
+pub fn hash_node(left: &Hash, right: &Hash) -> Hash {
+    Sha256::new().chain_update([0x01]).chain_update(left).chain_update(right).finalize().into()
+}
+
+/// Largest power of two strictly below n, n >= 2
+fn split(n: usize) -> usize {
+    1 << (usize::BITS - 1 - (n - 1).leading_zeros())
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MerkleTree {
+    leaves: Vec<Hash>,
+}
+
+impl MerkleTree {
+    pub fn new<T: AsRef<[u8]>>(data: &[T]) -> Self {
+        Self { leaves: data.iter().map(|d| hash_leaf(d.as_ref())).collect() }
+    }
+
+    pub fn push(&mut self, data: &[u8]) {
+        self.leaves.push(hash_leaf(data));
+    }
+
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    pub fn leaf_hash(&self, index: usize) -> Option<Hash> {
+        self.leaves.get(index).copied()
+    }
+
+    /// MTH(D[lo:hi])
+    fn subtree_root(&self, lo: usize, hi: usize) -> Hash {
+        match hi - lo {
+            0 => Sha256::digest([]).into(),
+            1 => self.leaves[lo],
+            n => {
+                let k = split(n);
+                hash_node(&self.subtree_root(lo, lo + k), &self.subtree_root(lo + k, hi))
+            }
+        }
+    }
+
+    pub fn root(&self) -> Hash {
+        self.subtree_root(0, self.len())
+    }
+
+    /// Root of the tree as it was with the first `size` leaves
+    pub fn root_at(&self, size: usize) -> Result<Hash, &'static str> {
+        if size > self.len() {
+            return Err("size beyond the end of the tree");
+        }
+        Ok(self.subtree_root(0, size))
+    }
+
+    /// PATH(index, D[0:size]), leaf sibling first
+    pub fn inclusion_proof(&self, index: usize, size: usize) -> Result<Vec<Hash>, &'static str> {
+        if size > self.len() || index >= size {
+            return Err("index outside the tree");
+        }
+        let mut proof = Vec::new();
+        self.path(index, 0, size, &mut proof);
+        Ok(proof)
+    }
+
+    fn path(&self, index: usize, lo: usize, hi: usize, proof: &mut Vec<Hash>) {
+        if hi - lo <= 1 {
+            return;
+        }
+        let mid = lo + split(hi - lo);
+        if index < mid {
+            self.path(index, lo, mid, proof);
+            proof.push(self.subtree_root(mid, hi));
+        } else {
+            self.path(index, mid, hi, proof);
+            proof.push(self.subtree_root(lo, mid));
+        }
+    }
+
+    /// Subtree hashes for the leaves at `indices` in the tree of size `size`, in the
+    /// order `verify_multiproof` consumes them
+    pub fn multiproof(&self, indices: &[usize], size: usize) -> Result<Vec<Hash>, &'static str> {
+        let indices = sorted_indices(indices, size)?;
+        if size > self.len() {
+            return Err("size beyond the end of the tree");
+        }
+        let mut proof = Vec::new();
+        self.multi_path(&indices, 0, size, &mut proof);
+        Ok(proof)
+    }
+
+    fn multi_path(&self, indices: &[usize], lo: usize, hi: usize, proof: &mut Vec<Hash>) {
+        if indices.is_empty() {
+            proof.push(self.subtree_root(lo, hi));
+        } else if hi - lo > 1 {
+            let mid = lo + split(hi - lo);
+            let at = indices.partition_point(|i| *i < mid);
+            self.multi_path(&indices[..at], lo, mid, proof);
+            self.multi_path(&indices[at..], mid, hi, proof);
+        }
+    }
+
+    /// SUBPROOF(old_size, D[0:new_size], true)
+    pub fn consistency_proof(&self, old_size: usize, new_size: usize) -> Result<Vec<Hash>, &'static str> {
+        if new_size > self.len() || old_size > new_size {
+            return Err("sizes outside the tree");
+        }
+        let mut proof = Vec::new();
+        if old_size > 0 && old_size < new_size {
+            self.subproof(old_size, 0, new_size, true, &mut proof);
+        }
+        Ok(proof)
+    }
+
+    fn subproof(&self, m: usize, lo: usize, hi: usize, complete: bool, proof: &mut Vec<Hash>) {
+        let n = hi - lo;
+        if m == n {
+            if !complete {
+                proof.push(self.subtree_root(lo, hi));
+            }
+            return;
+        }
+        let k = split(n);
+        if m <= k {
+            self.subproof(m, lo, lo + k, complete, proof);
+            proof.push(self.subtree_root(lo + k, hi));
+        } else {
+            self.subproof(m - k, lo + k, hi, false, proof);
+            proof.push(self.subtree_root(lo, lo + k));
+        }
+    }
+}
+
+fn sorted_indices(indices: &[usize], size: usize) -> Result<Vec<usize>, &'static str> {
+    let mut sorted = indices.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    if sorted.is_empty() || sorted.len() != indices.len() || sorted.last().is_some_and(|i| *i >= size) {
+        return Err("indices must be distinct, non-empty and inside the tree");
+    }
+    Ok(sorted)
+}
+
+/// RFC 9162 2.1.3.2
+pub fn verify_inclusion(root: &Hash, size: usize, index: usize, leaf: &Hash, proof: &[Hash]) -> bool {
+    if index >= size {
+        return false;
+    }
+    let (mut fn_, mut sn) = (index, size - 1);
+    let mut r = *leaf;
+    for p in proof {
+        if sn == 0 {
+            return false;
+        }
+        if fn_ & 1 == 1 || fn_ == sn {
+            r = hash_node(p, &r);
+            while fn_ & 1 == 0 && fn_ != 0 {
+                fn_ >>= 1;
+                sn >>= 1;
+            }
+        } else {
+            r = hash_node(&r, p);
+        }
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+    sn == 0 && r == *root
+}
+
+/// Recompute the root from (index, leaf hash) pairs and a `multiproof`
+pub fn verify_multiproof(root: &Hash, size: usize, leaves: &[(usize, Hash)], proof: &[Hash]) -> bool {
+    let indices: Vec<usize> = leaves.iter().map(|(i, _)| *i).collect();
+    let Ok(sorted) = sorted_indices(&indices, size) else {
+        return false;
+    };
+    let mut by_index = leaves.to_vec();
+    by_index.sort_unstable_by_key(|(i, _)| *i);
+    let mut proof = proof.iter();
+    let computed = rebuild(&sorted, &by_index, 0, size, &mut proof);
+    computed.is_some_and(|c| c == *root) && proof.next().is_none()
+}
+
+fn rebuild<'a>(
+    indices: &[usize],
+    leaves: &[(usize, Hash)],
+    lo: usize,
+    hi: usize,
+    proof: &mut impl Iterator<Item = &'a Hash>,
+) -> Option<Hash> {
+    if indices.is_empty() {
+        return proof.next().copied();
+    }
+    if hi - lo == 1 {
+        return Some(leaves[0].1);
+    }
+    let mid = lo + split(hi - lo);
+    let at = indices.partition_point(|i| *i < mid);
+    let left = rebuild(&indices[..at], &leaves[..at], lo, mid, proof)?;
+    let right = rebuild(&indices[at..], &leaves[at..], mid, hi, proof)?;
+    Some(hash_node(&left, &right))
+}
+
+/// RFC 9162 2.1.4.2
+pub fn verify_consistency(old_root: &Hash, old_size: usize, new_root: &Hash, new_size: usize, proof: &[Hash]) -> bool {
+    if old_size > new_size {
+        return false;
+    }
+    if old_size == new_size {
+        return proof.is_empty() && old_root == new_root;
+    }
+    if old_size == 0 {
+        return proof.is_empty();
+    }
+    let mut path = proof.to_vec();
+    if old_size.is_power_of_two() {
+        path.insert(0, *old_root);
+    }
+    let Some((first, rest)) = path.split_first() else {
+        return false;
+    };
+    let (mut fn_, mut sn) = (old_size - 1, new_size - 1);
+    while fn_ & 1 == 1 {
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+    let (mut fr, mut sr) = (*first, *first);
+    for c in rest {
+        if sn == 0 {
+            return false;
+        }
+        if fn_ & 1 == 1 || fn_ == sn {
+            fr = hash_node(c, &fr);
+            sr = hash_node(c, &sr);
+            while fn_ & 1 == 0 && fn_ != 0 {
+                fn_ >>= 1;
+                sn >>= 1;
+            }
+        } else {
+            sr = hash_node(&sr, c);
+        }
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+    fr == *old_root && sr == *new_root && sn == 0
+}
+
+pub fn entry_point() {
+    println!("--- MERKLE TREE ---");
+    let mut log = MerkleTree::new(&[b"cert 0", b"cert 1", b"cert 2"]);
+    let (old_size, old_root) = (log.len(), log.root());
+    for i in 3..10 {
+        log.push(format!("cert {i}").as_bytes());
+    }
+    let proof = log.inclusion_proof(5, log.len()).unwrap();
+    let ok = verify_inclusion(&log.root(), log.len(), 5, &hash_leaf(b"cert 5"), &proof);
+    println!("cert 5 is in the log of {} ({} hashes): {ok}", log.len(), proof.len());
+    let proof = log.consistency_proof(old_size, log.len()).unwrap();
+    let ok = verify_consistency(&old_root, old_size, &log.root(), log.len(), &proof);
+    println!("the log only appended since size {old_size} ({} hashes): {ok}", proof.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::{bytes_to_hex, hex_to_bytes};
+
+    /// The leaves from Certificate Transparency's reference test suite
+    fn ct_leaves() -> Vec<Vec<u8>> {
+        ["", "00", "10", "2021", "3031", "40414243", "5051525354555657", "606162636465666768696a6b6c6d6e6f"]
+            .into_iter()
+            .map(hex_to_bytes)
+            .collect()
+    }
+
+    #[test]
+    fn test_roots_match_ct_vectors() {
+        let tree = MerkleTree::new(&ct_leaves());
+        let expected = [
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+            "fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125",
+            "aeb6bcfe274b70a14fb067a5e5578264db0fa9b51af5e0ba159158f329e06e77",
+            "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+            "4e3bbb1f7b478dcfe71fb631631519a3bca12c9aefca1612bfce4c13a86264d4",
+            "76e67dadbcdf1e10e1b74ddc608abd2f98dfb16fbce75277b5232a127f2087ef",
+            "ddb89be403809e325750d3d263cd78929c2942b7942a34b77e122c9594a74c8c",
+            "5dc9da79a70659a9ad559cb701ded9a2ab9d823aad2f4960cfe370eff4604328",
+        ];
+        for (size, root) in expected.iter().enumerate() {
+            assert_eq!(bytes_to_hex(&tree.root_at(size).unwrap()), *root, "size {size}");
+        }
+    }
+
+    #[test]
+    fn test_inclusion_proofs() {
+        let data: Vec<Vec<u8>> = (0..21u8).map(|i| vec![i; i as usize]).collect();
+        let tree = MerkleTree::new(&data);
+        for size in 1..=tree.len() {
+            let root = tree.root_at(size).unwrap();
+            for index in 0..size {
+                let proof = tree.inclusion_proof(index, size).unwrap();
+                let leaf = tree.leaf_hash(index).unwrap();
+                assert!(verify_inclusion(&root, size, index, &leaf, &proof), "{index} in {size}");
+                assert!(!verify_inclusion(&root, size, (index + 1) % size, &leaf, &proof) || size == 1);
+                if let Some((_, shorter)) = proof.split_last() {
+                    assert!(!verify_inclusion(&root, size, index, &leaf, shorter));
+                }
+            }
+        }
+        // The leaf prefix: an inner node is not a leaf, even with matching bytes
+        let two = MerkleTree::new(&[b"a", b"b"]);
+        let inner = [two.leaf_hash(0).unwrap(), two.leaf_hash(1).unwrap()].concat();
+        assert_ne!(hash_leaf(&inner), two.root());
+        assert!(tree.inclusion_proof(5, 5).is_err());
+    }
+
+    #[test]
+    fn test_multiproofs() {
+        let data: Vec<[u8; 1]> = (0..13u8).map(|i| [i]).collect();
+        let tree = MerkleTree::new(&data);
+        let root = tree.root();
+        let leaves = |indices: &[usize]| -> Vec<(usize, Hash)> {
+            indices.iter().map(|i| (*i, tree.leaf_hash(*i).unwrap())).collect()
+        };
+        for indices in [vec![0], vec![12], vec![0, 1], vec![3, 9, 4], (0..13).collect::<Vec<_>>()] {
+            let proof = tree.multiproof(&indices, 13).unwrap();
+            assert!(verify_multiproof(&root, 13, &leaves(&indices), &proof), "{indices:?}");
+        }
+        // Neighbours share their path: fewer hashes than two inclusion proofs
+        let proof = tree.multiproof(&[4, 5], 13).unwrap();
+        assert_eq!(proof.len(), tree.inclusion_proof(4, 13).unwrap().len() - 1);
+        assert!(tree.multiproof(&(0..13).collect::<Vec<_>>(), 13).unwrap().is_empty());
+
+        let mut wrong = leaves(&[4, 5]);
+        wrong[1].1 = hash_leaf(b"forged");
+        assert!(!verify_multiproof(&root, 13, &wrong, &proof));
+        assert!(!verify_multiproof(&root, 13, &leaves(&[4, 6]), &proof));
+        let mut padded = proof.clone();
+        padded.push(root);
+        assert!(!verify_multiproof(&root, 13, &leaves(&[4, 5]), &padded));
+        assert!(tree.multiproof(&[4, 4], 13).is_err());
+        assert!(tree.multiproof(&[], 13).is_err());
+    }
+
+    #[test]
+    fn test_consistency_proofs() {
+        let mut tree = MerkleTree::default();
+        for i in 0..20u8 {
+            tree.push(&[i]);
+        }
+        for new_size in 1..=tree.len() {
+            let new_root = tree.root_at(new_size).unwrap();
+            for old_size in 1..=new_size {
+                let old_root = tree.root_at(old_size).unwrap();
+                let proof = tree.consistency_proof(old_size, new_size).unwrap();
+                assert!(verify_consistency(&old_root, old_size, &new_root, new_size, &proof), "{old_size} -> {new_size}");
+                if old_size < new_size {
+                    // A rewritten history does not verify
+                    let forged = hash_leaf(b"rewritten");
+                    assert!(!verify_consistency(&forged, old_size, &new_root, new_size, &proof));
+                    assert!(!verify_consistency(&old_root, old_size, &forged, new_size, &proof));
+                }
+            }
+        }
+        // The examples from RFC 6962 2.1.3: [c, d, g, l], [l] and [i, j, k]
+        assert_eq!(tree.consistency_proof(3, 7).unwrap().len(), 4);
+        assert_eq!(tree.consistency_proof(4, 7).unwrap(), vec![tree.subtree_root(4, 7)]);
+        assert_eq!(tree.consistency_proof(6, 7).unwrap().len(), 3);
+
+        // Changing a leaf that was already logged breaks consistency with the old root
+        let old_root = tree.root_at(8).unwrap();
+        let mut rewritten = MerkleTree::new(&(0..20u8).map(|i| [if i == 2 { 99 } else { i }]).collect::<Vec<_>>());
+        let proof = rewritten.consistency_proof(8, 20).unwrap();
+        assert!(!verify_consistency(&old_root, 8, &rewritten.root(), 20, &proof));
+        rewritten.push(b"x");
+        assert!(tree.consistency_proof(8, 21).is_err());
+    }
+}
@@ -1,6 +1,9 @@
 // Modular Square Root Algorithms
 // These algorithms find x such that x^2 ≡ a (mod p)
 
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
 /// Simple modular exponentiation: base^exp mod m
 /// Time Complexity: O(log exp)
 fn mod_pow(mut base: u128, mut exp: u128, modulus: u128) -> u128 {
@@ -175,6 +178,67 @@ fn field_pow(x: u128, y: u128, mut exp: u128, omega2: u128, p: u128) -> (u128, u
 
     (result_x, result_y)
 }
+
+// --- Arbitrary precision versions ---
+// The u128 versions above overflow once p no longer fits in 64 bits (p^2 > 2^128),
+// so real curves (secp256k1, P-256, BN254) need these instead.
+
+/// Euler's criterion: n^((p-1)/2) = 1 mod p. 0 counts as a square.
+pub fn is_square_big(n: &BigUint, p: &BigUint) -> bool {
+    let n = n % p;
+    n.is_zero() || n.modpow(&((p - 1u32) >> 1), p).is_one()
+}
+
+/// Tonelli-Shanks over BigUint, with the direct formula when p = 3 (mod 4)
+/// Unlike `tonelli_shanks`, sqrt(0) = 0 is returned instead of None.
+/// For p = 2 every residue is its own root; other even moduli give None.
+pub fn tonelli_shanks_big(n: &BigUint, p: &BigUint) -> Option<BigUint> {
+    if !p.bit(0) {
+        return (p == &BigUint::from(2u32)).then(|| n % p);
+    }
+    let n = n % p;
+    if n.is_zero() {
+        return Some(n);
+    }
+    if !is_square_big(&n, p) {
+        return None;
+    }
+    if p % 4u32 == BigUint::from(3u32) {
+        return Some(n.modpow(&((p + 1u32) >> 2), p));
+    }
+
+    // p - 1 = Q * 2^S with Q odd
+    let p_minus_1 = p - 1u32;
+    let s = p_minus_1.trailing_zeros().unwrap();
+    let q = &p_minus_1 >> s;
+
+    // Any quadratic non-residue z
+    let mut z = BigUint::from(2u32);
+    while is_square_big(&z, p) {
+        z += 1u32;
+    }
+
+    let mut m = s;
+    let mut c = z.modpow(&q, p);
+    let mut t = n.modpow(&q, p);
+    let mut r = n.modpow(&((&q + 1u32) >> 1), p);
+    while !t.is_one() {
+        // Least i with t^(2^i) = 1
+        let mut i = 1;
+        let mut temp = (&t * &t) % p;
+        while !temp.is_one() {
+            temp = (&temp * &temp) % p;
+            i += 1;
+        }
+        let b = c.modpow(&(BigUint::one() << (m - i - 1)), p);
+        m = i;
+        c = (&b * &b) % p;
+        t = (t * &c) % p;
+        r = (r * b) % p;
+    }
+    Some(r)
+}
+
 pub fn entrypoint() {
     let square = 18;
     let p = 47; // Prime where p ≡ 3 (mod 4)
@@ -267,6 +331,34 @@ mod tests {
         println!("Cipolla: sqrt(18) mod 47 = {}", r);
     }
 
+    #[test]
+    fn test_tonelli_shanks_big() {
+        // Agrees with the u128 version on small primes, both 1 and 3 mod 4
+        for p in [47u128, 1009] {
+            let big_p = BigUint::from(p);
+            for n in 1..p {
+                let small = tonelli_shanks(n, p);
+                let big = tonelli_shanks_big(&BigUint::from(n), &big_p);
+                assert_eq!(small.is_some(), big.is_some());
+                if let Some(r) = big {
+                    assert_eq!((&r * &r) % &big_p, BigUint::from(n));
+                }
+            }
+        }
+
+        // 2^255 - 19 is 1 mod 4 with S = 2
+        let p = (BigUint::one() << 255u32) - 19u32;
+        let x = BigUint::from(123456789u64).pow(5);
+        let r = tonelli_shanks_big(&(&x * &x), &p).unwrap();
+        assert!(r == x || r == &p - &x);
+        assert!(!is_square_big(&BigUint::from(2u32), &p));
+
+        // p = 2 used to search forever for a non-residue
+        let two = BigUint::from(2u32);
+        assert_eq!(tonelli_shanks_big(&BigUint::from(3u32), &two), Some(BigUint::one()));
+        assert_eq!(tonelli_shanks_big(&BigUint::from(3u32), &BigUint::from(8u32)), None);
+    }
+
     #[test]
     fn test_non_residue() {
         // 5 is not a quadratic residue mod 47
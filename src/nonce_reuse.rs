@@ -1,35 +0,0 @@
-use std::ops::{Sub, Mul, Div}; // Pseudo-traits for field math
-
-// Imagine these are BigInts in a finite field
-pub fn recover_private_key(
-    z1: u64, // Hash of Message 1
-    z2: u64, // Hash of Message 2
-    s1: u64, // Signature part s of Message 1
-    s2: u64, // Signature part s of Message 2
-    r: u64,  // The shared 'r' (proving k was reused)
-    n: u64   // The Curve Order
-) -> u64 {
-    println!("--- ATTACKING NONCE REUSE ---");
-
-    // Step 1: Recover the random nonce 'k'
-    // Formula: k = (z1 - z2) / (s1 - s2)
-    let z_diff = (z1 as i128 - z2 as i128); 
-    let s_diff = (s1 as i128 - s2 as i128);
-    
-    // In real code, this division is "Modular Inverse"
-    let k = (z_diff / s_diff) as u64; 
-    println!("Recovered nonce k: {}", k);
-
-    // Step 2: Extract the Private Key
-    // Formula: priv = ((s1 * k) - z1) / r
-    let priv_key = ((s1 * k) - z1) / r;
-    
-    priv_key
-}
-
-fn main() {
-    // Real world example (simplified numbers)
-    // The Sony PS3 hack used this exact logic because they set k = static number.
-    let recovered_key = recover_private_key(10, 20, 45, 55, 5, 100);
-    println!("CRITICAL FAILURE: Private Key is {}", recovered_key);
-}
\ No newline at end of file
@@ -0,0 +1,225 @@
+//! Biased nonce attack: the Hidden Number Problem
+//!
+//! Reusing k is the extreme case. Leaking just the top l bits of every nonce (a short
+//! RNG, a timing side channel, k generated as a 248-bit number...) is enough too.
+//! Write each nonce as k_i = a_i + b_i with a_i = leak_i << (N - l) known and
+//! 0 <= b_i < 2^(N - l). From s_i = k_i^-1 (z_i + r_i x):
+//!   b_i = t_i x + u_i  (mod n)   with   t_i = r_i / s_i,   u_i = z_i / s_i - a_i
+//! Every b_i is unusually small, and x is the hidden number they all depend on.
+//! Centering with K = 2^(N - l - 1) makes them |b_i - K| <= K, and the lattice
+//! spanned by the rows of
+//!
+//!   [ n                        ]
+//!   [    ...                   ]
+//!   [          n               ]
+//!   [ t_1 ... t_m   K/n        ]
+//!   [ u_1 ... u_m        K     ]    (u_i here is u_i - K)
+//!
+//! contains (b_1 - K, ..., b_m - K, x K / n, K), a vector much shorter than the
+//! lattice determinant suggests. LLL finds it once m * l comfortably exceeds N,
+//! then x sits in the second to last coordinate. Everything is scaled by n so the
+//! basis stays integral.
+//!
+//! The nonces must come from the signer as-is: a low-s normalizer replaces k by n - k
+//! and its top bits with them.
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{Signed, Zero};
+use sha2::{Digest, Sha256};
+
+use super::lattice::{bkz_lite, lll};
+use crate::ecdsa::{hash_to_scalar, sign_with_nonce, KeyPair, Signature};
+use crate::elliptic_point::curve::{DomainParams, Point};
+
+/// Block size and tour count for the BKZ-lite fallback
+const BKZ_BLOCK_SIZE: usize = 6;
+const BKZ_TOURS: usize = 4;
+
+/// A signature plus what leaked about its nonce
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeakySignature {
+    pub z: BigUint,
+    pub sig: Signature,
+    /// The top `known_bits` bits of k, as an integer
+    pub leak: BigUint,
+}
+
+/// Top `known_bits` of k, seen as an N-bit number where N = log2(n)
+pub fn nonce_leak(k: &BigUint, n: &BigUint, known_bits: u64) -> BigUint {
+    k >> (n.bits() - known_bits)
+}
+
+/// Rough number of signatures LLL needs: m * l should beat N with some slack
+pub fn signatures_needed(n: &BigUint, known_bits: u64) -> usize {
+    (n.bits() * 4 / 3).div_ceil(known_bits) as usize
+}
+
+fn to_int(x: &BigUint) -> BigInt {
+    BigInt::from_biguint(Sign::Plus, x.clone())
+}
+
+/// The (m + 2)-dimensional HNP basis, already scaled by n
+pub fn build_lattice(samples: &[LeakySignature], n: &BigUint, known_bits: u64) -> Result<Vec<Vec<BigInt>>, &'static str> {
+    if known_bits == 0 || known_bits >= n.bits() {
+        return Err("known bits must be between 1 and log2(n) - 1");
+    }
+    let unknown_bits = n.bits() - known_bits;
+    let half_bound = BigUint::from(1u32) << (unknown_bits - 1);
+    let m = samples.len();
+
+    let mut t_row = vec![BigInt::zero(); m + 2];
+    let mut u_row = vec![BigInt::zero(); m + 2];
+    for (i, sample) in samples.iter().enumerate() {
+        let s_inv = sample.sig.s.modinv(n).ok_or("s is not invertible mod n")?;
+        let t = (&sample.sig.r * &s_inv) % n;
+        let a = (&sample.leak << unknown_bits) % n;
+        // u - K = z / s - a - K, kept in [0, n)
+        let u = ((&sample.z * &s_inv) % n + n + n - a - (&half_bound % n)) % n;
+        t_row[i] = to_int(&(t * n));
+        u_row[i] = to_int(&(u * n));
+    }
+    t_row[m] = to_int(&half_bound);
+    u_row[m + 1] = to_int(&(n * &half_bound));
+
+    let modulus = to_int(&(n * n));
+    let mut basis: Vec<Vec<BigInt>> = (0..m)
+        .map(|i| {
+            let mut row = vec![BigInt::zero(); m + 2];
+            row[i] = modulus.clone();
+            row
+        })
+        .collect();
+    basis.push(t_row);
+    basis.push(u_row);
+    Ok(basis)
+}
+
+/// Read x off any reduced vector of the form (..., ±x K, ±n K)
+fn candidates(basis: &[Vec<BigInt>], n: &BigUint, known_bits: u64) -> Vec<BigUint> {
+    let half_bound = to_int(&(BigUint::from(1u32) << (n.bits() - known_bits - 1)));
+    let embedding = to_int(n) * &half_bound;
+    let modulus = to_int(n);
+    let m = basis[0].len() - 2;
+    basis
+        .iter()
+        .filter(|row| row[m + 1].abs() == embedding)
+        .filter_map(|row| {
+            let scaled = if row[m + 1].is_positive() { row[m].clone() } else { -&row[m] };
+            if !(&scaled % &half_bound).is_zero() {
+                return None;
+            }
+            let x = ((scaled / &half_bound) % &modulus + &modulus) % &modulus;
+            x.to_biguint()
+        })
+        .collect()
+}
+
+fn find_secret(
+    domain: &DomainParams,
+    public: &Point,
+    basis: &[Vec<BigInt>],
+    known_bits: u64,
+) -> Option<BigUint> {
+    candidates(basis, &domain.n, known_bits)
+        .into_iter()
+        .find(|x| &domain.mul_g(x) == public)
+}
+
+/// Recover the private key from signatures whose nonces leak their top `known_bits`
+/// Runs LLL first and falls back to BKZ-lite if that was not enough.
+pub fn attack(
+    domain: &DomainParams,
+    public: &Point,
+    samples: &[LeakySignature],
+    known_bits: u64,
+) -> Option<BigUint> {
+    let mut basis = build_lattice(samples, &domain.n, known_bits).ok()?;
+    lll(&mut basis).ok()?;
+    if let Some(secret) = find_secret(domain, public, &basis, known_bits) {
+        return Some(secret);
+    }
+    bkz_lite(&mut basis, BKZ_BLOCK_SIZE, BKZ_TOURS).ok()?;
+    find_secret(domain, public, &basis, known_bits)
+}
+
+/// A signer whose nonces leak: returns the signature along with the top bits of k
+pub fn leaky_sign(domain: &DomainParams, secret: &BigUint, msg: &[u8], known_bits: u64) -> LeakySignature {
+    let z = hash_to_scalar(domain, &Sha256::digest(msg));
+    loop {
+        let k = domain.random_scalar();
+        if let Some(sig) = sign_with_nonce(domain, secret, &z, &k) {
+            let leak = nonce_leak(&k, &domain.n, known_bits);
+            return LeakySignature { z, sig, leak };
+        }
+    }
+}
+
+pub fn entry_point() {
+    println!("--- ATTACKING BIASED NONCES ---");
+    let domain = DomainParams::secp256k1();
+    let victim = KeyPair::generate(&domain);
+    let known_bits = 32;
+    let count = signatures_needed(&domain.n, known_bits);
+    let samples: Vec<LeakySignature> = (0..count)
+        .map(|i| leaky_sign(&domain, &victim.secret, format!("tx {i}").as_bytes(), known_bits))
+        .collect();
+
+    match attack(&domain, &victim.public, &samples, known_bits) {
+        Some(secret) => println!("{count} signatures leaking {known_bits} bits each give the key {secret:x}"),
+        None => println!("Lattice reduction did not find the key, collect more signatures"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(domain: &DomainParams, keys: &KeyPair, count: usize, known_bits: u64) -> Vec<LeakySignature> {
+        (0..count)
+            .map(|i| leaky_sign(domain, &keys.secret, format!("msg {i}").as_bytes(), known_bits))
+            .collect()
+    }
+
+    #[test]
+    fn test_recovers_key_from_32_leaked_bits() {
+        let domain = DomainParams::secp256k1();
+        let keys = KeyPair::generate(&domain);
+        let samples = samples(&domain, &keys, signatures_needed(&domain.n, 32), 32);
+        assert_eq!(attack(&domain, &keys.public, &samples, 32), Some(keys.secret));
+    }
+
+    #[test]
+    fn test_short_nonces() {
+        // A signer drawing 224-bit nonces: the leak is 32 zero bits
+        let domain = DomainParams::p256();
+        let keys = KeyPair::generate(&domain);
+        let samples: Vec<LeakySignature> = (0..11u32)
+            .map(|i| {
+                let z = hash_to_scalar(&domain, &Sha256::digest(i.to_be_bytes()));
+                let k = domain.random_scalar() >> 32;
+                let sig = sign_with_nonce(&domain, &keys.secret, &z, &k).unwrap();
+                LeakySignature { z, sig, leak: BigUint::zero() }
+            })
+            .collect();
+        assert_eq!(attack(&domain, &keys.public, &samples, 32), Some(keys.secret));
+    }
+
+    #[test]
+    fn test_too_few_signatures_fail() {
+        let domain = DomainParams::secp256k1();
+        let keys = KeyPair::generate(&domain);
+        let samples = samples(&domain, &keys, 3, 32);
+        assert_eq!(attack(&domain, &keys.public, &samples, 32), None);
+    }
+
+    #[test]
+    fn test_lattice_shape() {
+        let domain = DomainParams::secp256k1();
+        let keys = KeyPair::generate(&domain);
+        let samples = samples(&domain, &keys, 4, 64);
+        let basis = build_lattice(&samples, &domain.n, 64).unwrap();
+        assert_eq!(basis.len(), 6);
+        assert!(build_lattice(&samples, &domain.n, 0).is_err());
+        assert!(build_lattice(&samples, &domain.n, 256).is_err());
+    }
+}
@@ -0,0 +1,294 @@
+//! Exact lattice basis reduction
+//!
+//! LLL turns a basis into one whose first vectors are short and nearly orthogonal.
+//! The textbook version tracks the Gram-Schmidt coefficients mu_ij as rationals;
+//! this is the integral variant (Cohen, "A Course in Computational Algebraic Number
+//! Theory", algorithm 2.6.7) which keeps
+//!   d_i       = det of the Gram matrix of b_1..b_i
+//!   lambda_ij = d_j * mu_ij
+//! Both are integers and every division below is exact, so no precision is ever lost.
+//!
+//! Rows of the basis are the lattice vectors, and must be linearly independent.
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Signed, Zero};
+
+/// Lovász constant 99/100: slower than the classic 3/4 but gives much shorter vectors
+pub const DEFAULT_DELTA: (u32, u32) = (99, 100);
+
+pub fn dot(a: &[BigInt], b: &[BigInt]) -> BigInt {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+pub fn norm_squared(v: &[BigInt]) -> BigInt {
+    dot(v, v)
+}
+
+/// Integral Gram-Schmidt data, 1-indexed like the reference: d[0] = 1 and
+/// lambda[k][j] for j < k
+struct GramSchmidt {
+    d: Vec<BigInt>,
+    lambda: Vec<Vec<BigInt>>,
+}
+
+impl GramSchmidt {
+    fn new(n: usize) -> Self {
+        let mut d = vec![BigInt::zero(); n + 1];
+        d[0] = BigInt::one();
+        Self {
+            d,
+            lambda: vec![vec![BigInt::zero(); n + 1]; n + 1],
+        }
+    }
+
+    /// Fill in row k from rows 1..k-1
+    fn extend(&mut self, basis: &[Vec<BigInt>], k: usize) -> Result<(), &'static str> {
+        for j in 1..=k {
+            let mut u = dot(&basis[k - 1], &basis[j - 1]);
+            for i in 1..j {
+                u = (&self.d[i] * &u - &self.lambda[k][i] * &self.lambda[j][i]) / &self.d[i - 1];
+            }
+            if j < k {
+                self.lambda[k][j] = u;
+            } else if u.is_zero() {
+                return Err("basis vectors are linearly dependent");
+            } else {
+                self.d[k] = u;
+            }
+        }
+        Ok(())
+    }
+
+    fn compute(basis: &[Vec<BigInt>]) -> Result<Self, &'static str> {
+        let mut gs = Self::new(basis.len());
+        for k in 1..=basis.len() {
+            gs.extend(basis, k)?;
+        }
+        Ok(gs)
+    }
+
+    /// d_k d_{k-2} < delta d_{k-1}^2 - lambda_{k,k-1}^2, i.e. Lovász fails at k
+    fn lovasz_fails(&self, k: usize, (num, den): (&BigInt, &BigInt)) -> bool {
+        let lambda = &self.lambda[k][k - 1];
+        den * &self.d[k] * &self.d[k - 2] < num * &self.d[k - 1] * &self.d[k - 1] - den * lambda * lambda
+    }
+}
+
+/// Size reduction: make |mu_kl| <= 1/2 by subtracting the nearest integer multiple of b_l
+fn reduce(basis: &mut [Vec<BigInt>], gs: &mut GramSchmidt, k: usize, l: usize) {
+    let lambda = &gs.lambda[k][l];
+    let d = &gs.d[l];
+    if BigInt::from(2) * lambda.abs() <= *d {
+        return;
+    }
+    // round(lambda / d), with d > 0
+    let q = (BigInt::from(2) * lambda + d).div_floor(&(BigInt::from(2) * d));
+    let row_l = basis[l - 1].clone();
+    for (x, y) in basis[k - 1].iter_mut().zip(&row_l) {
+        *x -= &q * y;
+    }
+    gs.lambda[k][l] -= &q * d;
+    for i in 1..l {
+        let delta = &q * &gs.lambda[l][i];
+        gs.lambda[k][i] -= delta;
+    }
+}
+
+/// Exchange b_k and b_{k-1} and patch up the Gram-Schmidt data
+fn swap(basis: &mut [Vec<BigInt>], gs: &mut GramSchmidt, k: usize, kmax: usize) {
+    basis.swap(k - 1, k - 2);
+    for j in 1..k - 1 {
+        let tmp = gs.lambda[k][j].clone();
+        gs.lambda[k][j] = gs.lambda[k - 1][j].clone();
+        gs.lambda[k - 1][j] = tmp;
+    }
+    let lambda = gs.lambda[k][k - 1].clone();
+    let b = (&gs.d[k - 2] * &gs.d[k] + &lambda * &lambda) / &gs.d[k - 1];
+    for i in k + 1..=kmax {
+        let t = gs.lambda[i][k].clone();
+        gs.lambda[i][k] = (&gs.d[k] * &gs.lambda[i][k - 1] - &lambda * &t) / &gs.d[k - 1];
+        gs.lambda[i][k - 1] = (&b * &t + &lambda * &gs.lambda[i][k]) / &gs.d[k];
+    }
+    gs.d[k - 1] = b;
+}
+
+/// LLL-reduce `basis` in place with Lovász constant delta = num / den (1/4 < delta <= 1)
+pub fn lll_with_delta(basis: &mut [Vec<BigInt>], delta: (u32, u32)) -> Result<(), &'static str> {
+    let n = basis.len();
+    if n == 0 {
+        return Ok(());
+    }
+    let (num, den) = (BigInt::from(delta.0), BigInt::from(delta.1));
+    let mut gs = GramSchmidt::new(n);
+    gs.extend(basis, 1)?;
+    let (mut k, mut kmax) = (2, 1);
+    while k <= n {
+        if k > kmax {
+            kmax = k;
+            gs.extend(basis, k)?;
+        }
+        reduce(basis, &mut gs, k, k - 1);
+        if gs.lovasz_fails(k, (&num, &den)) {
+            swap(basis, &mut gs, k, kmax);
+            k = (k - 1).max(2);
+        } else {
+            for l in (1..k - 1).rev() {
+                reduce(basis, &mut gs, k, l);
+            }
+            k += 1;
+        }
+    }
+    Ok(())
+}
+
+pub fn lll(basis: &mut [Vec<BigInt>]) -> Result<(), &'static str> {
+    lll_with_delta(basis, DEFAULT_DELTA)
+}
+
+/// Size reduced and satisfies the Lovász condition everywhere
+pub fn is_lll_reduced(basis: &[Vec<BigInt>], delta: (u32, u32)) -> bool {
+    let Ok(gs) = GramSchmidt::compute(basis) else {
+        return false;
+    };
+    let (num, den) = (BigInt::from(delta.0), BigInt::from(delta.1));
+    let size_reduced =
+        (2..=basis.len()).all(|k| (1..k).all(|j| BigInt::from(2) * gs.lambda[k][j].abs() <= gs.d[j]));
+    size_reduced && (2..=basis.len()).all(|k| !gs.lovasz_fails(k, (&num, &den)))
+}
+
+/// Shortest combination of `block` with coefficients in {-1, 0, 1}, at least two of
+/// them nonzero and the first nonzero one +1. Also returns the index of the last
+/// vector used, which the combination can replace without changing the lattice.
+fn shortest_small_combination(block: &[Vec<BigInt>]) -> Option<(Vec<BigInt>, usize)> {
+    let dim = block[0].len();
+    let mut best: Option<(BigInt, Vec<BigInt>, usize)> = None;
+    let mut coefficients = vec![0i8; block.len()];
+    'outer: loop {
+        // Next vector in {-1, 0, 1}^len, counting in balanced ternary
+        for c in coefficients.iter_mut() {
+            if *c < 1 {
+                *c += 1;
+                break;
+            }
+            *c = -1;
+        }
+        if coefficients.iter().all(|c| *c == 0) {
+            break 'outer;
+        }
+        let nonzero: Vec<usize> = (0..block.len()).filter(|i| coefficients[*i] != 0).collect();
+        if nonzero.len() < 2 || coefficients[nonzero[0]] != 1 {
+            continue;
+        }
+        let mut v = vec![BigInt::zero(); dim];
+        for &i in &nonzero {
+            for (x, y) in v.iter_mut().zip(&block[i]) {
+                if coefficients[i] > 0 { *x += y } else { *x -= y }
+            }
+        }
+        let norm = norm_squared(&v);
+        if best.as_ref().is_none_or(|(best_norm, _, _)| norm < *best_norm) {
+            best = Some((norm, v, *nonzero.last().unwrap()));
+        }
+    }
+    best.map(|(_, v, pivot)| (v, pivot))
+}
+
+/// A light BKZ: LLL, then slide a window of `block_size` vectors along the basis and
+/// look for a short {-1, 0, 1} combination inside it. Anything shorter than the
+/// window's first vector is moved to the front of the window and LLL is re-run.
+///
+/// Real BKZ enumerates the projected sublattice exhaustively. This only searches tiny
+/// coefficients on the actual vectors, which is cheap and already shakes LLL out of
+/// a good share of the local minima it gets stuck in.
+pub fn bkz_lite(basis: &mut [Vec<BigInt>], block_size: usize, max_tours: usize) -> Result<(), &'static str> {
+    lll(basis)?;
+    let n = basis.len();
+    for _ in 0..max_tours {
+        let mut improved = false;
+        for k in 0..n.saturating_sub(1) {
+            let end = (k + block_size).min(n);
+            let Some((v, pivot)) = shortest_small_combination(&basis[k..end]) else {
+                continue;
+            };
+            if norm_squared(&v) < norm_squared(&basis[k]) {
+                // v = ±b_pivot + (others), so swapping it in keeps the same lattice
+                basis[k + pivot] = v;
+                basis[k..=k + pivot].rotate_right(1);
+                lll(basis)?;
+                improved = true;
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn basis(rows: &[&[i64]]) -> Vec<Vec<BigInt>> {
+        rows.iter().map(|row| row.iter().map(|x| BigInt::from(*x)).collect()).collect()
+    }
+
+    #[test]
+    fn test_lll_textbook_example() {
+        // Hoffstein, Pipher, Silverman example 7.75 (delta = 3/4)
+        let mut b = basis(&[
+            &[19, 2, 32, 46, 3, 33],
+            &[15, 42, 11, 0, 3, 24],
+            &[43, 15, 0, 24, 4, 16],
+            &[20, 44, 44, 0, 18, 15],
+            &[0, 48, 35, 16, 31, 31],
+            &[48, 33, 32, 9, 1, 29],
+        ]);
+        lll_with_delta(&mut b, (3, 4)).unwrap();
+        assert!(is_lll_reduced(&b, (3, 4)));
+        assert_eq!(b[0], basis(&[&[7, -12, -8, 4, 19, 9]])[0]);
+    }
+
+    #[test]
+    fn test_lll_finds_planted_short_vector() {
+        // The knapsack-style lattice: a short vector hides behind huge coordinates
+        let mut b = basis(&[
+            &[1, 0, 0, 0, 1_000_003 * 5],
+            &[0, 1, 0, 0, 1_000_003 * 7],
+            &[0, 0, 1, 0, 1_000_003 * 11],
+            &[0, 0, 0, 1, -1_000_003 * 23],
+        ]);
+        lll(&mut b).unwrap();
+        assert!(is_lll_reduced(&b, DEFAULT_DELTA));
+        // 5 + 7 + 11 - 23 = 0 gives (1, 1, 1, 1, 0)
+        assert!(b.iter().any(|v| norm_squared(v) == BigInt::from(4)));
+    }
+
+    #[test]
+    fn test_dependent_basis_rejected() {
+        let mut b = basis(&[&[1, 2, 3], &[2, 4, 6], &[0, 0, 1]]);
+        assert!(lll(&mut b).is_err());
+    }
+
+    #[test]
+    fn test_bkz_lite_keeps_lattice() {
+        let original = basis(&[
+            &[19, 2, 32, 46, 3, 33],
+            &[15, 42, 11, 0, 3, 24],
+            &[43, 15, 0, 24, 4, 16],
+            &[20, 44, 44, 0, 18, 15],
+            &[0, 48, 35, 16, 31, 31],
+            &[48, 33, 32, 9, 1, 29],
+        ]);
+        let mut reduced = original.clone();
+        lll(&mut reduced).unwrap();
+        let mut stronger = original.clone();
+        bkz_lite(&mut stronger, 4, 5).unwrap();
+        assert!(is_lll_reduced(&stronger, DEFAULT_DELTA));
+        assert!(norm_squared(&stronger[0]) <= norm_squared(&reduced[0]));
+        // Same lattice: unimodular change of basis keeps the Gram determinant
+        let det = |b: &[Vec<BigInt>]| GramSchmidt::compute(b).unwrap().d[b.len()].clone();
+        assert_eq!(det(&stronger), det(&original));
+    }
+}
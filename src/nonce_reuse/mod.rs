@@ -0,0 +1,3 @@
+pub mod reuse;
+pub mod lattice;
+pub mod hnp;
@@ -0,0 +1,176 @@
+//! ECDSA nonce reuse attack
+//!
+//! Two signatures made with the same nonce k share r = (kG).x, and
+//!   s1 = k^-1 (z1 + r x)      s2 = k^-1 (z2 + r x)      (mod n)
+//! Subtracting kills the private key:  k = (z1 - z2) / (s1 - s2)
+//! and then any one signature gives:   x = (s1 k - z1) / r
+//! Every division is a modular inverse mod the curve order n.
+//!
+//! Low-s normalization (or anyone flipping s to n - s) means we only know each s up
+//! to sign, so all four sign combinations are tried and the candidates checked
+//! against the public key.
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+use sha2::{Digest, Sha256};
+
+use crate::ecdsa::{hash_to_scalar, sign_with_nonce, KeyPair, Signature};
+use crate::elliptic_point::curve::{DomainParams, Point};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveredKey {
+    /// The reused nonce (up to sign, if an s was flipped)
+    pub k: BigUint,
+    pub secret: BigUint,
+}
+
+fn sub_mod(a: &BigUint, b: &BigUint, n: &BigUint) -> BigUint {
+    ((a % n) + n - (b % n)) % n
+}
+
+fn neg_mod(a: &BigUint, n: &BigUint) -> BigUint {
+    sub_mod(&BigUint::zero(), a, n)
+}
+
+/// Every (k, x) pair consistent with two signatures sharing r
+///
+/// * `z1`, `z2`: the message hashes as scalars
+/// * `s1`, `s2`: the s parts of the two signatures
+/// * `r`: the shared r
+/// * `n`: the curve order
+///
+/// Without the public key there is no way to tell which candidate is right,
+/// see `attack` for the checked version.
+pub fn recover_private_key(
+    z1: &BigUint,
+    z2: &BigUint,
+    s1: &BigUint,
+    s2: &BigUint,
+    r: &BigUint,
+    n: &BigUint,
+) -> Vec<RecoveredKey> {
+    let mut candidates: Vec<RecoveredKey> = Vec::new();
+    let r_inv = match r.modinv(n) {
+        Some(inv) => inv,
+        None => return candidates,
+    };
+    let z_diff = sub_mod(z1, z2, n);
+    for s1 in [s1 % n, neg_mod(s1, n)] {
+        for s2 in [s2 % n, neg_mod(s2, n)] {
+            // k = (z1 - z2) / (s1 - s2)
+            let s_diff = sub_mod(&s1, &s2, n);
+            let Some(s_diff_inv) = s_diff.modinv(n) else {
+                continue;
+            };
+            let k = (&z_diff * s_diff_inv) % n;
+            // x = (s1 k - z1) / r
+            let secret = (sub_mod(&(&s1 * &k), z1, n) * &r_inv) % n;
+            let candidate = RecoveredKey { k, secret };
+            if !candidates.contains(&candidate) {
+                candidates.push(candidate);
+            }
+        }
+    }
+    candidates
+}
+
+/// Full attack: check the shared r, recover, and keep the key that matches `public`
+pub fn attack(
+    domain: &DomainParams,
+    public: &Point,
+    (z1, sig1): (&BigUint, &Signature),
+    (z2, sig2): (&BigUint, &Signature),
+) -> Option<RecoveredKey> {
+    if sig1.r != sig2.r || z1 % &domain.n == z2 % &domain.n {
+        return None;
+    }
+    recover_private_key(z1, z2, &sig1.s, &sig2.s, &sig1.r, &domain.n)
+        .into_iter()
+        .find(|candidate| &domain.mul_g(&candidate.secret) == public)
+}
+
+pub fn entry_point() {
+    println!("--- ATTACKING NONCE REUSE ---");
+    // The Sony PS3 hack used this exact logic because they set k = static number.
+    let domain = DomainParams::secp256k1();
+    let victim = KeyPair::generate(&domain);
+    let static_k = BigUint::from(4u32);
+
+    let z1 = hash_to_scalar(&domain, &Sha256::digest(b"firmware v1"));
+    let z2 = hash_to_scalar(&domain, &Sha256::digest(b"firmware v2"));
+    let sig1 = sign_with_nonce(&domain, &victim.secret, &z1, &static_k).unwrap();
+    let sig2 = sign_with_nonce(&domain, &victim.secret, &z2, &static_k).unwrap();
+
+    match attack(&domain, &victim.public, (&z1, &sig1), (&z2, &sig2)) {
+        Some(found) => {
+            println!("Recovered nonce k: {}", found.k);
+            println!("CRITICAL FAILURE: Private Key is {:x}", found.secret);
+        }
+        None => println!("Signatures do not share a nonce"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecdsa::sign;
+
+    fn signed(domain: &DomainParams, keys: &KeyPair, msg: &[u8], k: &BigUint) -> (BigUint, Signature) {
+        let z = hash_to_scalar(domain, &Sha256::digest(msg));
+        let sig = sign_with_nonce(domain, &keys.secret, &z, k).unwrap();
+        (z, sig)
+    }
+
+    #[test]
+    fn test_recovers_secp256k1_key() {
+        let domain = DomainParams::secp256k1();
+        let keys = KeyPair::generate(&domain);
+        let k = domain.random_scalar();
+        let (z1, sig1) = signed(&domain, &keys, b"tx 1", &k);
+        let (z2, sig2) = signed(&domain, &keys, b"tx 2", &k);
+
+        let found = attack(&domain, &keys.public, (&z1, &sig1), (&z2, &sig2)).unwrap();
+        assert_eq!(found.secret, keys.secret);
+        assert_eq!(found.k, k);
+    }
+
+    #[test]
+    fn test_handles_low_s_normalization() {
+        let domain = DomainParams::p256();
+        let keys = KeyPair::generate(&domain);
+        let k = domain.random_scalar();
+        let (z1, sig1) = signed(&domain, &keys, b"a", &k);
+        let (z2, sig2) = signed(&domain, &keys, b"b", &k);
+        // Flip one s only, as a low-s normalizer might
+        let flipped = Signature { r: sig2.r.clone(), s: &domain.n - &sig2.s };
+
+        let found = attack(&domain, &keys.public, (&z1, &sig1), (&z2, &flipped)).unwrap();
+        assert_eq!(found.secret, keys.secret);
+    }
+
+    #[test]
+    fn test_toy_curve() {
+        let domain = DomainParams::f211();
+        let keys = KeyPair::from_secret(&domain, BigUint::from(42u32));
+        let k = BigUint::from(7u32);
+        let (z1, sig1) = signed(&domain, &keys, b"hello", &k);
+        let (z2, sig2) = signed(&domain, &keys, b"world", &k);
+        let candidates = recover_private_key(&z1, &z2, &sig1.s, &sig2.s, &sig1.r, &domain.n);
+        assert!(candidates.iter().any(|c| c.secret == keys.secret));
+        assert_ne!(z1, z2);
+        let found = attack(&domain, &keys.public, (&z1, &sig1), (&z2, &sig2)).unwrap();
+        assert_eq!(found.secret, BigUint::from(42u32));
+    }
+
+    #[test]
+    fn test_rfc6979_signatures_are_safe() {
+        let domain = DomainParams::secp256k1();
+        let keys = KeyPair::generate(&domain);
+        let sig1 = sign(&domain, &keys.secret, b"tx 1");
+        let sig2 = sign(&domain, &keys.secret, b"tx 2");
+        let z1 = hash_to_scalar(&domain, &Sha256::digest(b"tx 1"));
+        let z2 = hash_to_scalar(&domain, &Sha256::digest(b"tx 2"));
+        assert_ne!(sig1.r, sig2.r);
+        assert_eq!(attack(&domain, &keys.public, (&z1, &sig1), (&z2, &sig2)), None);
+    }
+}
@@ -1,47 +1,285 @@
-use ark_ec::{AffineCurve, ProjectiveCurve};
-use ark_ff::{PrimeField, UniformRand};
-use ark_bn254::{G1Projective as Point, Fr as Scalar}; // Using BN254 Curve
-use rand::{Rng, rng};
-
-struct Pedersen {
-    g: Point,
-    h: Point,
-}
-
-impl Pedersen {
-    // 1. Setup: Generate the generator points
-    fn setup() -> Self {
-        let mut rng = rng();
-        // In production, H must be derived verifiably (e.g., MapToCurve hash)
-        // to ensure no one knows the discrete log relationship.
-        let g = Point::prime_subgroup_generator();
-        let h = Point::rand(&mut rng); 
-        
+//! Pedersen commitments on BN254 G1
+//!
+//! C = v G + r H hides v perfectly (for any v there is an r giving the same C) and
+//! binds computationally: opening C two ways means knowing log_G(H). That is why H is
+//! hashed to the curve from a public tag (RFC 9380) instead of sampled: anyone can
+//! recompute it and check no one knows log_G(H).
+//!
+//! Commitments are additively homomorphic, C(v1, r1) + C(v2, r2) = C(v1 + v2, r1 + r2),
+//! so `Commitment` and `Opening` both support +, - and scaling by a scalar, and the
+//! sum of commitments opens with the sum of openings.
+//!
+//! `VectorParams` commits to a whole vector at once, C = Σ v_i G_i + r H, with one
+//! multi-scalar multiplication. Binding now also needs the G_i to have no known
+//! relations among each other, so every G_i is hashed to the curve from its index.
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use ark_bn254::{Fr as Scalar, G1Affine, G1Projective as Point};
+use ark_ec::{AffineRepr, CurveGroup, PrimeGroup, VariableBaseMSM};
+use ark_ff::{PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::{RngCore, rng};
+
+use crate::elliptic_point::hash_to_curve::{PEDERSEN_DST, hash_to_bn254_g1};
+
+/// Uniform scalar from 64 random bytes, so the reduction mod r has negligible bias
+pub fn random_scalar() -> Scalar {
+    let mut bytes = [0u8; 64];
+    rng().fill_bytes(&mut bytes);
+    Scalar::from_le_bytes_mod_order(&bytes)
+}
+
+/// The two generators everybody has to agree on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PedersenParams {
+    pub g: Point,
+    pub h: Point,
+}
+
+/// C = v G + r H, 32 bytes compressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Commitment {
+    pub point: G1Affine,
+}
+
+/// What the committer keeps secret until opening: the value and its blinding factor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Opening {
+    pub value: Scalar,
+    pub blinding: Scalar,
+}
+
+impl Default for PedersenParams {
+    fn default() -> Self {
+        Self::setup(PEDERSEN_DST)
+    }
+}
+
+impl PedersenParams {
+    /// G is the standard generator, H = hash_to_curve("H", dst)
+    pub fn setup(dst: &[u8]) -> Self {
+        let g = Point::generator();
+        let h = Point::from(hash_to_bn254_g1(b"H", dst));
         Self { g, h }
     }
 
-    // 2. Commit: C = v*G + r*H
-    // We use 'Scalar' (Fr) for the value because that's what we count with.
-    fn commit(&self, value: Scalar, blinding: Scalar) -> Point {
-        // mul performs scalar multiplication on the curve
-        // + adds two curve points together
-        (self.g.mul(value.into_repr())) + (self.h.mul(blinding.into_repr()))
+    pub fn commit(&self, opening: &Opening) -> Commitment {
+        Commitment::from(self.g * opening.value + self.h * opening.blinding)
+    }
+
+    /// Commit to `value` with a fresh blinding factor
+    pub fn commit_random(&self, value: Scalar) -> (Commitment, Opening) {
+        let opening = Opening { value, blinding: random_scalar() };
+        (self.commit(&opening), opening)
+    }
+
+    /// Does `opening` open `commitment`
+    pub fn verify(&self, commitment: &Commitment, opening: &Opening) -> bool {
+        self.commit(opening) == *commitment
+    }
+}
+
+impl From<Point> for Commitment {
+    fn from(point: Point) -> Self {
+        Self { point: point.into_affine() }
+    }
+}
+
+impl Commitment {
+    /// The commitment to 0 with blinding 0, the neutral element for +
+    pub fn zero() -> Self {
+        Self::from(Point::zero())
+    }
+
+    pub fn to_bytes(self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.serialize_compressed(&mut out).expect("writing to a Vec cannot fail");
+        out
+    }
+
+    /// Rejects points off the curve or outside the prime order subgroup
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::deserialize_compressed(bytes).map_err(|_| "invalid commitment encoding")
     }
 }
 
-fn main() {
-    let scheme = Pedersen::setup();
-    let mut rng = thread_rng();
+impl Opening {
+    pub fn to_bytes(self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.serialize_compressed(&mut out).expect("writing to a Vec cannot fail");
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::deserialize_compressed(bytes).map_err(|_| "invalid opening encoding")
+    }
+}
+
+impl Add for Commitment {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self::from(self.point + other.point)
+    }
+}
+
+impl Sub for Commitment {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self::from(self.point.into_group() - other.point)
+    }
+}
+
+impl Neg for Commitment {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self { point: -self.point }
+    }
+}
+
+impl Mul<Scalar> for Commitment {
+    type Output = Self;
+    fn mul(self, k: Scalar) -> Self {
+        Self::from(self.point * k)
+    }
+}
+
+impl Add for Opening {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self { value: self.value + other.value, blinding: self.blinding + other.blinding }
+    }
+}
+
+impl Sub for Opening {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self { value: self.value - other.value, blinding: self.blinding - other.blinding }
+    }
+}
+
+impl Neg for Opening {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self { value: -self.value, blinding: -self.blinding }
+    }
+}
+
+impl Mul<Scalar> for Opening {
+    type Output = Self;
+    fn mul(self, k: Scalar) -> Self {
+        Self { value: self.value * k, blinding: self.blinding * k }
+    }
+}
+
+/// Generators G_0..G_{n-1} for the vector entries and H for the blinding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VectorParams {
+    pub gens: Vec<G1Affine>,
+    pub h: G1Affine,
+}
+
+/// A committed vector and its blinding factor
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VectorOpening {
+    pub values: Vec<Scalar>,
+    pub blinding: Scalar,
+}
+
+impl VectorParams {
+    /// G_i = hash_to_curve("G" || i, dst), H is the same H as `PedersenParams::setup(dst)`
+    pub fn setup(n: usize, dst: &[u8]) -> Self {
+        let gens = (0..n as u32).map(|i| Self::generator(i, dst)).collect();
+        Self { gens, h: hash_to_bn254_g1(b"H", dst) }
+    }
+
+    fn generator(i: u32, dst: &[u8]) -> G1Affine {
+        let mut msg = b"G".to_vec();
+        msg.extend_from_slice(&i.to_be_bytes());
+        hash_to_bn254_g1(&msg, dst)
+    }
+
+    /// Longest vector these parameters can commit to
+    pub fn len(&self) -> usize {
+        self.gens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gens.is_empty()
+    }
+
+    /// Σ v_i G_i + r H, shorter vectors count as padded with zeros
+    pub fn commit(&self, opening: &VectorOpening) -> Result<Commitment, &'static str> {
+        if opening.values.len() > self.gens.len() {
+            return Err("vector longer than the number of generators");
+        }
+        let mut bases = self.gens[..opening.values.len()].to_vec();
+        bases.push(self.h);
+        let mut scalars = opening.values.clone();
+        scalars.push(opening.blinding);
+        let point = Point::msm(&bases, &scalars).map_err(|_| "base and scalar counts differ")?;
+        Ok(Commitment::from(point))
+    }
+
+    /// Commit to `values` with a fresh blinding factor
+    pub fn commit_random(&self, values: Vec<Scalar>) -> Result<(Commitment, VectorOpening), &'static str> {
+        let opening = VectorOpening { values, blinding: random_scalar() };
+        Ok((self.commit(&opening)?, opening))
+    }
+
+    pub fn verify(&self, commitment: &Commitment, opening: &VectorOpening) -> bool {
+        self.commit(opening).is_ok_and(|c| c == *commitment)
+    }
+}
+
+impl VectorOpening {
+    /// Entry-wise combination, padding the shorter vector with zeros
+    fn zip_with(&self, other: &Self, f: impl Fn(Scalar, Scalar) -> Scalar) -> Self {
+        let n = self.values.len().max(other.values.len());
+        let at = |v: &[Scalar], i: usize| v.get(i).copied().unwrap_or_default();
+        Self {
+            values: (0..n).map(|i| f(at(&self.values, i), at(&other.values, i))).collect(),
+            blinding: f(self.blinding, other.blinding),
+        }
+    }
+}
+
+impl Add for &VectorOpening {
+    type Output = VectorOpening;
+    fn add(self, other: Self) -> VectorOpening {
+        self.zip_with(other, |a, b| a + b)
+    }
+}
+
+impl Sub for &VectorOpening {
+    type Output = VectorOpening;
+    fn sub(self, other: Self) -> VectorOpening {
+        self.zip_with(other, |a, b| a - b)
+    }
+}
+
+impl Neg for &VectorOpening {
+    type Output = VectorOpening;
+    fn neg(self) -> VectorOpening {
+        VectorOpening { values: self.values.iter().map(|v| -*v).collect(), blinding: -self.blinding }
+    }
+}
+
+impl Mul<Scalar> for &VectorOpening {
+    type Output = VectorOpening;
+    fn mul(self, k: Scalar) -> VectorOpening {
+        VectorOpening { values: self.values.iter().map(|v| *v * k).collect(), blinding: self.blinding * k }
+    }
+}
+
+pub fn entry_point() {
+    let scheme = PedersenParams::default();
 
     // --- User A: Commits to Value 50 ---
-    let value_a = Scalar::from(50u64);
-    let blind_a = Scalar::rand(&mut rng); // Random secret to hide the 50
-    let comm_a = scheme.commit(value_a, blind_a);
+    let (comm_a, open_a) = scheme.commit_random(Scalar::from(50u64));
 
     // --- User B: Commits to Value 25 ---
-    let value_b = Scalar::from(25u64);
-    let blind_b = Scalar::rand(&mut rng);
-    let comm_b = scheme.commit(value_b, blind_b);
+    let (comm_b, open_b) = scheme.commit_random(Scalar::from(25u64));
 
     // --- The Magic: "Homomorphic Addition" ---
     // We add the COMMITMENTS (Curve Points) together.
@@ -50,15 +288,126 @@ fn main() {
 
     // --- Verification ---
     // Later, we want to prove that comm_sum really is a commitment to 75 (50+25).
-    // We simply add our secret values and blindings locally...
-    let expected_value = value_a + value_b; // 75
-    let expected_blind = blind_a + blind_b;
-    
-    // ...and regenerate the commitment.
-    let verification_comm = scheme.commit(expected_value, expected_blind);
-
-    // If the points match, the math works!
-    assert_eq!(comm_sum, verification_comm);
-    
+    // We simply add our secret values and blindings locally and check the opening.
+    let opening_sum = open_a + open_b;
+    assert_eq!(opening_sum.value, Scalar::from(75u64));
+    assert!(scheme.verify(&comm_sum, &opening_sum));
+
     println!("Success: The sum of the commitments equals the commitment of the sum.");
-}
\ No newline at end of file
+
+    // --- Vectors: one 32-byte commitment for a whole witness ---
+    let params = VectorParams::setup(4, PEDERSEN_DST);
+    let values: Vec<Scalar> = [1u64, 2, 3, 4].map(Scalar::from).to_vec();
+    let (comm_v, open_v) = params.commit_random(values).unwrap();
+    let doubled = &open_v * Scalar::from(2u64);
+    assert!(params.verify(&(comm_v * Scalar::from(2u64)), &doubled));
+    println!("Vector commitment to [1, 2, 3, 4] scales to a commitment to [2, 4, 6, 8].");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_and_open() {
+        let params = PedersenParams::default();
+        let (c, opening) = params.commit_random(Scalar::from(42u64));
+        assert!(params.verify(&c, &opening));
+        assert!(!params.verify(&c, &Opening { value: Scalar::from(43u64), ..opening }));
+        // Hiding: the same value commits differently every time
+        let (c2, _) = params.commit_random(Scalar::from(42u64));
+        assert_ne!(c, c2);
+        // H is a nothing-up-my-sleeve point, recomputable from the tag
+        assert_eq!(params, PedersenParams::setup(PEDERSEN_DST));
+        assert_ne!(params.h, params.g);
+    }
+
+    #[test]
+    fn test_homomorphic() {
+        let params = PedersenParams::default();
+        let (c1, o1) = params.commit_random(Scalar::from(50u64));
+        let (c2, o2) = params.commit_random(Scalar::from(25u64));
+        let k = Scalar::from(3u64);
+
+        assert!(params.verify(&(c1 + c2), &(o1 + o2)));
+        assert!(params.verify(&(c1 - c2), &(o1 - o2)));
+        assert!(params.verify(&(c1 * k), &(o1 * k)));
+        assert!(params.verify(&-c1, &-o1));
+        assert_eq!((o1 - o2).value, Scalar::from(25u64));
+        assert_eq!(c1 - c1, Commitment::zero());
+        assert!(params.verify(&Commitment::zero(), &Opening { value: Scalar::zero(), blinding: Scalar::zero() }));
+    }
+
+    #[test]
+    fn test_serialization() {
+        let params = PedersenParams::default();
+        let (c, opening) = params.commit_random(Scalar::from(7u64));
+        let bytes = c.to_bytes();
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(Commitment::from_bytes(&bytes), Ok(c));
+        assert_eq!(Opening::from_bytes(&opening.to_bytes()), Ok(opening));
+        assert_eq!(opening.to_bytes().len(), 64);
+
+        assert!(Commitment::from_bytes(&bytes[..31]).is_err());
+        // x >= q with both flag bits set is not an encoding of anything
+        assert!(Commitment::from_bytes(&[0xff; 32]).is_err());
+    }
+
+    #[test]
+    fn test_vector_commit_and_open() {
+        let params = VectorParams::setup(8, PEDERSEN_DST);
+        assert_eq!(params.len(), 8);
+        // Independent generators, and H shared with the single-value scheme
+        assert_eq!(params.h, PedersenParams::default().h.into_affine());
+        for (i, g) in params.gens.iter().enumerate() {
+            assert!(g.is_on_curve() && g.is_in_correct_subgroup_assuming_on_curve());
+            assert!(params.gens[i + 1..].iter().all(|other| other != g));
+            assert_ne!(*g, params.h);
+        }
+        assert_eq!(params, VectorParams::setup(8, PEDERSEN_DST));
+
+        let values: Vec<Scalar> = (1..=5u64).map(Scalar::from).collect();
+        let (c, opening) = params.commit_random(values).unwrap();
+        assert!(params.verify(&c, &opening));
+        let mut swapped = opening.clone();
+        swapped.values.swap(0, 1);
+        assert!(!params.verify(&c, &swapped));
+
+        // The naive sum agrees with the MSM
+        let naive = opening.values.iter().zip(&params.gens).fold(params.h * opening.blinding, |acc, (v, g)| acc + *g * v);
+        assert_eq!(c, Commitment::from(naive));
+
+        // A one-entry vector is an ordinary Pedersen commitment
+        let single = Opening { value: Scalar::from(9u64), blinding: Scalar::from(4u64) };
+        let as_vector = VectorOpening { values: vec![single.value], blinding: single.blinding };
+        let scalar_params = PedersenParams { g: params.gens[0].into_group(), h: params.h.into_group() };
+        assert_eq!(params.commit(&as_vector), Ok(scalar_params.commit(&single)));
+
+        let too_long = VectorOpening { values: vec![Scalar::zero(); 9], blinding: Scalar::zero() };
+        assert!(params.commit(&too_long).is_err());
+        assert!(!params.verify(&c, &too_long));
+    }
+
+    #[test]
+    fn test_vector_homomorphic() {
+        let params = VectorParams::setup(4, PEDERSEN_DST);
+        let (c1, o1) = params.commit_random((1..=4u64).map(Scalar::from).collect()).unwrap();
+        let (c2, o2) = params.commit_random(vec![Scalar::from(10u64), Scalar::from(20u64)]).unwrap();
+        let k = Scalar::from(7u64);
+
+        let sum = &o1 + &o2;
+        assert_eq!(sum.values.len(), 4);
+        assert_eq!(sum.values[1], Scalar::from(22u64));
+        assert!(params.verify(&(c1 + c2), &sum));
+        assert!(params.verify(&(c1 - c2), &(&o1 - &o2)));
+        assert!(params.verify(&(c1 * k), &(&o1 * k)));
+        assert!(params.verify(&-c2, &-&o2));
+
+        let bytes = {
+            let mut out = Vec::new();
+            o1.serialize_compressed(&mut out).unwrap();
+            out
+        };
+        assert_eq!(VectorOpening::deserialize_compressed(&bytes[..]).unwrap(), o1);
+    }
+}
@@ -0,0 +1,14 @@
+//! Vector helpers over BN254's scalar field
+
+use ark_bn254::Fr;
+use ark_ff::Field;
+
+/// Σ a_i b_i, over the shorter of the two
+pub fn inner_product(a: &[Fr], b: &[Fr]) -> Fr {
+    a.iter().zip(b).map(|(x, y)| *x * y).sum()
+}
+
+/// 1, x, x^2, ..., x^(n-1)
+pub fn powers(x: Fr, n: usize) -> Vec<Fr> {
+    std::iter::successors(Some(Fr::ONE), |p| Some(*p * x)).take(n).collect()
+}
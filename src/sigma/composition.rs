@@ -0,0 +1,267 @@
+//! AND and OR composition of Sigma protocols
+//!
+//! `And` runs both protocols side by side and answers the same challenge with both:
+//! the prover knows both witnesses.
+//!
+//! `Or` (Cramer, Damgård, Schoenmakers 1994) proves knowledge of one of two witnesses
+//! without revealing which. The prover simulates the side it has no witness for,
+//! with a challenge c_sim of its own choice, and once the verifier sends c it answers
+//! the real side with c - c_sim. The verifier only checks that the two challenges
+//! add up to c, and since simulated transcripts look exactly like real ones it cannot
+//! tell the sides apart. A prover knowing neither witness would have to fix both
+//! challenges before seeing c, which fails except with probability 1 / |Fr|.
+//! Ring signatures and "this ciphertext encrypts 0 or 1" proofs are built this way.
+
+use ark_bn254::Fr;
+
+use super::protocol::SigmaProtocol;
+use crate::perdesen_commitment::random_scalar;
+
+/// Both statements, both witnesses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct And<P0, P1>(pub P0, pub P1);
+
+impl<P0: SigmaProtocol, P1: SigmaProtocol> SigmaProtocol for And<P0, P1> {
+    type Statement = (P0::Statement, P1::Statement);
+    type Witness = (P0::Witness, P1::Witness);
+    type Commitment = (P0::Commitment, P1::Commitment);
+    type State = (P0::State, P1::State);
+    type Response = (P0::Response, P1::Response);
+
+    fn label(&self) -> Vec<u8> {
+        [b"and(".as_slice(), &self.0.label(), b",", &self.1.label(), b")"].concat()
+    }
+
+    fn commit(&self, (s0, s1): &Self::Statement, (w0, w1): &Self::Witness) -> (Self::Commitment, Self::State) {
+        let (t0, st0) = self.0.commit(s0, w0);
+        let (t1, st1) = self.1.commit(s1, w1);
+        ((t0, t1), (st0, st1))
+    }
+
+    fn respond(
+        &self,
+        (s0, s1): &Self::Statement,
+        (w0, w1): &Self::Witness,
+        (st0, st1): Self::State,
+        c: Fr,
+    ) -> Self::Response {
+        (self.0.respond(s0, w0, st0, c), self.1.respond(s1, w1, st1, c))
+    }
+
+    fn verify(
+        &self,
+        (s0, s1): &Self::Statement,
+        (t0, t1): &Self::Commitment,
+        c: Fr,
+        (z0, z1): &Self::Response,
+    ) -> bool {
+        self.0.verify(s0, t0, c, z0) && self.1.verify(s1, t1, c, z1)
+    }
+
+    fn simulate(&self, (s0, s1): &Self::Statement, c: Fr) -> (Self::Commitment, Self::Response) {
+        let (t0, z0) = self.0.simulate(s0, c);
+        let (t1, z1) = self.1.simulate(s1, c);
+        ((t0, t1), (z0, z1))
+    }
+
+    fn extract(
+        &self,
+        (s0, s1): &Self::Statement,
+        (c, (z0, z1)): (Fr, &Self::Response),
+        (c2, (z0_2, z1_2)): (Fr, &Self::Response),
+    ) -> Option<Self::Witness> {
+        Some((self.0.extract(s0, (c, z0), (c2, z0_2))?, self.1.extract(s1, (c, z1), (c2, z1_2))?))
+    }
+}
+
+/// One of two statements
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Or<P0, P1>(pub P0, pub P1);
+
+/// Which side the prover actually knows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrWitness<W0, W1> {
+    Left(W0),
+    Right(W1),
+}
+
+/// The real side's state, plus the simulated side's challenge and response
+pub enum OrState<P0: SigmaProtocol, P1: SigmaProtocol> {
+    Left(P0::State, Fr, P1::Response),
+    Right(P0::Response, Fr, P1::State),
+}
+
+impl<P0: SigmaProtocol, P1: SigmaProtocol> Clone for OrState<P0, P1> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Left(state, c, z) => Self::Left(state.clone(), *c, z.clone()),
+            Self::Right(z, c, state) => Self::Right(z.clone(), *c, state.clone()),
+        }
+    }
+}
+
+impl<P0: SigmaProtocol, P1: SigmaProtocol> SigmaProtocol for Or<P0, P1> {
+    type Statement = (P0::Statement, P1::Statement);
+    type Witness = OrWitness<P0::Witness, P1::Witness>;
+    type Commitment = (P0::Commitment, P1::Commitment);
+    type State = OrState<P0, P1>;
+    /// (c_0, z_0, z_1), with c_1 = c - c_0
+    type Response = (Fr, P0::Response, P1::Response);
+
+    fn label(&self) -> Vec<u8> {
+        [b"or(".as_slice(), &self.0.label(), b",", &self.1.label(), b")"].concat()
+    }
+
+    fn commit(&self, (s0, s1): &Self::Statement, witness: &Self::Witness) -> (Self::Commitment, Self::State) {
+        let c_sim = random_scalar();
+        match witness {
+            OrWitness::Left(w0) => {
+                let (t0, st0) = self.0.commit(s0, w0);
+                let (t1, z1) = self.1.simulate(s1, c_sim);
+                ((t0, t1), OrState::Left(st0, c_sim, z1))
+            }
+            OrWitness::Right(w1) => {
+                let (t0, z0) = self.0.simulate(s0, c_sim);
+                let (t1, st1) = self.1.commit(s1, w1);
+                ((t0, t1), OrState::Right(z0, c_sim, st1))
+            }
+        }
+    }
+
+    /// # Panics
+    ///
+    /// If `witness` is on the other side from the one `state` was committed with:
+    /// the inner witness types differ, so the state cannot stand in for it.
+    fn respond(
+        &self,
+        (s0, s1): &Self::Statement,
+        witness: &Self::Witness,
+        state: Self::State,
+        c: Fr,
+    ) -> Self::Response {
+        match (witness, state) {
+            (OrWitness::Left(w0), OrState::Left(st0, c1, z1)) => {
+                let c0 = c - c1;
+                (c0, self.0.respond(s0, w0, st0, c0), z1)
+            }
+            (OrWitness::Right(w1), OrState::Right(z0, c0, st1)) => (c0, z0, self.1.respond(s1, w1, st1, c - c0)),
+            _ => panic!("state from a commitment with the other witness"),
+        }
+    }
+
+    fn verify(
+        &self,
+        (s0, s1): &Self::Statement,
+        (t0, t1): &Self::Commitment,
+        c: Fr,
+        (c0, z0, z1): &Self::Response,
+    ) -> bool {
+        self.0.verify(s0, t0, *c0, z0) && self.1.verify(s1, t1, c - c0, z1)
+    }
+
+    fn simulate(&self, (s0, s1): &Self::Statement, c: Fr) -> (Self::Commitment, Self::Response) {
+        let c0 = random_scalar();
+        let (t0, z0) = self.0.simulate(s0, c0);
+        let (t1, z1) = self.1.simulate(s1, c - c0);
+        ((t0, t1), (c0, z0, z1))
+    }
+
+    /// c ≠ c' means c_0 ≠ c_0' or c_1 ≠ c_1', and that side's witness comes out
+    fn extract(
+        &self,
+        (s0, s1): &Self::Statement,
+        (c, (c0, z0, z1)): (Fr, &Self::Response),
+        (c2, (c0_2, z0_2, z1_2)): (Fr, &Self::Response),
+    ) -> Option<Self::Witness> {
+        if c0 != c0_2 {
+            self.0.extract(s0, (*c0, z0), (*c0_2, z0_2)).map(OrWitness::Left)
+        } else {
+            self.1.extract(s1, (c - c0, z1), (c2 - c0_2, z1_2)).map(OrWitness::Right)
+        }
+    }
+}
+
+pub fn entry_point() {
+    use super::protocol::FiatShamir;
+    use super::protocols::{Schnorr, public_key};
+    use crate::transcript::Transcript;
+
+    println!("--- SIGMA PROTOCOLS ---");
+    // A two-member ring signature: one of these keys signed, nobody can tell which
+    let (alice, bob) = (random_scalar(), random_scalar());
+    let ring = (public_key(alice), public_key(bob));
+    let protocol = FiatShamir(Or(Schnorr::default(), Schnorr::default()));
+    let mut transcript = Transcript::new(b"ring signature");
+    transcript.append_message(b"message", b"meet at noon");
+    let proof = protocol.prove(&mut transcript, &ring, &OrWitness::Right(bob));
+    let mut transcript = Transcript::new(b"ring signature");
+    transcript.append_message(b"message", b"meet at noon");
+    println!("OR proof signed by one of two keys verifies: {}", protocol.verify(&mut transcript, &ring, &proof));
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::Field;
+
+    use super::*;
+    use crate::sigma::protocol::FiatShamir;
+    use crate::sigma::protocols::tests::check;
+    use crate::sigma::protocols::{ChaumPedersen, PedersenOpening, Schnorr, public_key};
+    use crate::transcript::Transcript;
+
+    #[test]
+    fn test_and() {
+        let protocol = And(Schnorr::default(), PedersenOpening::default());
+        let x = random_scalar();
+        let (c, opening) = protocol.1.params.commit_random(Fr::from(5u64));
+        check(protocol, (public_key(x), c), (x, opening), (public_key(x), c + c));
+        assert_eq!(protocol.label(), b"and(schnorr,pedersen opening)");
+    }
+
+    #[test]
+    fn test_or_either_side() {
+        let protocol = Or(Schnorr::default(), Schnorr::default());
+        let (x0, x1) = (random_scalar(), random_scalar());
+        let statement = (public_key(x0), public_key(x1));
+        let false_statement = (public_key(x0 + Fr::ONE), public_key(x1 + Fr::ONE));
+        check(protocol, statement, OrWitness::Left(x0), false_statement);
+        check(protocol, statement, OrWitness::Right(x1), false_statement);
+
+        // Only one witness is needed: the other key can be anyone's
+        let stranger = public_key(random_scalar());
+        check(protocol, (stranger, public_key(x1)), OrWitness::Right(x1), false_statement);
+    }
+
+    #[test]
+    fn test_or_needs_a_witness() {
+        // With the wrong witness for its side, the real branch does not verify
+        let protocol = FiatShamir(Or(Schnorr::default(), Schnorr::default()));
+        let statement = (public_key(random_scalar()), public_key(random_scalar()));
+        let proof = protocol.prove(&mut Transcript::new(b"or"), &statement, &OrWitness::Left(random_scalar()));
+        assert!(!protocol.verify(&mut Transcript::new(b"or"), &statement, &proof));
+    }
+
+    #[test]
+    #[should_panic(expected = "state from a commitment with the other witness")]
+    fn test_or_respond_with_the_other_side_panics() {
+        let protocol = Or(Schnorr::default(), Schnorr::default());
+        let (x0, x1) = (random_scalar(), random_scalar());
+        let statement = (public_key(x0), public_key(x1));
+        let (_, state) = protocol.commit(&statement, &OrWitness::Left(x0));
+        protocol.respond(&statement, &OrWitness::Right(x1), state, random_scalar());
+    }
+
+    #[test]
+    fn test_nested() {
+        // (knows x and a DLEQ) or knows y
+        let protocol = Or(And(Schnorr::default(), ChaumPedersen::default()), Schnorr::default());
+        let (x, y) = (random_scalar(), random_scalar());
+        let cp = protocol.0.1;
+        let dleq = ((cp.g * x).into(), (cp.h * x).into());
+        let statement = ((public_key(x), dleq), public_key(y));
+        let false_statement = ((public_key(y), dleq), public_key(x));
+        check(protocol, statement, OrWitness::Left((x, x)), false_statement);
+        check(protocol, statement, OrWitness::Right(y), false_statement);
+        assert_eq!(protocol.label(), b"or(and(schnorr,chaum-pedersen),schnorr)");
+    }
+}
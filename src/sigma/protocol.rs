@@ -0,0 +1,108 @@
+//! Sigma protocols and the Fiat-Shamir transform
+//!
+//! A Sigma protocol is three moves: the prover sends a commitment, the verifier a
+//! random challenge, the prover a response. Two properties make it a proof of
+//! knowledge that leaks nothing:
+//! - special soundness: two accepting transcripts with the same commitment and
+//!   different challenges give away the witness (`extract`), so a prover who can
+//!   answer more than one challenge knows it
+//! - honest-verifier zero knowledge: for any challenge, accepting transcripts can be
+//!   produced without the witness (`simulate`), with the same distribution
+//!
+//! Both compose: `composition::And` runs two protocols on one challenge and
+//! `composition::Or` proves one of two statements without saying which. Challenges
+//! are scalars of BN254's Fr, so every protocol here shares one challenge space.
+//!
+//! `FiatShamir` replaces the verifier with a hash of the protocol label, the
+//! statement and the commitment. Leaving the statement out of that hash would let
+//! a prover pick it after the challenge (the "weak Fiat-Shamir" attacks).
+
+use ark_bn254::Fr;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::perdesen_commitment::random_scalar;
+use crate::transcript::Transcript;
+
+pub trait SigmaProtocol {
+    type Statement: CanonicalSerialize;
+    type Witness;
+    type Commitment: CanonicalSerialize + CanonicalDeserialize;
+    /// The prover's randomness between the first and the last move, `Clone` so a
+    /// prover can be rewound (which is exactly what `extract` models)
+    type State: Clone;
+    type Response: CanonicalSerialize + CanonicalDeserialize + Clone;
+
+    /// Domain separator for Fiat-Shamir, compositions include their parts'
+    fn label(&self) -> Vec<u8>;
+
+    fn commit(&self, statement: &Self::Statement, witness: &Self::Witness) -> (Self::Commitment, Self::State);
+
+    /// What an honest verifier sends
+    fn challenge(&self) -> Fr {
+        random_scalar()
+    }
+
+    fn respond(
+        &self,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        state: Self::State,
+        challenge: Fr,
+    ) -> Self::Response;
+
+    fn verify(
+        &self,
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+        challenge: Fr,
+        response: &Self::Response,
+    ) -> bool;
+
+    /// An accepting transcript for `challenge`, made without the witness
+    fn simulate(&self, statement: &Self::Statement, challenge: Fr) -> (Self::Commitment, Self::Response);
+
+    /// The witness from two accepting transcripts sharing a commitment, None if the
+    /// challenges are equal
+    fn extract(
+        &self,
+        statement: &Self::Statement,
+        first: (Fr, &Self::Response),
+        second: (Fr, &Self::Response),
+    ) -> Option<Self::Witness>;
+}
+
+/// A non-interactive proof: the commitment and the response, the challenge is
+/// recomputed by the verifier
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Proof<C: CanonicalSerialize + CanonicalDeserialize, Z: CanonicalSerialize + CanonicalDeserialize> {
+    pub commitment: C,
+    pub response: Z,
+}
+
+pub type ProofOf<P> = Proof<<P as SigmaProtocol>::Commitment, <P as SigmaProtocol>::Response>;
+
+/// Any Sigma protocol made non-interactive in the random oracle model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FiatShamir<P>(pub P);
+
+impl<P: SigmaProtocol> FiatShamir<P> {
+    fn challenge(&self, transcript: &mut Transcript, statement: &P::Statement, commitment: &P::Commitment) -> Fr {
+        transcript.append_message(b"sigma protocol", &self.0.label());
+        transcript.append(b"statement", statement);
+        transcript.append(b"commitment", commitment);
+        transcript.challenge_scalar(b"challenge")
+    }
+
+    /// `transcript` can carry context (a message to sign, a session id) beforehand
+    pub fn prove(&self, transcript: &mut Transcript, statement: &P::Statement, witness: &P::Witness) -> ProofOf<P> {
+        let (commitment, state) = self.0.commit(statement, witness);
+        let challenge = self.challenge(transcript, statement, &commitment);
+        let response = self.0.respond(statement, witness, state, challenge);
+        Proof { commitment, response }
+    }
+
+    pub fn verify(&self, transcript: &mut Transcript, statement: &P::Statement, proof: &ProofOf<P>) -> bool {
+        let challenge = self.challenge(transcript, statement, &proof.commitment);
+        self.0.verify(statement, &proof.commitment, challenge, &proof.response)
+    }
+}
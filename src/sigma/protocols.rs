@@ -0,0 +1,303 @@
+//! Concrete Sigma protocols on BN254 G1
+//!
+//! - `Schnorr`: knowledge of x with X = x G
+//! - `ChaumPedersen`: the same x in X = x G and Y = x H (DLEQ), e.g. a VRF output or
+//!   a correct decryption share
+//! - `Okamoto`: knowledge of (a, b) with X = a G + b H, two witnesses at once
+//! - `PedersenOpening`: knowledge of an opening of a `perdesen_commitment` commitment,
+//!   which is Okamoto on that module's generators and types
+//!
+//! All of them answer with z = k + c w per witness scalar, so the extractor is
+//! w = (z - z') / (c - c') and the simulator picks z, then solves for the commitment.
+
+use ark_bn254::{Fr, G1Affine, G1Projective};
+use ark_ec::{AffineRepr, CurveGroup, PrimeGroup};
+use ark_ff::Field;
+
+use super::protocol::SigmaProtocol;
+use crate::elliptic_point::hash_to_curve::{PEDERSEN_DST, hash_to_bn254_g1};
+use crate::perdesen_commitment::{Commitment, Opening, PedersenParams, random_scalar};
+
+/// (z - z') / (c - c')
+fn solve(first: (Fr, Fr), second: (Fr, Fr)) -> Option<Fr> {
+    Some((first.1 - second.1) * (first.0 - second.0).inverse()?)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Schnorr {
+    pub g: G1Affine,
+}
+
+impl Default for Schnorr {
+    fn default() -> Self {
+        Self { g: G1Affine::generator() }
+    }
+}
+
+impl SigmaProtocol for Schnorr {
+    type Statement = G1Affine;
+    type Witness = Fr;
+    type Commitment = G1Affine;
+    type State = Fr;
+    type Response = Fr;
+
+    fn label(&self) -> Vec<u8> {
+        b"schnorr".to_vec()
+    }
+
+    fn commit(&self, _: &G1Affine, _: &Fr) -> (G1Affine, Fr) {
+        let k = random_scalar();
+        ((self.g * k).into_affine(), k)
+    }
+
+    fn respond(&self, _: &G1Affine, x: &Fr, k: Fr, c: Fr) -> Fr {
+        k + c * x
+    }
+
+    /// z G = R + c X
+    fn verify(&self, x: &G1Affine, r: &G1Affine, c: Fr, z: &Fr) -> bool {
+        self.g * z == *r + *x * c
+    }
+
+    fn simulate(&self, x: &G1Affine, c: Fr) -> (G1Affine, Fr) {
+        let z = random_scalar();
+        ((self.g * z - *x * c).into_affine(), z)
+    }
+
+    fn extract(&self, _: &G1Affine, first: (Fr, &Fr), second: (Fr, &Fr)) -> Option<Fr> {
+        solve((first.0, *first.1), (second.0, *second.1))
+    }
+}
+
+/// Two generators with no known relation, the second hashed to the curve
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChaumPedersen {
+    pub g: G1Affine,
+    pub h: G1Affine,
+}
+
+impl Default for ChaumPedersen {
+    fn default() -> Self {
+        Self { g: G1Affine::generator(), h: hash_to_bn254_g1(b"sigma H", PEDERSEN_DST) }
+    }
+}
+
+impl SigmaProtocol for ChaumPedersen {
+    /// (X, Y) = (x G, x H)
+    type Statement = (G1Affine, G1Affine);
+    type Witness = Fr;
+    type Commitment = (G1Affine, G1Affine);
+    type State = Fr;
+    type Response = Fr;
+
+    fn label(&self) -> Vec<u8> {
+        b"chaum-pedersen".to_vec()
+    }
+
+    fn commit(&self, _: &Self::Statement, _: &Fr) -> (Self::Commitment, Fr) {
+        let k = random_scalar();
+        (((self.g * k).into_affine(), (self.h * k).into_affine()), k)
+    }
+
+    fn respond(&self, _: &Self::Statement, x: &Fr, k: Fr, c: Fr) -> Fr {
+        k + c * x
+    }
+
+    /// z G = R_1 + c X and z H = R_2 + c Y, with the same z
+    fn verify(&self, (x, y): &Self::Statement, (r1, r2): &Self::Commitment, c: Fr, z: &Fr) -> bool {
+        self.g * z == *r1 + *x * c && self.h * z == *r2 + *y * c
+    }
+
+    fn simulate(&self, (x, y): &Self::Statement, c: Fr) -> (Self::Commitment, Fr) {
+        let z = random_scalar();
+        let r1 = (self.g * z - *x * c).into_affine();
+        let r2 = (self.h * z - *y * c).into_affine();
+        ((r1, r2), z)
+    }
+
+    fn extract(&self, _: &Self::Statement, first: (Fr, &Fr), second: (Fr, &Fr)) -> Option<Fr> {
+        solve((first.0, *first.1), (second.0, *second.1))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Okamoto {
+    pub g: G1Affine,
+    pub h: G1Affine,
+}
+
+impl Default for Okamoto {
+    fn default() -> Self {
+        let ChaumPedersen { g, h } = ChaumPedersen::default();
+        Self { g, h }
+    }
+}
+
+impl SigmaProtocol for Okamoto {
+    /// X = a G + b H
+    type Statement = G1Affine;
+    type Witness = (Fr, Fr);
+    type Commitment = G1Affine;
+    type State = (Fr, Fr);
+    type Response = (Fr, Fr);
+
+    fn label(&self) -> Vec<u8> {
+        b"okamoto".to_vec()
+    }
+
+    fn commit(&self, _: &G1Affine, _: &(Fr, Fr)) -> (G1Affine, (Fr, Fr)) {
+        let (k1, k2) = (random_scalar(), random_scalar());
+        ((self.g * k1 + self.h * k2).into_affine(), (k1, k2))
+    }
+
+    fn respond(&self, _: &G1Affine, (a, b): &(Fr, Fr), (k1, k2): (Fr, Fr), c: Fr) -> (Fr, Fr) {
+        (k1 + c * a, k2 + c * b)
+    }
+
+    /// z_1 G + z_2 H = R + c X
+    fn verify(&self, x: &G1Affine, r: &G1Affine, c: Fr, (z1, z2): &(Fr, Fr)) -> bool {
+        self.g * z1 + self.h * z2 == *r + *x * c
+    }
+
+    fn simulate(&self, x: &G1Affine, c: Fr) -> (G1Affine, (Fr, Fr)) {
+        let (z1, z2) = (random_scalar(), random_scalar());
+        ((self.g * z1 + self.h * z2 - *x * c).into_affine(), (z1, z2))
+    }
+
+    fn extract(&self, _: &G1Affine, first: (Fr, &(Fr, Fr)), second: (Fr, &(Fr, Fr))) -> Option<(Fr, Fr)> {
+        let a = solve((first.0, first.1.0), (second.0, second.1.0))?;
+        let b = solve((first.0, first.1.1), (second.0, second.1.1))?;
+        Some((a, b))
+    }
+}
+
+/// Knowledge of (v, r) with C = v G + r H, in the types of `perdesen_commitment`: the
+/// commitment is a `Commitment` to random values and the response is an `Opening`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PedersenOpening {
+    pub params: PedersenParams,
+}
+
+impl SigmaProtocol for PedersenOpening {
+    type Statement = Commitment;
+    type Witness = Opening;
+    type Commitment = Commitment;
+    type State = Opening;
+    type Response = Opening;
+
+    fn label(&self) -> Vec<u8> {
+        b"pedersen opening".to_vec()
+    }
+
+    fn commit(&self, _: &Commitment, _: &Opening) -> (Commitment, Opening) {
+        let mask = Opening { value: random_scalar(), blinding: random_scalar() };
+        (self.params.commit(&mask), mask)
+    }
+
+    fn respond(&self, _: &Commitment, opening: &Opening, mask: Opening, c: Fr) -> Opening {
+        mask + *opening * c
+    }
+
+    /// The response opens T + c C, by the homomorphism
+    fn verify(&self, statement: &Commitment, t: &Commitment, c: Fr, z: &Opening) -> bool {
+        self.params.verify(&(*t + *statement * c), z)
+    }
+
+    fn simulate(&self, statement: &Commitment, c: Fr) -> (Commitment, Opening) {
+        let z = Opening { value: random_scalar(), blinding: random_scalar() };
+        (self.params.commit(&z) - *statement * c, z)
+    }
+
+    fn extract(&self, _: &Commitment, first: (Fr, &Opening), second: (Fr, &Opening)) -> Option<Opening> {
+        Some((*first.1 - *second.1) * (first.0 - second.0).inverse()?)
+    }
+}
+
+/// X = x G for the default generator, handy for building statements
+pub fn public_key(x: Fr) -> G1Affine {
+    (G1Projective::generator() * x).into_affine()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::fmt::Debug;
+
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+    use super::*;
+    use crate::sigma::protocol::{FiatShamir, ProofOf};
+    use crate::transcript::Transcript;
+
+    /// Completeness, simulation, extraction and Fiat-Shamir for one protocol
+    pub(crate) fn check<P>(protocol: P, statement: P::Statement, witness: P::Witness, false_statement: P::Statement)
+    where
+        P: SigmaProtocol + Clone,
+        P::Witness: PartialEq + Debug,
+    {
+        let (commitment, state) = protocol.commit(&statement, &witness);
+        let c = protocol.challenge();
+        let z = protocol.respond(&statement, &witness, state, c);
+        assert!(protocol.verify(&statement, &commitment, c, &z));
+        assert!(!protocol.verify(&statement, &commitment, c + Fr::ONE, &z));
+        assert!(!protocol.verify(&false_statement, &commitment, c, &z));
+
+        let (sim_commitment, sim_z) = protocol.simulate(&statement, c);
+        assert!(protocol.verify(&statement, &sim_commitment, c, &sim_z));
+
+        // Rewind: the same commitment answered twice gives the witness away
+        let (_, state) = protocol.commit(&statement, &witness);
+        let (c1, c2) = (protocol.challenge(), protocol.challenge());
+        let z1 = protocol.respond(&statement, &witness, state.clone(), c1);
+        let z2 = protocol.respond(&statement, &witness, state, c2);
+        assert_eq!(protocol.extract(&statement, (c1, &z1), (c2, &z2)).as_ref(), Some(&witness));
+        assert!(protocol.extract(&statement, (c1, &z1), (c1, &z1)).is_none());
+
+        let fs = FiatShamir(protocol);
+        let proof = fs.prove(&mut Transcript::new(b"test"), &statement, &witness);
+        assert!(fs.verify(&mut Transcript::new(b"test"), &statement, &proof));
+        assert!(!fs.verify(&mut Transcript::new(b"other context"), &statement, &proof));
+        assert!(!fs.verify(&mut Transcript::new(b"test"), &false_statement, &proof));
+
+        // Proofs round-trip through their canonical encoding
+        let mut bytes = Vec::new();
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let decoded = ProofOf::<P>::deserialize_compressed(&bytes[..]).unwrap();
+        assert!(fs.verify(&mut Transcript::new(b"test"), &statement, &decoded));
+    }
+
+    #[test]
+    fn test_schnorr() {
+        let x = random_scalar();
+        check(Schnorr::default(), public_key(x), x, public_key(x + Fr::ONE));
+    }
+
+    #[test]
+    fn test_chaum_pedersen() {
+        let protocol = ChaumPedersen::default();
+        let x = random_scalar();
+        let statement = |x: Fr, y: Fr| ((protocol.g * x).into_affine(), (protocol.h * y).into_affine());
+        check(protocol, statement(x, x), x, statement(x, x + Fr::ONE));
+
+        // Knowing both logs is not enough when they differ: no honest proof verifies
+        let bad = statement(x, x + Fr::ONE);
+        let proof = FiatShamir(protocol).prove(&mut Transcript::new(b"dleq"), &bad, &x);
+        assert!(!FiatShamir(protocol).verify(&mut Transcript::new(b"dleq"), &bad, &proof));
+    }
+
+    #[test]
+    fn test_okamoto() {
+        let protocol = Okamoto::default();
+        let (a, b) = (random_scalar(), random_scalar());
+        let x = (protocol.g * a + protocol.h * b).into_affine();
+        let wrong = (protocol.g * b + protocol.h * a).into_affine();
+        check(protocol, x, (a, b), wrong);
+    }
+
+    #[test]
+    fn test_pedersen_opening() {
+        let protocol = PedersenOpening::default();
+        let (c, opening) = protocol.params.commit_random(Fr::from(42u64));
+        let (other, _) = protocol.params.commit_random(Fr::from(42u64));
+        check(protocol, c, opening, other);
+    }
+}